@@ -1,8 +1,11 @@
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use std::fs;
 use std::path::PathBuf;
-use crate::services::{AIConfig, SummaryStyle, generate_summary, generate_summary_custom, test_connection};
-use crate::database::update_history_summary;
+use crate::services::{AIConfig, SummaryStyle, validate_base_url, validate_sampling, answer_question, generate_chapter_markers, generate_summary, generate_summary_custom, generate_summary_stream, generate_translation, test_connection, TranslationResult};
+use crate::types::Chapter;
+use super::config::load_download_config;
+use super::video::parse_subtitle_file_with_timestamps;
+use crate::database::{add_log_internal, get_history_entry_from_db, get_history_transcript, update_history_summary, update_history_transcript};
 
 /// Get the AI config file path
 fn get_config_path(app: &AppHandle) -> Result<PathBuf, String> {
@@ -13,7 +16,12 @@ fn get_config_path(app: &AppHandle) -> Result<PathBuf, String> {
 
 /// Save AI configuration
 #[tauri::command]
-pub async fn save_ai_config(app: AppHandle, config: AIConfig) -> Result<(), String> {
+pub async fn save_ai_config(app: AppHandle, mut config: AIConfig) -> Result<(), String> {
+    config.base_url = match config.base_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(base_url) => Some(validate_base_url(base_url)?),
+        None => None,
+    };
+    validate_sampling(config.temperature, config.max_tokens)?;
     let path = get_config_path(&app)?;
     let json = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
@@ -64,14 +72,89 @@ pub async fn generate_video_summary(
         .await
         .map_err(|e| e.to_string())?;
     
-    // If history_id is provided, save summary to database
+    // If history_id is provided, save summary (and its transcript, for regenerate_summary) to database
     if let Some(id) = history_id {
-        update_history_summary(id, result.summary.clone())?;
+        update_history_summary(id.clone(), result.summary.clone(), Some(result.provider), Some(result.model))?;
+        update_history_transcript(id, transcript)?;
+    }
+    
+    Ok(result.summary)
+}
+
+/// Partial summary text emitted as `summary-chunk` while streaming
+#[derive(Clone, serde::Serialize)]
+pub struct SummaryChunk {
+    pub history_id: Option<String>,
+    pub text: String,
+}
+
+/// Final summary emitted as `summary-done` once streaming completes
+#[derive(Clone, serde::Serialize)]
+pub struct SummaryDone {
+    pub history_id: Option<String>,
+    pub summary: String,
+}
+
+/// Generate summary for a video transcript, streaming partial text via `summary-chunk` events
+/// and emitting `summary-done` with the full summary when finished
+#[tauri::command]
+pub async fn generate_video_summary_stream(
+    app: AppHandle,
+    transcript: String,
+    history_id: Option<String>,
+    title: Option<String>,
+) -> Result<String, String> {
+    let config = get_ai_config(app.clone()).await?;
+    
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    
+    let result = generate_summary_stream(&config, &transcript, title.as_deref(), |text| {
+        app.emit("summary-chunk", SummaryChunk {
+            history_id: history_id.clone(),
+            text: text.to_string(),
+        }).ok();
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    
+    if let Some(id) = &history_id {
+        update_history_summary(id.clone(), result.summary.clone(), Some(result.provider.clone()), Some(result.model.clone()))?;
+        update_history_transcript(id.clone(), transcript)?;
     }
     
+    app.emit("summary-done", SummaryDone {
+        history_id,
+        summary: result.summary.clone(),
+    }).ok();
+    
     Ok(result.summary)
 }
 
+/// Translate a transcript into the target language using the configured AI provider
+#[tauri::command]
+pub async fn translate_transcript(
+    app: AppHandle,
+    transcript: String,
+    target_lang: String,
+) -> Result<TranslationResult, String> {
+    let config = get_ai_config(app.clone()).await?;
+    
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    
+    let target_lang = target_lang.trim();
+    if target_lang.is_empty() || target_lang == "auto" {
+        return Err("Please choose a target language for the translation.".to_string());
+    }
+    
+    generate_translation(&config, &transcript, target_lang)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Generate summary with custom style and language options
 #[tauri::command]
 pub async fn generate_summary_with_options(
@@ -101,12 +184,140 @@ pub async fn generate_summary_with_options(
     
     Ok(SummaryResult {
         summary: result.summary,
+        provider: result.provider,
+        model: result.model,
     })
 }
 
+/// Regenerate a stored summary with another style or language. Uses the transcript saved
+/// with the previous summary, re-fetching it only for entries summarized before transcripts
+/// were stored, then replaces the summary in history. A re-fetch uses the saved cookie settings.
+#[tauri::command]
+pub async fn regenerate_summary(
+    app: AppHandle,
+    history_id: String,
+    style: SummaryStyle,
+    language: String,
+    proxy_url: Option<String>,
+) -> Result<SummaryResult, String> {
+    let config = get_ai_config(app.clone()).await?;
+    
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    
+    let entry = get_history_entry_from_db(&history_id)?
+        .ok_or_else(|| "History entry not found".to_string())?;
+    
+    let download_config = load_download_config(&app);
+    let transcript = super::video::get_or_fetch_transcript(
+        app.clone(),
+        history_id.clone(),
+        entry.url.clone(),
+        None,
+        None,
+        download_config.cookie_mode,
+        download_config.cookie_browser,
+        download_config.cookie_browser_profile,
+        download_config.cookie_file_path,
+        proxy_url,
+    ).await?;
+    
+    let result = generate_summary_custom(&config, &transcript, &style, &language, Some(&entry.title))
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    update_history_summary(history_id, result.summary.clone(), Some(result.provider.clone()), Some(result.model.clone()))?;
+    
+    Ok(SummaryResult {
+        summary: result.summary,
+        provider: result.provider,
+        model: result.model,
+    })
+}
+
+/// Generate chapter markers ("0:00 Intro", "3:42 Setup", ...) from a timestamped
+/// transcript, given as the raw VTT or SRT subtitle content
+#[tauri::command]
+pub async fn generate_chapters(
+    app: AppHandle,
+    transcript_with_timestamps: String,
+    url: String,
+) -> Result<Vec<Chapter>, String> {
+    let config = get_ai_config(app.clone()).await?;
+    
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    
+    let cues = parse_subtitle_file_with_timestamps(&transcript_with_timestamps);
+    if cues.is_empty() {
+        return Err("No timestamps found in the transcript. Provide VTT or SRT subtitles.".to_string());
+    }
+    
+    add_log_internal("info", "Generating chapters with AI", None, Some(&url)).ok();
+    let chapters = generate_chapter_markers(&config, &cues)
+        .await
+        .map_err(|e| e.to_string())?;
+    add_log_internal("success", &format!("Generated {} chapters", chapters.len()), None, Some(&url)).ok();
+    
+    Ok(chapters)
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct SummaryResult {
     pub summary: String,
+    pub provider: String,
+    pub model: String,
+}
+
+/// Answer to a question asked with `ask_about_video`
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct QaAnswer {
+    pub question: String,
+    pub answer: String,
+    pub provider: String,
+    pub model: String,
+}
+
+/// Ask a free-form question about a video. When `history_id` is given, the entry's title is
+/// used as context and its stored transcript is used if `transcript` is empty.
+#[tauri::command]
+pub async fn ask_about_video(
+    app: AppHandle,
+    transcript: String,
+    question: String,
+    history_id: Option<String>,
+) -> Result<QaAnswer, String> {
+    let config = get_ai_config(app.clone()).await?;
+    
+    if !config.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".to_string());
+    }
+    
+    if question.trim().is_empty() {
+        return Err("Question is empty".to_string());
+    }
+    
+    let entry = match &history_id {
+        Some(id) => get_history_entry_from_db(id)?,
+        None => None,
+    };
+    let transcript = match &history_id {
+        Some(id) if transcript.trim().is_empty() => get_history_transcript(id)?.unwrap_or_default(),
+        _ => transcript,
+    };
+    
+    let result = answer_question(&config, &transcript, &question, entry.as_ref().map(|e| e.title.as_str()))
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    Ok(QaAnswer {
+        question,
+        answer: result.summary,
+        provider: result.provider,
+        model: result.model,
+    })
 }
 
 /// Get available AI models for a provider
@@ -152,6 +363,24 @@ pub fn get_ai_models(provider: String) -> Vec<ModelOption> {
             ModelOption { value: "qwen-max".to_string(), label: "Qwen Max".to_string() },
             ModelOption { value: "qwen-long".to_string(), label: "Qwen Long".to_string() },
         ],
+        "anthropic" => vec![
+            ModelOption { value: "claude-3-5-sonnet-latest".to_string(), label: "Claude 3.5 Sonnet (Recommended)".to_string() },
+            ModelOption { value: "claude-3-5-haiku-latest".to_string(), label: "Claude 3.5 Haiku".to_string() },
+            ModelOption { value: "claude-3-opus-latest".to_string(), label: "Claude 3 Opus".to_string() },
+        ],
+        "groq" => vec![
+            ModelOption { value: "llama-3.3-70b-versatile".to_string(), label: "Llama 3.3 70B (Recommended)".to_string() },
+            ModelOption { value: "llama-3.1-8b-instant".to_string(), label: "Llama 3.1 8B Instant".to_string() },
+            ModelOption { value: "gemma2-9b-it".to_string(), label: "Gemma 2 9B".to_string() },
+            ModelOption { value: "mixtral-8x7b-32768".to_string(), label: "Mixtral 8x7B".to_string() },
+        ],
+        "openrouter" => vec![
+            ModelOption { value: "openai/gpt-4o-mini".to_string(), label: "GPT-4o Mini (Recommended)".to_string() },
+            ModelOption { value: "anthropic/claude-3.5-sonnet".to_string(), label: "Claude 3.5 Sonnet".to_string() },
+            ModelOption { value: "google/gemini-2.0-flash-001".to_string(), label: "Gemini 2.0 Flash".to_string() },
+            ModelOption { value: "meta-llama/llama-3.3-70b-instruct".to_string(), label: "Llama 3.3 70B".to_string() },
+            ModelOption { value: "deepseek/deepseek-chat".to_string(), label: "DeepSeek Chat".to_string() },
+        ],
         "proxy" => vec![
             ModelOption { value: "gpt-4.1-nano".to_string(), label: "GPT-4.1 Nano".to_string() },
             ModelOption { value: "gpt-4.1-mini".to_string(), label: "GPT-4.1 Mini".to_string() },
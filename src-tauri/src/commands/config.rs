@@ -0,0 +1,263 @@
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use crate::types::{DownloadConfig, DownloadProfile, NetworkConfig};
+use crate::services::{network_config, set_network_config, CookieSource};
+use crate::utils::{validate_rate_limit, DownloadFormat, Quality};
+
+/// Get download config file path
+fn get_download_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("download_config.json"))
+}
+
+/// Load download defaults, falling back to defaults if missing or unreadable
+pub fn load_download_config(app: &AppHandle) -> DownloadConfig {
+    get_download_config_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save download defaults
+#[tauri::command]
+pub async fn save_download_config(app: AppHandle, mut config: DownloadConfig) -> Result<(), String> {
+    // Reject invalid cookie defaults before they are persisted
+    CookieSource::from_settings(
+        config.cookie_mode.as_deref(),
+        config.cookie_browser.as_deref(),
+        config.cookie_browser_profile.as_deref(),
+        config.cookie_file_path.as_deref(),
+    )?;
+    if let Some(ref rate_limit) = config.rate_limit {
+        if !rate_limit.is_empty() {
+            validate_rate_limit(rate_limit)?;
+        }
+    }
+    config.preferred_langs = normalize_lang_list(&config.preferred_langs)?;
+    
+    let path = get_download_config_path(&app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    Ok(())
+}
+
+/// Load download defaults
+#[tauri::command]
+pub async fn get_download_config(app: AppHandle) -> Result<DownloadConfig, String> {
+    let path = get_download_config_path(&app)?;
+    
+    if !path.exists() {
+        return Ok(DownloadConfig::default());
+    }
+    
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+    
+    let config: DownloadConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+    
+    Ok(config)
+}
+
+/// Trim and de-duplicate a subtitle language list, keeping its order.
+/// Codes are passed to `--sub-langs` one at a time, so each must be a plain code like "en" or "pt-BR"
+pub fn normalize_lang_list(langs: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for lang in langs.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("Invalid language code: {}", lang));
+        }
+        if !normalized.iter().any(|l| l.eq_ignore_ascii_case(lang)) {
+            normalized.push(lang.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Upper bound for the socket timeout, in seconds
+const MAX_SOCKET_TIMEOUT_SECS: u32 = 600;
+/// Upper bound for request and fragment retries
+const MAX_NETWORK_RETRIES: u32 = 100;
+
+fn get_network_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("network_config.json"))
+}
+
+/// Load the saved network settings into the yt-dlp service; called once at startup.
+/// A missing or unreadable file keeps the defaults
+pub fn init_network_config(app: &AppHandle) {
+    let config = get_network_config_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<NetworkConfig>(&content).ok())
+        .filter(|config| validate_network_config(config).is_ok());
+    if let Some(config) = config {
+        set_network_config(config);
+    }
+}
+
+fn validate_network_config(config: &NetworkConfig) -> Result<(), String> {
+    if config.socket_timeout == 0 || config.socket_timeout > MAX_SOCKET_TIMEOUT_SECS {
+        return Err(format!("Socket timeout must be between 1 and {} seconds", MAX_SOCKET_TIMEOUT_SECS));
+    }
+    if config.retries > MAX_NETWORK_RETRIES || config.fragment_retries > MAX_NETWORK_RETRIES {
+        return Err(format!("Retries must be between 0 and {}", MAX_NETWORK_RETRIES));
+    }
+    Ok(())
+}
+
+/// Save network settings and apply them to every following yt-dlp run
+#[tauri::command]
+pub async fn save_network_config(app: AppHandle, config: NetworkConfig) -> Result<(), String> {
+    validate_network_config(&config)?;
+    
+    let path = get_network_config_path(&app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+    
+    set_network_config(config);
+    Ok(())
+}
+
+/// Network settings currently in effect
+#[tauri::command]
+pub fn get_network_config() -> NetworkConfig {
+    network_config()
+}
+
+/// Resolve cookie settings for a command, using saved defaults when none are passed
+pub fn resolve_cookie_source(
+    app: &AppHandle,
+    cookie_mode: Option<&str>,
+    cookie_browser: Option<&str>,
+    cookie_browser_profile: Option<&str>,
+    cookie_file_path: Option<&str>,
+) -> Result<Option<CookieSource>, String> {
+    if cookie_mode.is_some() {
+        return CookieSource::from_settings(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path);
+    }
+    
+    let config = load_download_config(app);
+    CookieSource::from_settings(
+        config.cookie_mode.as_deref(),
+        config.cookie_browser.as_deref(),
+        config.cookie_browser_profile.as_deref(),
+        config.cookie_file_path.as_deref(),
+    )
+}
+
+fn get_profiles_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("download_profiles.json"))
+}
+
+/// Load saved download profiles; an empty list if none were saved yet
+pub fn load_profiles(app: &AppHandle) -> Result<Vec<DownloadProfile>, String> {
+    let path = get_profiles_path(app)?;
+    
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profiles: {}", e))
+}
+
+fn write_profiles(app: &AppHandle, profiles: &[DownloadProfile]) -> Result<(), String> {
+    let path = get_profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(profiles)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+/// Add `profile` to the list, replacing one with the same name (ignoring case)
+fn upsert_profile(profiles: &mut Vec<DownloadProfile>, profile: DownloadProfile) {
+    match profiles.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&profile.name)) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+}
+
+/// Save a download profile, replacing an existing one with the same name
+#[tauri::command]
+pub async fn save_profile(app: AppHandle, mut profile: DownloadProfile) -> Result<(), String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    // Reject options download_video would refuse before they are persisted
+    profile.quality.parse::<Quality>()?;
+    profile.format.parse::<DownloadFormat>()?;
+    if let Some(ref rate_limit) = profile.rate_limit {
+        if !rate_limit.is_empty() {
+            validate_rate_limit(rate_limit)?;
+        }
+    }
+    
+    let mut profiles = load_profiles(&app)?;
+    upsert_profile(&mut profiles, profile);
+    write_profiles(&app, &profiles)
+}
+
+/// Saved download profiles, in the order they were created
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle) -> Result<Vec<DownloadProfile>, String> {
+    load_profiles(&app)
+}
+
+/// Delete the download profile named `name`
+#[tauri::command]
+pub async fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    let mut profiles = load_profiles(&app)?;
+    let count = profiles.len();
+    profiles.retain(|p| !p.name.eq_ignore_ascii_case(name.trim()));
+    if profiles.len() == count {
+        return Err(format!("No profile named \"{}\"", name.trim()));
+    }
+    write_profiles(&app, &profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, quality: &str) -> DownloadProfile {
+        serde_json::from_value(serde_json::json!({ "name": name, "quality": quality, "format": "mp4" })).unwrap()
+    }
+
+    #[test]
+    fn test_upsert_profile() {
+        let mut profiles = vec![profile("Music", "audio"), profile("HD", "1080")];
+        upsert_profile(&mut profiles, profile("hd", "720"));
+        upsert_profile(&mut profiles, profile("4K", "4k"));
+        
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Music", "hd", "4K"]);
+        assert_eq!(profiles[1].quality, "720");
+    }
+}
@@ -1,16 +1,26 @@
 use std::process::Stdio;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 use tokio::process::Command;
-use crate::types::{YtdlpVersionInfo, FfmpegStatus, BunStatus};
+use crate::types::{YtdlpVersionInfo, FfmpegStatus, BunStatus, HealthCheck, ToolVersions};
 use crate::services::{
     get_ytdlp_version_internal, get_ytdlp_download_info, verify_sha256,
     check_ffmpeg_internal, get_ffmpeg_download_info, parse_ffmpeg_version,
-    get_ffmpeg_path, check_ffmpeg_update_internal, FfmpegUpdateInfo,
+    get_ffmpeg_path, is_ffmpeg_available, check_ffmpeg_update_internal, FfmpegUpdateInfo,
     check_bun_internal, get_bun_download_url, check_bun_update_internal, BunUpdateInfo,
+    run_ytdlp_json_with_retry, run_ytdlp_with_stderr, test_connection,
+    clear_ytdlp_outdated, is_ytdlp_outdated, network_args, get_supported_sites_internal, clear_supported_sites_cache,
 };
 use crate::utils::{extract_tar_gz, extract_tar_xz, extract_zip, extract_bun_from_zip};
 
+/// How long a `--version` probe may take before the tool is reported as unavailable
+const VERSION_PROBE_TIMEOUT_SECS: u64 = 5;
+/// Time limit for the health checks that go over the network
+const NETWORK_CHECK_TIMEOUT_SECS: u64 = 30;
+/// Short, long-lived public video used to check that extraction works end to end
+const HEALTHCHECK_TEST_URL: &str = "https://www.youtube.com/watch?v=jNQXAC9IVRw";
+
 #[derive(Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -160,9 +170,127 @@ pub async fn update_ytdlp(app: AppHandle) -> Result<String, String> {
         .await
         .map_err(|e| format!("Failed to verify update: {}", e))?;
     
+    clear_ytdlp_outdated();
+    // A new version may add or fix extractors
+    clear_supported_sites_cache(&app);
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Whether yt-dlp has warned that it is out of date since the last update,
+/// so the UI can offer the `update_ytdlp` flow
+#[tauri::command]
+pub fn ytdlp_needs_update() -> bool {
+    is_ytdlp_outdated()
+}
+
+/// Extractor names yt-dlp supports, cached under app data for a week.
+/// Pass `refresh` to re-run `yt-dlp --list-extractors` right away
+#[tauri::command]
+pub async fn get_supported_sites(app: AppHandle, refresh: Option<bool>) -> Result<Vec<String>, String> {
+    get_supported_sites_internal(&app, refresh.unwrap_or(false)).await
+}
+
+/// Report which yt-dlp and FFmpeg binaries are in use and their versions.
+/// Each probe is time-limited so a hung binary can't block the caller.
+#[tauri::command]
+pub async fn get_tool_versions(app: AppHandle) -> Result<ToolVersions, String> {
+    let probe_timeout = Duration::from_secs(VERSION_PROBE_TIMEOUT_SECS);
+    
+    let (ytdlp_version, ytdlp_source) = match tokio::time::timeout(probe_timeout, get_ytdlp_version_internal(&app)).await {
+        Ok(Ok(info)) if !info.version.is_empty() => {
+            // Updates are saved to app_data/bin, which is still an app-managed binary
+            let app_managed = info.is_bundled || app.path().app_data_dir()
+                .map(|dir| std::path::Path::new(&info.binary_path).starts_with(dir))
+                .unwrap_or(false);
+            let source = if app_managed { "bundled" } else { "system" };
+            (Some(info.version), Some(source.to_string()))
+        }
+        _ => (None, None),
+    };
+    
+    let (ffmpeg_version, ffmpeg_source) = match tokio::time::timeout(probe_timeout, check_ffmpeg_internal(&app)).await {
+        Ok(Ok(status)) if status.installed => {
+            let source = if status.is_system { "system" } else { "bundled" };
+            (status.version, Some(source.to_string()))
+        }
+        _ => (None, None),
+    };
+    
+    Ok(ToolVersions {
+        ytdlp_version,
+        ffmpeg_version,
+        ytdlp_source,
+        ffmpeg_source,
+    })
+}
+
+/// Run first-run diagnostics concurrently: yt-dlp spawns, FFmpeg is present,
+/// a known-good video can be extracted and the configured AI provider answers.
+#[tauri::command]
+pub async fn run_healthcheck(app: AppHandle) -> Result<Vec<HealthCheck>, String> {
+    let probe_timeout = Duration::from_secs(VERSION_PROBE_TIMEOUT_SECS);
+    let network_timeout = Duration::from_secs(NETWORK_CHECK_TIMEOUT_SECS);
+    
+    let ytdlp_check = async {
+        match tokio::time::timeout(probe_timeout, run_ytdlp_with_stderr(&app, &["--version"])).await {
+            Ok(Ok(output)) if output.success => Ok(format!("yt-dlp {}", output.stdout.trim())),
+            Ok(Ok(output)) => Err(output.stderr.trim().to_string()),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("yt-dlp did not respond in time".to_string()),
+        }
+    };
+    
+    let ffmpeg_check = async {
+        if !is_ffmpeg_available(&app).await {
+            return Err("FFmpeg is not installed".to_string());
+        }
+        match tokio::time::timeout(probe_timeout, check_ffmpeg_internal(&app)).await {
+            Ok(Ok(status)) if status.installed => Ok(status.version
+                .map(|version| format!("FFmpeg {}", version))
+                .unwrap_or_else(|| "FFmpeg installed".to_string())),
+            Ok(Ok(_)) => Err("FFmpeg is not installed".to_string()),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("FFmpeg did not respond in time".to_string()),
+        }
+    };
+    
+    let network_check = async {
+        // A single attempt: retries would only delay reporting a real outage
+        let mut args = vec!["--dump-json".to_string(), "--no-download".to_string(), "--no-playlist".to_string(), "--no-warnings".to_string()];
+        args.extend(network_args());
+        args.push(HEALTHCHECK_TEST_URL.to_string());
+        let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        match tokio::time::timeout(network_timeout, run_ytdlp_json_with_retry(&app, &args_ref, 1)).await {
+            Ok(Ok(_)) => Ok("Fetched test video info".to_string()),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("Timed out fetching test video info".to_string()),
+        }
+    };
+    
+    let ai_check = async {
+        let config = super::ai::get_ai_config(app.clone()).await?;
+        if !config.enabled {
+            return Ok("AI features are disabled".to_string());
+        }
+        match tokio::time::timeout(network_timeout, test_connection(&config)).await {
+            Ok(Ok(message)) => Ok(message),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("AI provider did not respond in time".to_string()),
+        }
+    };
+    
+    let (ytdlp, ffmpeg, network, ai) = tokio::join!(ytdlp_check, ffmpeg_check, network_check, ai_check);
+    
+    Ok([("ytdlp", ytdlp), ("ffmpeg", ffmpeg), ("network", network), ("ai", ai)]
+        .into_iter()
+        .map(|(name, result)| HealthCheck {
+            name: name.to_string(),
+            ok: result.is_ok(),
+            detail: result.unwrap_or_else(|e| e),
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn check_ffmpeg(app: AppHandle) -> Result<FfmpegStatus, String> {
     check_ffmpeg_internal(&app).await
@@ -6,25 +6,461 @@
 //! - Progress tracking
 //! - Subtitle handling
 
+use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex, Weak};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_shell::ShellExt;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::process::CommandEvent;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::Semaphore;
 
-use crate::types::DownloadProgress;
+use crate::types::{DownloadError, DownloadJob, DownloadJobOutcome, DownloadProgress, DownloadResult, DownloadSummaryResult, FailedDownload, FfmpegRequired, FilenamePreset, HistoryEntry, ImportedUrls, PlannedDownload, PlaylistOrder, PlaylistSizeEstimate, SponsorBlockMode, SubtitleInfo, SummaryReady, YtdlpWarning};
 use crate::database::add_log_internal;
-use crate::database::add_history_internal;
-use crate::database::update_history_download;
-use crate::utils::{build_format_string, parse_progress, format_size, sanitize_output_path};
-use crate::services::{get_ffmpeg_path, get_bun_path, get_ytdlp_path};
+use crate::database::{delete_download_job, delete_history_from_db, get_failed_download_job, get_failed_downloads_from_db, get_history_entry_from_db, insert_history, mark_download_job_failed, save_download_job, update_history_download, update_history_summary};
+use crate::utils::{apply_filesize_limit, build_format_string, build_separate_format_string, relaxed_format_strings, detect_postprocess_phase, parse_live_progress, parse_progress, parse_stream_size, parse_timestamp, PLAYLIST_ITEM_RE, format_size, is_youtube_url, move_dir_contents, normalize_url, sanitize_output_path, validate_max_filesize, validate_rate_limit, validate_thumbnail_format, DownloadFormat, Quality};
+use crate::services::{get_ffmpeg_path, get_bun_path, get_ytdlp_path, build_proxy_args, classify_download_failure, generate_summary, get_cached_info, info_cache_key, network_args, parse_ytdlp_error, report_ytdlp_warnings, run_ytdlp_with_stderr_and_options, spawn_ytdlp_sidecar, validate_proxy_url, YtdlpOptions};
+use super::config::{load_download_config, load_profiles, resolve_cookie_source};
+use super::ai::get_ai_config;
+use super::video::{get_or_fetch_transcript, get_video_info, list_subtitles};
 
 pub static CANCEL_FLAG: AtomicBool = AtomicBool::new(false);
 
-/// Kill all yt-dlp and ffmpeg processes
+/// Cancellation tokens of running batches. Queued batch jobs check their token
+/// instead of CANCEL_FLAG, which every newly started download clears
+static BATCH_CANCELS: LazyLock<Mutex<Vec<Weak<AtomicBool>>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Cancel every running batch, dropping tokens whose batch has finished
+fn cancel_batches() {
+    if let Ok(mut batches) = BATCH_CANCELS.lock() {
+        batches.retain(|token| match token.upgrade() {
+            Some(token) => {
+                token.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// Process ids of live recordings in progress. Cancelling another download must not
+/// kill these or their ffmpeg children, or the recordings could not finish muxing
+static LIVE_RECORDINGS: LazyLock<Mutex<HashSet<u32>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Destination files of in-flight downloads, keyed by download id. Only files yt-dlp
+/// reported writing in this session are listed, so cleanup never touches anything else.
+static ACTIVE_DESTINATIONS: LazyLock<Mutex<HashMap<String, ActiveDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Default)]
+struct ActiveDownload {
+    destinations: Vec<PathBuf>,
+    /// keep_separate download: its ".fNNN" streams are finished output, not merge leftovers
+    keep_streams: bool,
+}
+
+/// Tracks a download in ACTIVE_DESTINATIONS until it returns
+struct ActiveDownloadGuard(String);
+
+impl ActiveDownloadGuard {
+    fn new(id: &str, keep_streams: bool) -> Self {
+        if let Ok(mut active) = ACTIVE_DESTINATIONS.lock() {
+            active.entry(id.to_string()).or_default().keep_streams = keep_streams;
+        }
+        ActiveDownloadGuard(id.to_string())
+    }
+}
+
+impl Drop for ActiveDownloadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_DESTINATIONS.lock() {
+            active.remove(&self.0);
+        }
+        if let Ok(mut requests) = PAUSE_REQUESTS.lock() {
+            requests.remove(&self.0);
+        }
+    }
+}
+
+/// Ids of running downloads asked to pause; the download stops within PAUSE_CHECK_INTERVAL
+static PAUSE_REQUESTS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// How often a download checks for Stop and Pause requests while yt-dlp prints nothing
+const PAUSE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// How long a paused yt-dlp gets to exit after Ctrl+C before it is killed
+const PAUSE_EXIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Paused downloads, keyed by download id, waiting for `resume_download`
+static PAUSED_DOWNLOADS: LazyLock<Mutex<HashMap<String, PausedDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Everything needed to re-run a download's yt-dlp command and finish it
+struct PausedDownload {
+    args: Vec<String>,
+    quality: String,
+    format: String,
+    url: String,
+    should_log_stderr: bool,
+    history_id: Option<String>,
+    title: Option<String>,
+    notes: Vec<String>,
+    write_info_json: bool,
+    write_description: bool,
+    keep_separate: bool,
+    /// Kept alive so the partial files in it survive the pause
+    temp_dir: Option<TempDirGuard>,
+}
+
+/// Whether download `id` was asked to pause; clears the request
+fn take_pause_request(id: &str) -> bool {
+    PAUSE_REQUESTS.lock().map(|mut requests| requests.remove(id)).unwrap_or(false)
+}
+
+/// Drop every paused download, removing its temp directory and its jobs row
+fn drop_paused_downloads() {
+    let ids: Vec<String> = PAUSED_DOWNLOADS
+        .lock()
+        .map(|mut paused| paused.drain().map(|(id, _)| id).collect())
+        .unwrap_or_default();
+    for id in ids {
+        delete_download_job(&id).ok();
+    }
+}
+
+/// Wait until a stopped sidecar process has exited, so its partial files are complete
+async fn wait_for_sidecar_exit(rx: &mut tauri::async_runtime::Receiver<CommandEvent>) {
+    while let Some(event) = rx.recv().await {
+        if let CommandEvent::Terminated(_) = event {
+            break;
+        }
+    }
+}
+
+/// Whole-playlist completion: items before `playlist_index` (1-based, in download order) are done,
+/// downloaded or skipped, and the current item adds its share. None outside playlists
+fn overall_percent(playlist_index: Option<u32>, playlist_count: Option<u32>, item_percent: f64) -> Option<f64> {
+    let count = playlist_count.filter(|&count| count > 0)?;
+    let completed = playlist_index?.saturating_sub(1).min(count);
+    let item_fraction = item_percent.clamp(0.0, 100.0) / 100.0;
+    Some(((completed as f64 + item_fraction) / count as f64 * 100.0).min(100.0))
+}
+
+/// Report a paused download at the progress it had reached
+fn emit_paused(throttle: &ProgressThrottle, id: &str, percent: f64, title: Option<String>, playlist_index: Option<u32>, playlist_count: Option<u32>) {
+    let progress = DownloadProgress {
+        id: id.to_string(),
+        percent,
+        speed: String::new(),
+        eta: String::new(),
+        status: "paused".to_string(),
+        phase: "paused".to_string(),
+        title,
+        playlist_index,
+        playlist_count,
+        overall_percent: overall_percent(playlist_index, playlist_count, percent),
+        filesize: None,
+        resolution: None,
+        format_ext: None,
+        indeterminate: false,
+    };
+    throttle.emit_now(progress);
+}
+
+/// Report a live recording's progress: bytes written so far rather than a percent
+fn update_recording(throttle: &ProgressThrottle, id: &str, downloaded: u64, speed: String, title: Option<String>, playlist_index: Option<u32>, playlist_count: Option<u32>) {
+    let progress = DownloadProgress {
+        id: id.to_string(),
+        percent: 0.0,
+        speed,
+        eta: String::new(),
+        status: "recording".to_string(),
+        phase: "recording".to_string(),
+        title,
+        playlist_index,
+        playlist_count,
+        overall_percent: None,
+        filesize: Some(downloaded),
+        resolution: None,
+        format_ext: None,
+        indeterminate: true,
+    };
+    throttle.update(progress);
+}
+
+/// Minimum time between two `download-progress` events of one download
+const PROGRESS_EMIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Coalesces a download's progress updates: only the latest is kept and a timer sends it
+/// every PROGRESS_EMIT_INTERVAL, so fast downloads don't flood the frontend with events
+struct ProgressThrottle {
+    app: AppHandle,
+    pending: Arc<Mutex<Option<DownloadProgress>>>,
+    flusher: tokio::task::JoinHandle<()>,
+}
+
+impl ProgressThrottle {
+    fn new(app: &AppHandle) -> Self {
+        let pending: Arc<Mutex<Option<DownloadProgress>>> = Arc::new(Mutex::new(None));
+        let flusher = tokio::spawn({
+            let app = app.clone();
+            let pending = pending.clone();
+            async move {
+                let mut interval = tokio::time::interval(PROGRESS_EMIT_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    // Emit while holding the lock so an emit_now can't overtake this update
+                    if let Ok(mut pending) = pending.lock() {
+                        if let Some(progress) = pending.take() {
+                            app.emit("download-progress", progress).ok();
+                        }
+                    }
+                }
+            }
+        });
+        ProgressThrottle { app: app.clone(), pending, flusher }
+    }
+    
+    /// Queue a progress update, replacing one not sent yet
+    fn update(&self, progress: DownloadProgress) {
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = Some(progress);
+        }
+    }
+    
+    /// Send a state change (phase, skipped, finished) right away; queued updates it supersedes are dropped
+    fn emit_now(&self, progress: DownloadProgress) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.take();
+            self.app.emit("download-progress", progress).ok();
+        }
+    }
+}
+
+impl Drop for ProgressThrottle {
+    fn drop(&mut self) {
+        self.flusher.abort();
+    }
+}
+
+/// Record a "[download] Destination:" path for a running download
+fn track_destination(id: &str, line: &str) {
+    let Some((_, path)) = line.split_once("[download] Destination:") else {
+        return;
+    };
+    if let Ok(mut active) = ACTIVE_DESTINATIONS.lock() {
+        if let Some(download) = active.get_mut(id) {
+            download.destinations.push(PathBuf::from(path.trim()));
+        }
+    }
+}
+
+/// Temp directories of `use_temp_dir` downloads, keyed by download id
+static STAGED_DOWNLOADS: LazyLock<Mutex<HashMap<String, StagedDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct StagedDownload {
+    temp_dir: PathBuf,
+    output_dir: PathBuf,
+    /// History entries saved for finished items, forgotten again if the job fails
+    history_ids: Vec<String>,
+}
+
+/// Stages a download in a hidden per-job directory inside the output folder, so moving the
+/// finished files out is a rename on the same filesystem. The directory and everything left in
+/// it is removed when the guard is dropped, and unless committed, so are the history entries
+/// of its finished items
+struct TempDirGuard {
+    id: String,
+    temp_dir: PathBuf,
+    output_dir: PathBuf,
+    /// Set once the files were moved to the output folder
+    committed: bool,
+    /// Set when finished files could not be moved out, so they are not deleted with the directory
+    keep: bool,
+}
+
+impl TempDirGuard {
+    fn new(id: &str, output_dir: &str) -> Result<Self, String> {
+        let output_dir = PathBuf::from(output_dir);
+        let temp_dir = output_dir.join(format!(".youwee_download_{}", id));
+        // Leftovers from an earlier run with the same id would otherwise be moved along
+        std::fs::remove_dir_all(&temp_dir).ok();
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create temp download directory: {}", e))?;
+        if let Ok(mut staged) = STAGED_DOWNLOADS.lock() {
+            staged.insert(id.to_string(), StagedDownload {
+                temp_dir: temp_dir.clone(),
+                output_dir: output_dir.clone(),
+                history_ids: Vec::new(),
+            });
+        }
+        Ok(TempDirGuard {
+            id: id.to_string(),
+            temp_dir,
+            output_dir,
+            committed: false,
+            keep: false,
+        })
+    }
+    
+    /// Move everything downloaded to the output folder; returns where `files` ended up
+    fn commit(&mut self, files: Vec<String>) -> Result<Vec<String>, String> {
+        if let Err(e) = move_dir_contents(&self.temp_dir, &self.output_dir) {
+            self.keep = true;
+            return Err(format!(
+                "Download finished but could not be moved to the output folder: {}. The files were left in {}",
+                e,
+                self.temp_dir.display()
+            ));
+        }
+        self.committed = true;
+        Ok(files
+            .iter()
+            .map(|file| staged_path(file, &self.temp_dir, &self.output_dir))
+            .collect())
+    }
+}
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        let staged = STAGED_DOWNLOADS.lock().ok().and_then(|mut staged| staged.remove(&self.id));
+        // History entries of an uncommitted download point at files that never reached the output folder
+        if !self.committed {
+            for history_id in staged.map(|download| download.history_ids).unwrap_or_default() {
+                delete_history_from_db(history_id).ok();
+            }
+        }
+        if !self.keep {
+            std::fs::remove_dir_all(&self.temp_dir).ok();
+        }
+    }
+}
+
+/// Where a file written under `temp_dir` ends up in `output_dir`; other paths are returned as is
+fn staged_path(path: &str, temp_dir: &Path, output_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(temp_dir) {
+        Ok(relative) => output_dir.join(relative).to_string_lossy().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Path to record in history for a finished file: its final location when download `id` is
+/// staged in a temp directory. `entry_id` is remembered so a failed job can forget it again
+fn staged_history_path(id: &str, filepath: &str, entry_id: Option<&str>) -> String {
+    let Ok(mut staged) = STAGED_DOWNLOADS.lock() else {
+        return filepath.to_string();
+    };
+    let Some(download) = staged.get_mut(id) else {
+        return filepath.to_string();
+    };
+    if let Some(entry_id) = entry_id {
+        download.history_ids.push(entry_id.to_string());
+    }
+    staged_path(filepath, &download.temp_dir, &download.output_dir)
+}
+
+/// Seconds between checks while waiting for a scheduled premiere/stream to start
+const LIVE_WAIT_INTERVAL_SECS: u32 = 30;
+
+/// Lists a live recording's yt-dlp process in LIVE_RECORDINGS for as long as it runs
+struct LiveRecordingGuard(u32);
+
+impl LiveRecordingGuard {
+    fn new(pid: u32) -> Self {
+        if let Ok(mut recordings) = LIVE_RECORDINGS.lock() {
+            recordings.insert(pid);
+        }
+        LiveRecordingGuard(pid)
+    }
+}
+
+impl Drop for LiveRecordingGuard {
+    fn drop(&mut self) {
+        if let Ok(mut recordings) = LIVE_RECORDINGS.lock() {
+            recordings.remove(&self.0);
+        }
+    }
+}
+
+/// Ask yt-dlp to stop a live recording the way Ctrl+C would, so it muxes what it has.
+/// Returns false when that isn't possible and the process should be killed instead.
+fn interrupt_recording(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        std::process::Command::new("kill")
+            .args(["-INT", &pid.to_string()])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+/// Kill all yt-dlp and ffmpeg processes except live recordings and their children;
+/// each recording stops its own process so it can finish muxing
 fn kill_all_download_processes() {
+    let recordings: Vec<u32> = LIVE_RECORDINGS
+        .lock()
+        .map(|recordings| recordings.iter().copied().collect())
+        .unwrap_or_default();
+    if recordings.is_empty() {
+        force_kill_download_processes();
+        return;
+    }
+    
+    #[cfg(unix)]
+    {
+        use std::process::Command as StdCommand;
+        let mut spared = recordings;
+        let mut next = 0;
+        while next < spared.len() {
+            let children = process_ids(StdCommand::new("pgrep").args(["-P", &spared[next].to_string()]));
+            spared.extend(children);
+            next += 1;
+        }
+        for name in ["yt-dlp", "ffmpeg"] {
+            for pid in process_ids(StdCommand::new("pgrep").args(["-f", name])) {
+                if !spared.contains(&pid) {
+                    StdCommand::new("kill").args(["-9", &pid.to_string()]).spawn().ok();
+                }
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        use std::process::Command as StdCommand;
+        // /T takes each killed yt-dlp's ffmpeg along, so ffmpeg is not killed by name
+        let mut command = StdCommand::new("taskkill");
+        command.args(["/F", "/T", "/IM", "yt-dlp.exe"]);
+        for pid in &recordings {
+            command.args(["/FI", &format!("PID ne {}", pid)]);
+        }
+        command.spawn().ok();
+    }
+}
+
+/// Process ids printed one per line by `command` (pgrep)
+#[cfg(unix)]
+fn process_ids(command: &mut std::process::Command) -> Vec<u32> {
+    command
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Kill all yt-dlp and ffmpeg processes, live recordings included
+fn force_kill_download_processes() {
     #[cfg(unix)]
     {
         use std::process::Command as StdCommand;
@@ -49,7 +485,8 @@ pub async fn download_video(
     format: String,
     download_playlist: bool,
     video_codec: String,
-    audio_bitrate: String,
+    // Audio extraction quality: "auto", a VBR level 0-9 or a bitrate like "192K"
+    audio_bitrate: Option<String>,
     playlist_limit: Option<u32>,
     subtitle_mode: String,
     subtitle_langs: String,
@@ -67,32 +504,317 @@ pub async fn download_video(
     // Embed settings
     embed_metadata: Option<bool>,
     embed_thumbnail: Option<bool>,
+    embed_chapters: Option<bool>,
     // Proxy settings
     proxy_url: Option<String>,
-) -> Result<(), String> {
+    // Time ranges to download, as (start, end) timestamps
+    sections: Option<Vec<(String, String)>>,
+    // SponsorBlock settings
+    sponsorblock: Option<SponsorBlockMode>,
+    // Bandwidth limit, e.g. "2M" (falls back to saved default)
+    rate_limit: Option<String>,
+    // Exact yt-dlp format id (from get_video_info formats)
+    format_id: Option<String>,
+    // Download archive: skip items already recorded in this file
+    archive_file: Option<PathBuf>,
+    // Use a per-playlist archive under app data when no archive_file is given
+    skip_downloaded: Option<bool>,
+    // yt-dlp output template relative to output_path, e.g. "%(uploader)s/%(title)s.%(ext)s"
+    filename_template: Option<String>,
+    // Filename sanitizing: ASCII-only names, Windows-safe characters, max length
+    restrict_filenames: Option<bool>,
+    windows_safe: Option<bool>,
+    max_filename_len: Option<u32>,
+    // Playlist item selection, e.g. "1,3,7-9" (implies download_playlist)
+    playlist_items: Option<String>,
+    // Skip formats larger than this, e.g. "100M"
+    max_filesize: Option<String>,
+    // Record a live stream from its start (waits for scheduled premieres)
+    live: Option<bool>,
+    // Parallel fragment downloads for DASH/HLS streams (1-16, default 1)
+    concurrent_fragments: Option<u32>,
+    // Title already known from get_video_info, reported before yt-dlp prints a destination
+    title: Option<String>,
+    // Save the video and audio streams as two files instead of merging them
+    keep_separate: Option<bool>,
+    // Playlist download order; only valid with download_playlist
+    order: Option<PlaylistOrder>,
+    // Save playlist items in a subfolder named after the playlist (ignored for single videos)
+    create_playlist_folder: Option<bool>,
+    // Metadata files next to the media: "<name>.info.json" and "<name>.description"
+    write_info_json: Option<bool>,
+    write_description: Option<bool>,
+    // Embedded thumbnail format: "jpg" (default), "png" or "webp"
+    thumbnail_format: Option<String>,
+    // Download into a per-job temp directory and move the files to output_path only on success
+    use_temp_dir: Option<bool>,
+) -> Result<DownloadResult, DownloadError> {
+    let job = DownloadJob {
+        id,
+        url,
+        output_path,
+        quality,
+        format,
+        download_playlist,
+        video_codec: Some(video_codec),
+        audio_bitrate,
+        playlist_limit,
+        subtitle_mode: Some(subtitle_mode),
+        subtitle_langs: Some(subtitle_langs),
+        subtitle_embed,
+        subtitle_format: Some(subtitle_format),
+        log_stderr,
+        use_bun_runtime,
+        use_actual_player_js,
+        history_id,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        embed_metadata,
+        embed_thumbnail,
+        embed_chapters,
+        proxy_url,
+        sections,
+        sponsorblock,
+        rate_limit,
+        format_id,
+        archive_file,
+        skip_downloaded,
+        filename_template,
+        restrict_filenames,
+        windows_safe,
+        max_filename_len,
+        playlist_items,
+        max_filesize,
+        live,
+        concurrent_fragments,
+        title,
+        keep_separate,
+        order,
+        create_playlist_folder,
+        write_info_json,
+        write_description,
+        thumbnail_format,
+        use_temp_dir,
+    };
+    download_job(app, job).await
+}
+
+/// Run one download, keeping its parameters in the jobs table so a failure can be retried
+async fn download_job(app: AppHandle, job: DownloadJob) -> Result<DownloadResult, DownloadError> {
     CANCEL_FLAG.store(false, Ordering::SeqCst);
+    tracked_download_job(app, job).await
+}
+
+/// download_job without clearing CANCEL_FLAG, for batch jobs that must not undo a Stop
+async fn tracked_download_job(app: AppHandle, job: DownloadJob) -> Result<DownloadResult, DownloadError> {
+    let id = job.id.clone();
+    save_download_job(&job).ok();
+    let result = execute_download(app, job).await;
+    record_download_job_outcome(&id, &result);
+    result
+}
+
+/// Keep only failed downloads in the jobs table; a paused one stays recorded as running
+fn record_download_job_outcome(id: &str, result: &Result<DownloadResult, DownloadError>) {
+    match result {
+        Err(DownloadError::Paused) => {}
+        Err(DownloadError::Cancelled) | Ok(_) => {
+            delete_download_job(id).ok();
+        }
+        Err(error) => {
+            mark_download_job_failed(id, error).ok();
+        }
+    }
+}
+
+/// Download `job`: build the yt-dlp command from its parameters and run it
+async fn execute_download(app: AppHandle, job: DownloadJob) -> Result<DownloadResult, DownloadError> {
+    let DownloadJob {
+        id,
+        url,
+        output_path,
+        quality,
+        format,
+        download_playlist,
+        video_codec,
+        audio_bitrate,
+        playlist_limit,
+        subtitle_mode,
+        subtitle_langs,
+        subtitle_embed,
+        subtitle_format,
+        log_stderr,
+        use_bun_runtime,
+        use_actual_player_js,
+        history_id,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        embed_metadata,
+        embed_thumbnail,
+        embed_chapters,
+        proxy_url,
+        sections,
+        sponsorblock,
+        rate_limit,
+        format_id,
+        archive_file,
+        skip_downloaded,
+        filename_template,
+        restrict_filenames,
+        windows_safe,
+        max_filename_len,
+        playlist_items,
+        max_filesize,
+        live,
+        concurrent_fragments,
+        title,
+        keep_separate,
+        order,
+        create_playlist_folder,
+        write_info_json,
+        write_description,
+        thumbnail_format,
+        use_temp_dir,
+    } = job;
+    // Same defaults the frontend uses
+    let video_codec = video_codec.unwrap_or_else(|| "auto".to_string());
+    let subtitle_mode = subtitle_mode.unwrap_or_else(|| "off".to_string());
+    let subtitle_langs = subtitle_langs.unwrap_or_default();
+    let subtitle_format = subtitle_format.unwrap_or_else(|| "srt".to_string());
     
+    let url = normalize_url(&url)?.canonical;
+    let output_format: DownloadFormat = format.parse()?;
+    let quality_preset: Quality = quality.parse()?;
+    let _active_guard = ActiveDownloadGuard::new(&id, keep_separate.unwrap_or(false));
     let should_log_stderr = log_stderr.unwrap_or(true);
     let sanitized_path = sanitize_output_path(&output_path)?;
-    let format_string = build_format_string(&quality, &format, &video_codec);
-    let output_template = format!("{}/%(title)s.%(ext)s", sanitized_path);
+    let section_args = build_section_args(sections.as_deref().unwrap_or(&[]))?;
+    let cookie_source = resolve_cookie_source(
+        &app,
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+    )?;
+    let proxy = validate_proxy_url(proxy_url.as_deref())?;
+    let subtitle_options = YtdlpOptions {
+        cookies: cookie_source.clone(),
+        proxy: proxy.clone(),
+    };
+    let playlist_items = match playlist_items {
+        Some(spec) if !spec.trim().is_empty() => Some(validate_playlist_items(&spec)?),
+        _ => None,
+    };
+    let download_playlist = download_playlist || playlist_items.is_some();
+    let order = order.unwrap_or_default();
+    if order != PlaylistOrder::Original && !download_playlist {
+        return Err("Playlist order can only be changed when downloading a playlist".into());
+    }
+    let max_filesize = match max_filesize {
+        Some(size) if !size.trim().is_empty() => Some(validate_max_filesize(&size)?),
+        _ => None,
+    };
+    let concurrent_fragments = concurrent_fragments.map(validate_concurrent_fragments).transpose()?;
+    let audio_quality = validate_audio_bitrate(audio_bitrate.as_deref().unwrap_or("auto"))?;
+    let rate_limit = match rate_limit.or_else(|| load_download_config(&app).rate_limit) {
+        Some(limit) if !limit.trim().is_empty() => Some(validate_rate_limit(&limit)?),
+        _ => None,
+    };
+    let archive_file = match archive_file {
+        Some(path) => Some(path),
+        None if skip_downloaded.unwrap_or(false) => Some(default_archive_path(&app, &url)?),
+        None => None,
+    };
+    let sponsorblock = sponsorblock.unwrap_or_default();
+    let sponsorblock_args = build_sponsorblock_args(&sponsorblock)?;
+    // Extra information for the user, returned joined as `DownloadResult::note`
+    let mut notes: Vec<String> = Vec::new();
+    if let SponsorBlockMode::Remove(_) = sponsorblock {
+        notes.push("Removing SponsorBlock segments re-encodes the affected sections, so the download will take longer".to_string());
+    }
+    // Audio formats
+    let is_audio_format = output_format.is_audio() || quality_preset == Quality::Audio;
+    let keep_separate = keep_separate.unwrap_or(false);
+    if keep_separate && is_audio_format {
+        return Err("Keeping audio and video separate needs a video format (mp4, mkv or webm)".into());
+    }
+    
+    // An explicit format_id from get_video_info takes precedence over quality/codec presets.
+    // keep_separate selects "video,audio" so yt-dlp downloads both streams without merging
+    let format_id = format_id.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    let format_string = match (format_id, keep_separate) {
+        (Some(id), false) => build_format_id_selector(id, is_audio_format || cached_format_is_audio_only(&url, &subtitle_options, id)),
+        (Some(id), true) => build_separate_format_id_selector(id)?,
+        (None, false) => build_format_string(quality_preset, output_format, &video_codec),
+        (None, true) => build_separate_format_string(quality_preset, output_format, &video_codec),
+    };
+    let format_string = match &max_filesize {
+        Some(size) => apply_filesize_limit(&format_string, size),
+        None => format_string,
+    };
+    let merges_streams = !is_audio_format && !keep_separate && format_string.contains('+');
+    let filename_template = match filename_template.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(template) => validate_filename_template(template)?,
+        None => DEFAULT_FILENAME_TEMPLATE.to_string(),
+    };
+    let filename_template = if download_playlist && create_playlist_folder.unwrap_or(false) {
+        playlist_folder_template(&filename_template)
+    } else {
+        filename_template
+    };
+    // Both streams of a video share a title and may share an extension, so tell them apart by format id
+    let filename_template = if keep_separate {
+        separate_stream_template(&filename_template)
+    } else {
+        filename_template
+    };
+    // With a temp directory, failed or cancelled downloads leave nothing behind in output_path
+    let temp_dir = if use_temp_dir.unwrap_or(false) {
+        Some(TempDirGuard::new(&id, &sanitized_path)?)
+    } else {
+        None
+    };
+    let download_dir = match &temp_dir {
+        Some(guard) => guard.temp_dir.to_string_lossy().to_string(),
+        None => sanitized_path.clone(),
+    };
+    let output_template = format!("{}/{}", download_dir, filename_template);
+    // yt-dlp's size estimate only describes the output when it is neither re-encoded nor cut
+    let verify_size = !is_audio_format
+        && section_args.is_empty()
+        && !matches!(sponsorblock, SponsorBlockMode::Remove(_))
+        && !live.unwrap_or(false);
+    let filename_args = build_filename_args(
+        restrict_filenames.unwrap_or(false),
+        windows_safe.unwrap_or(false),
+        max_filename_len,
+    )?;
     
     let mut args = vec![
         "--newline".to_string(),
         "--progress".to_string(),
-        "--no-warnings".to_string(),
         "-f".to_string(),
         format_string,
         "-o".to_string(),
         output_template,
         "--print".to_string(),
-        "after_move:filepath".to_string(),
+        format!("after_move:{}", finished_template(verify_size)),
         "--no-keep-video".to_string(),
         "--no-keep-fragments".to_string(),
     ];
     
+    // Filename sanitizing
+    args.extend(filename_args);
+    
+    // YouTube-specific extractor args; other sites keep yt-dlp's default extraction
+    let is_youtube = is_youtube_url(&url);
+    
     // Add Bun runtime args if enabled
-    if use_bun_runtime.unwrap_or(false) && (url.contains("youtube.com") || url.contains("youtu.be")) {
+    if use_bun_runtime.unwrap_or(false) && is_youtube {
         if let Some(bun_path) = get_bun_path(&app).await {
             args.push("--extractor-args".to_string());
             args.push(format!("youtube:ejs_runtimes=bun;ejs_bun_path={}", bun_path.to_string_lossy()));
@@ -101,16 +823,37 @@ pub async fn download_video(
     
     // Add actual player.js version if enabled (fixes some YouTube download issues)
     // See: https://github.com/yt-dlp/yt-dlp/issues/14680
-    if use_actual_player_js.unwrap_or(false) && (url.contains("youtube.com") || url.contains("youtu.be")) {
+    if use_actual_player_js.unwrap_or(false) && is_youtube {
         args.push("--extractor-args".to_string());
         args.push("youtube:player_js_version=actual".to_string());
     }
     
-    // Add FFmpeg location if available
-    if let Some(ffmpeg_path) = get_ffmpeg_path(&app).await {
-        if let Some(parent) = ffmpeg_path.parent() {
-            args.push("--ffmpeg-location".to_string());
-            args.push(parent.to_string_lossy().to_string());
+    let should_embed_thumbnail = embed_thumbnail.unwrap_or(false);
+    let convert_thumbnail_to = validate_thumbnail_format(thumbnail_format.as_deref())?;
+    // Audio files have no subtitle stream, so embedding only applies to video
+    let embed_subs = subtitle_embed && subtitle_mode != "off" && !is_audio_format;
+    
+    // Add FFmpeg location if available. Without it yt-dlp would only fail once the download
+    // reaches post-processing, so refuse up front when any step needs it
+    match get_ffmpeg_path(&app).await {
+        Some(ffmpeg_path) => {
+            if let Some(parent) = ffmpeg_path.parent() {
+                args.push("--ffmpeg-location".to_string());
+                args.push(parent.to_string_lossy().to_string());
+            }
+        }
+        None => {
+            let step = ffmpeg_required_step(&FfmpegSteps {
+                extract_audio: is_audio_format,
+                merge: merges_streams,
+                embed: should_embed_thumbnail || embed_subs || embed_metadata.unwrap_or(false) || embed_chapters.unwrap_or(false),
+                remove_segments: matches!(sponsorblock, SponsorBlockMode::Remove(_)),
+                clip: !section_args.is_empty(),
+            });
+            if let Some(step) = step {
+                app.emit("ffmpeg-missing", FfmpegRequired { id: id.clone(), step: step.to_string() }).ok();
+                return Err(DownloadError::FfmpegMissing(step.to_string()));
+            }
         }
     }
     
@@ -122,128 +865,370 @@ pub async fn download_video(
             args.push("--sub-langs".to_string());
             args.push("all".to_string());
         } else {
+            if embed_subs {
+                // Fall back to automatic captions for languages without uploaded subtitles
+                args.push("--write-auto-subs".to_string());
+                // A playlist URL would list subtitles for every item, so only single videos are checked
+                if !download_playlist {
+                    match list_subtitles(&app, &url, &subtitle_options).await {
+                        Ok(available) => {
+                            let missing = check_subtitle_langs(&subtitle_langs, &available)?;
+                            if !missing.is_empty() {
+                                notes.push(format!("No subtitles found for: {}", missing.join(", ")));
+                            }
+                        }
+                        Err(e) => {
+                            // Listing is best-effort; yt-dlp reports missing languages itself
+                            add_log_internal("info", "Could not list subtitles to check languages", Some(&e), Some(&url)).ok();
+                        }
+                    }
+                }
+            }
             args.push("--sub-langs".to_string());
             args.push(subtitle_langs.clone());
         }
         args.push("--sub-format".to_string());
         args.push(subtitle_format.clone());
-        if subtitle_embed {
+        if embed_subs {
             args.push("--embed-subs".to_string());
-        }
-    }
-    
-    // Cookie/Authentication settings
-    let mode = cookie_mode.as_deref().unwrap_or("off");
-    match mode {
-        "browser" => {
-            if let Some(browser) = cookie_browser.as_ref() {
-                let mut cookie_arg = browser.clone();
-                // Add profile if specified
-                if let Some(profile) = cookie_browser_profile.as_ref() {
-                    if !profile.is_empty() {
-                        cookie_arg = format!("{}:{}", browser, profile);
-                    }
+            match output_format {
+                // WebM can only carry WebVTT subtitles
+                DownloadFormat::Webm => {
+                    args.push("--convert-subs".to_string());
+                    args.push("vtt".to_string());
                 }
-                args.push("--cookies-from-browser".to_string());
-                args.push(cookie_arg);
-            }
-        }
-        "file" => {
-            if let Some(file_path) = cookie_file_path.as_ref() {
-                if !file_path.is_empty() {
-                    args.push("--cookies".to_string());
-                    args.push(file_path.clone());
+                // MP4 stores subtitles as plain mov_text
+                DownloadFormat::Mp4 if matches!(subtitle_format.as_str(), "ass" | "ssa") => {
+                    notes.push("MP4 can't keep ASS/SSA subtitle styling; choose MKV to preserve it".to_string());
                 }
+                _ => {}
             }
         }
-        _ => {}
+    }
+    
+    // Cookie/Authentication settings
+    if let Some(cookie_source) = cookie_source {
+        args.extend(cookie_source.to_args());
     }
     
     // Proxy settings
-    if let Some(proxy) = proxy_url.as_ref() {
-        if !proxy.is_empty() {
-            args.push("--proxy".to_string());
-            args.push(proxy.clone());
-        }
+    args.extend(build_proxy_args(proxy.as_deref()));
+    
+    // Socket timeout and retries
+    args.extend(network_args());
+    
+    // Rate limit
+    if let Some(limit) = rate_limit {
+        args.push("--limit-rate".to_string());
+        args.push(limit);
+    }
+    
+    if let Some(size) = &max_filesize {
+        args.push("--max-filesize".to_string());
+        args.push(size.clone());
+    }
+    
+    // Fragments fetched in parallel still report one combined percent per stream
+    // (with a "(frag N/M)" suffix that parse_progress ignores), so progress stays accurate
+    if let Some(fragments) = concurrent_fragments {
+        args.push("--concurrent-fragments".to_string());
+        args.push(fragments.to_string());
+    }
+    
+    // Live streams: record from the beginning, waiting for scheduled premieres to start
+    let live = live.unwrap_or(false);
+    if live {
+        args.push("--live-from-start".to_string());
+        args.push("--wait-for-video".to_string());
+        args.push(LIVE_WAIT_INTERVAL_SECS.to_string());
+    }
+    
+    // Download archive
+    if let Some(archive) = archive_file {
+        args.push("--download-archive".to_string());
+        args.push(archive.to_string_lossy().to_string());
     }
     
-    // Playlist handling
+    // Playlist handling; yt-dlp numbers a selected subset 1..N in its progress lines
     if !download_playlist {
         args.push("--no-playlist".to_string());
+    } else if let Some(items) = playlist_items {
+        args.push("--playlist-items".to_string());
+        args.push(items);
     } else if let Some(limit) = playlist_limit {
         if limit > 0 {
             args.push("--playlist-end".to_string());
             args.push(limit.to_string());
         }
     }
-    
-    // Audio formats
-    let is_audio_format = format == "mp3" || format == "m4a" || format == "opus" || quality == "audio";
+    // Progress lines keep counting 1..N in download order, so indices stay sequential
+    match order {
+        PlaylistOrder::Original => {}
+        PlaylistOrder::Reverse => args.push("--playlist-reverse".to_string()),
+        PlaylistOrder::Shuffle => args.push("--playlist-random".to_string()),
+    }
     
     if is_audio_format {
         args.push("-x".to_string());
         args.push("--audio-format".to_string());
-        match format.as_str() {
-            "mp3" => args.push("mp3".to_string()),
-            "m4a" => args.push("m4a".to_string()),
-            "opus" => args.push("opus".to_string()),
-            _ => args.push("mp3".to_string()),
-        }
+        let audio_format = match output_format {
+            DownloadFormat::M4a => DownloadFormat::M4a,
+            DownloadFormat::Opus => DownloadFormat::Opus,
+            // "audio" quality with a video container extracts mp3
+            DownloadFormat::Mp3 | DownloadFormat::Mp4 | DownloadFormat::Mkv | DownloadFormat::Webm => DownloadFormat::Mp3,
+        };
+        args.push(audio_format.to_string());
         args.push("--audio-quality".to_string());
-        match audio_bitrate.as_str() {
-            "128" => args.push("128K".to_string()),
-            _ => args.push("0".to_string()),
-        }
-    } else {
+        args.push(audio_quality);
+    } else if merges_streams {
         args.push("--merge-output-format".to_string());
-        args.push(format.clone());
+        args.push(output_format.to_string());
     }
     
-    // Embed metadata and thumbnail
+    // Embed metadata, chapters and thumbnail
     if embed_metadata.unwrap_or(false) {
         args.push("--embed-metadata".to_string());
     }
-    if embed_thumbnail.unwrap_or(false) {
+    if embed_chapters.unwrap_or(false) {
+        args.push("--embed-chapters".to_string());
+    }
+    let write_info_json = write_info_json.unwrap_or(false);
+    let write_description = write_description.unwrap_or(false);
+    args.extend(build_metadata_file_args(write_info_json, write_description));
+    if should_embed_thumbnail {
         args.push("--embed-thumbnail".to_string());
-        // Convert thumbnail to jpg for better compatibility with MP4 container
+        // jpg by default for better compatibility with MP4 containers and audio players
         args.push("--convert-thumbnails".to_string());
-        args.push("jpg".to_string());
+        args.push(convert_thumbnail_to.to_string());
     }
     
+    // Time range sections
+    args.extend(section_args);
+    
+    // SponsorBlock
+    args.extend(sponsorblock_args);
+    
     args.push(url.clone());
     
-    // Log command
+    // Log start and command
+    add_log_internal("info", &format!("Download started ({} {})", format, quality), None, Some(&url)).ok();
     let command_str = format!("yt-dlp {}", args.join(" "));
     add_log_internal("command", &command_str, None, Some(&url)).ok();
     
-    // Try to get yt-dlp path (prioritizes user-updated version)
-    if let Some((binary_path, _)) = get_ytdlp_path(&app).await {
-        let process = Command::new(&binary_path)
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    // Show the known title right away instead of waiting for yt-dlp to re-extract it
+    if title.is_some() {
+        let progress = DownloadProgress {
+            id: id.clone(),
+            percent: 0.0,
+            speed: String::new(),
+            eta: String::new(),
+            status: "downloading".to_string(),
+            phase: "downloading".to_string(),
+            title: title.clone(),
+            playlist_index: None,
+            playlist_count: None,
+            overall_percent: None,
+            filesize: None,
+            resolution: None,
+            format_ext: None,
+            indeterminate: live,
+        };
+        app.emit("download-progress", progress).ok();
+    }
+    
+    // A selector that can't be satisfied fails fast; retry with looser ones before giving up.
+    // Explicit format ids, separate streams and audio picks are exactly what the user asked for
+    let format_arg = args.iter().position(|arg| arg == "-f").map(|i| i + 1);
+    let mut fallbacks = if format_id.is_none() && !keep_separate && !is_audio_format {
+        relaxed_format_strings(quality_preset, output_format, &video_codec)
+            .into_iter()
+            .map(|selector| match &max_filesize {
+                Some(size) => apply_filesize_limit(&selector, size),
+                None => selector,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+    .into_iter();
+    
+    loop {
+        let result = run_download_process(
+            app.clone(),
+            id.clone(),
+            &args,
+            quality.clone(),
+            format.clone(),
+            url.clone(),
+            should_log_stderr,
+            history_id.clone(),
+            live,
+            title.clone(),
+        ).await;
         
-        return handle_tokio_download(app, id, process, quality, format, url, should_log_stderr).await;
+        match (result, format_arg) {
+            (Err(DownloadError::FormatUnavailable), Some(index)) => {
+                let Some(selector) = fallbacks.next() else {
+                    return Err(DownloadError::FormatUnavailable);
+                };
+                let message = format!(
+                    "The requested format is not available; retrying with a looser format selection ({})",
+                    selector
+                );
+                add_log_internal("info", &message, None, Some(&url)).ok();
+                app.emit("ytdlp-warning", YtdlpWarning {
+                    id: Some(id.clone()),
+                    lines: vec![message],
+                    outdated: false,
+                }).ok();
+                args[index] = selector;
+            }
+            (result, _) => {
+                let download = PausedDownload {
+                    args,
+                    quality,
+                    format,
+                    url,
+                    should_log_stderr,
+                    history_id,
+                    title,
+                    notes,
+                    write_info_json,
+                    write_description,
+                    keep_separate,
+                    temp_dir,
+                };
+                return complete_download(id, result, download);
+            }
+        }
+    }
+}
+
+/// Turn the outcome of a download's yt-dlp run into its result: staged files are moved into place,
+/// and a paused download is kept for `resume_download`
+fn complete_download(
+    id: String,
+    result: Result<Vec<String>, DownloadError>,
+    mut download: PausedDownload,
+) -> Result<DownloadResult, DownloadError> {
+    let files = match (result, download.temp_dir.as_mut()) {
+        (Err(DownloadError::Paused), _) => {
+            if let Ok(mut paused) = PAUSED_DOWNLOADS.lock() {
+                paused.insert(id, download);
+            }
+            return Err(DownloadError::Paused);
+        }
+        (Ok(files), Some(guard)) => guard.commit(files)?,
+        // Dropping the temp directory guard forgets the finished items
+        (result, _) => result?,
+    };
+    let note = (!download.notes.is_empty()).then(|| download.notes.join("\n"));
+    let sidecar_files = existing_sidecar_files(&files, download.write_info_json, download.write_description);
+    Ok(DownloadResult { note, files, sidecar_files })
+}
+
+/// Pause a running download. yt-dlp is stopped with its partial files kept, so
+/// `resume_download` continues where it left off. Live recordings can't be paused
+#[tauri::command]
+pub async fn pause_download(id: String) -> Result<(), String> {
+    let active = ACTIVE_DESTINATIONS
+        .lock()
+        .map(|active| active.contains_key(&id))
+        .unwrap_or(false);
+    if !active {
+        return Err("No running download with this id".to_string());
     }
+    if let Ok(mut requests) = PAUSE_REQUESTS.lock() {
+        requests.insert(id);
+    }
+    Ok(())
+}
+
+/// Resume a download paused with `pause_download`, re-running yt-dlp with `--continue`
+/// against the partial files. Returns like the `download_video` call it continues
+#[tauri::command]
+pub async fn resume_download(app: AppHandle, id: String) -> Result<DownloadResult, DownloadError> {
+    let mut download = PAUSED_DOWNLOADS
+        .lock()
+        .ok()
+        .and_then(|mut paused| paused.remove(&id))
+        .ok_or("This download is not paused")?;
+    CANCEL_FLAG.store(false, Ordering::SeqCst);
+    let _active_guard = ActiveDownloadGuard::new(&id, download.keep_separate);
     
-    // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
+    // The URL is always the last argument
+    if !download.args.iter().any(|arg| arg == "--continue") {
+        let url_position = download.args.len().saturating_sub(1);
+        download.args.insert(url_position, "--continue".to_string());
+    }
+    add_log_internal("info", "Download resumed", None, Some(&download.url)).ok();
     
-    match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, child) = sidecar
-                .args(&args)
-                .spawn()
-                .map_err(|e| format!("Failed to start bundled yt-dlp: {}", e))?;
-            
-            let mut current_title: Option<String> = None;
+    let result = run_download_process(
+        app,
+        id.clone(),
+        &download.args,
+        download.quality.clone(),
+        download.format.clone(),
+        download.url.clone(),
+        download.should_log_stderr,
+        download.history_id.clone(),
+        false,
+        download.title.clone(),
+    ).await;
+    let result = complete_download(id.clone(), result, download);
+    record_download_job_outcome(&id, &result);
+    result
+}
+
+/// Run one yt-dlp download with `args`, reporting progress and saving finished items to history.
+/// Returns every file written
+async fn run_download_process(
+    app: AppHandle,
+    id: String,
+    args: &[String],
+    quality: String,
+    format: String,
+    url: String,
+    should_log_stderr: bool,
+    history_id: Option<String>,
+    live: bool,
+    title: Option<String>,
+) -> Result<Vec<String>, DownloadError> {
+    // Try to get yt-dlp path (prioritizes user-updated version)
+    if let Some((binary_path, _)) = get_ytdlp_path(&app).await {
+        let process = Command::new(&binary_path)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+        
+        return handle_tokio_download(app, id, process, quality, format, url, should_log_stderr, history_id, live, title).await;
+    }
+    
+    // Fallback to sidecar
+    match spawn_ytdlp_sidecar(&app, args)? {
+        Some((mut rx, child)) => {
+            let _recording_guard = live.then(|| LiveRecordingGuard::new(child.pid()));
+            let mut current_title: Option<String> = title;
             let mut current_index: Option<u32> = None;
             let mut total_count: Option<u32> = None;
             let mut total_filesize: u64 = 0;
             let mut current_stream_size: Option<u64> = None;
             let mut final_filepath: Option<String> = None;
+            // Every file written, in order, and the history index last saved
+            let mut files: Vec<String> = Vec::new();
+            let mut recorded_index: Option<Option<u32>> = None;
+            let mut size_limit_skipped = false;
+            // Set when a finished file is much smaller than yt-dlp expected
+            let mut suspect = false;
+            let mut current_phase: Option<&'static str> = None;
+            let mut stderr_buffer = String::new();
+            // Set once a live recording has been asked to stop gracefully
+            let mut stopping = false;
+            // Reported again when the download is paused
+            let mut last_percent = 0.0;
+            let throttle = ProgressThrottle::new(&app);
             
             let quality_display = match quality.as_str() {
                 "8k" => Some("8K".to_string()),
@@ -258,82 +1243,165 @@ pub async fn download_video(
                 _ => None,
             };
             
-            while let Some(event) = rx.recv().await {
-                if CANCEL_FLAG.load(Ordering::SeqCst) {
+            let mut pause_check = tokio::time::interval(PAUSE_CHECK_INTERVAL);
+            loop {
+                // The timer lets a stalled download notice Stop and Pause too
+                let event = tokio::select! {
+                    event = rx.recv() => match event {
+                        Some(event) => Some(event),
+                        None => break,
+                    },
+                    _ = pause_check.tick() => None,
+                };
+                if CANCEL_FLAG.load(Ordering::SeqCst) && !stopping {
+                    // Let a live recording finish muxing what it has recorded so far
+                    if live && interrupt_recording(child.pid()) {
+                        stopping = true;
+                        add_log_internal("info", "Stopping live recording", None, Some(&url)).ok();
+                        continue;
+                    }
                     child.kill().ok();
                     kill_all_download_processes();
-                    return Err("Download cancelled".to_string());
+                    add_log_internal("info", "Download cancelled by user", None, Some(&url)).ok();
+                    return Err(DownloadError::Cancelled);
+                }
+                
+                // Stop like Ctrl+C so yt-dlp keeps its .part files for `--continue`
+                if !live && take_pause_request(&id) {
+                    let interrupted = interrupt_recording(child.pid());
+                    if !interrupted || tokio::time::timeout(PAUSE_EXIT_TIMEOUT, wait_for_sidecar_exit(&mut rx)).await.is_err() {
+                        child.kill().ok();
+                        wait_for_sidecar_exit(&mut rx).await;
+                    }
+                    add_log_internal("info", "Download paused", None, Some(&url)).ok();
+                    emit_paused(&throttle, &id, last_percent, current_title, current_index, total_count);
+                    return Err(DownloadError::Paused);
                 }
                 
+                let Some(event) = event else {
+                    continue;
+                };
                 match event {
                     CommandEvent::Stdout(line_bytes) => {
                         let line = String::from_utf8_lossy(&line_bytes);
                         
                         // Parse playlist item info
                         if line.contains("Downloading item") {
-                            if let Some(re) = regex::Regex::new(r"Downloading item (\d+) of (\d+)").ok() {
-                                if let Some(caps) = re.captures(&line) {
-                                    current_index = caps.get(1).and_then(|m| m.as_str().parse().ok());
-                                    total_count = caps.get(2).and_then(|m| m.as_str().parse().ok());
-                                }
+                            if let Some(caps) = PLAYLIST_ITEM_RE.captures(&line) {
+                                current_index = caps.get(1).and_then(|m| m.as_str().parse().ok());
+                                total_count = caps.get(2).and_then(|m| m.as_str().parse().ok());
                             }
                         }
                         
+                        // Remember where yt-dlp writes, for stop_all_downloads cleanup
+                        track_destination(&id, &line);
+                        
                         // Extract title
-                        if line.contains("[download] Destination:") || line.contains("[ExtractAudio]") {
-                            if let Some(start) = line.rfind('/') {
-                                let filename = &line[start + 1..];
-                                if let Some(end) = filename.rfind('.') {
-                                    current_title = Some(filename[..end].to_string());
-                                }
+                        if let Some(title) = parse_destination_title(&line) {
+                            current_title = Some(title);
+                        }
+                        
+                        // Report post-processing (merge/extract/convert) instead of sitting at the last percent
+                        if let Some(phase) = detect_postprocess_phase(&line) {
+                            if current_phase != Some(phase) {
+                                current_phase = Some(phase);
+                                let progress = DownloadProgress {
+                                    id: id.clone(),
+                                    percent: 99.0,
+                                    speed: String::new(),
+                                    eta: String::new(),
+                                    status: "processing".to_string(),
+                                    phase: phase.to_string(),
+                                    title: current_title.clone(),
+                                    playlist_index: current_index,
+                                    playlist_count: total_count,
+                                    overall_percent: overall_percent(current_index, total_count, 99.0),
+                                    filesize: None,
+                                    resolution: None,
+                                    format_ext: None,
+                                    indeterminate: false,
+                                };
+                                throttle.emit_now(progress);
                             }
                         }
                         
-                        // Capture final filepath
-                        let trimmed = line.trim();
-                        if !trimmed.is_empty() 
-                            && !trimmed.starts_with('[') 
-                            && !trimmed.starts_with("Deleting")
-                            && !trimmed.starts_with("WARNING")
-                            && !trimmed.starts_with("ERROR")
-                            && (trimmed.ends_with(".mp3") 
-                                || trimmed.ends_with(".m4a") 
-                                || trimmed.ends_with(".opus")
-                                || trimmed.ends_with(".mp4")
-                                || trimmed.ends_with(".mkv")
-                                || trimmed.ends_with(".webm"))
-                        {
-                            final_filepath = Some(trimmed.to_string());
+                        // Item over --max-filesize: yt-dlp skips it without failing
+                        if is_max_filesize_skip(&line) {
+                            size_limit_skipped = true;
+                            continue;
+                        }
+                        
+                        // Item already in the download archive
+                        if let Some(title) = parse_archive_skip(&line) {
+                            let progress = DownloadProgress {
+                                id: id.clone(),
+                                percent: 100.0,
+                                speed: String::new(),
+                                eta: String::new(),
+                                status: "skipped".to_string(),
+                                phase: "skipped".to_string(),
+                                title: Some(title),
+                                playlist_index: current_index,
+                                playlist_count: total_count,
+                                overall_percent: overall_percent(current_index, total_count, 100.0),
+                                filesize: None,
+                                resolution: None,
+                                format_ext: None,
+                                indeterminate: false,
+                            };
+                            throttle.emit_now(progress);
+                            continue;
+                        }
+                        
+                        // Item finished post-processing: save it to history
+                        if let Some(item) = parse_finished_item(&line) {
+                            suspect |= check_finished_size(&item, &url);
+                            // With keep_separate an item finishes once per stream; the video comes first
+                            let history_index = total_count.and(current_index);
+                            if recorded_index != Some(history_index) {
+                                save_finished_item(
+                                    &item,
+                                    &id,
+                                    &url,
+                                    history_index,
+                                    history_id.as_deref(),
+                                    quality_display.clone(),
+                                    &format,
+                                );
+                                recorded_index = Some(history_index);
+                            }
+                            current_title = Some(item.title);
+                            files.push(item.filepath.clone());
+                            final_filepath = Some(item.filepath);
+                            current_phase = None;
+                            continue;
                         }
                         
                         // Parse filesize
-                        if line.contains(" of ") && (line.contains("MiB") || line.contains("GiB") || line.contains("KiB")) {
-                            if let Some(re) = regex::Regex::new(r"of\s+(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB)").ok() {
-                                if let Some(caps) = re.captures(&line) {
-                                    if let (Some(num), Some(unit)) = (caps.get(1), caps.get(2)) {
-                                        if let Ok(size) = num.as_str().parse::<f64>() {
-                                            let size_bytes = match unit.as_str() {
-                                                "GiB" => (size * 1024.0 * 1024.0 * 1024.0) as u64,
-                                                "MiB" => (size * 1024.0 * 1024.0) as u64,
-                                                "KiB" => (size * 1024.0) as u64,
-                                                _ => size as u64,
-                                            };
-                                            if current_stream_size != Some(size_bytes) {
-                                                if let Some(prev_size) = current_stream_size {
-                                                    total_filesize += prev_size;
-                                                }
-                                                current_stream_size = Some(size_bytes);
-                                            }
-                                        }
+                        if line.contains(" of ") {
+                            if let Some(size_bytes) = parse_stream_size(&line) {
+                                if current_stream_size != Some(size_bytes) {
+                                    if let Some(prev_size) = current_stream_size {
+                                        total_filesize += prev_size;
                                     }
+                                    current_stream_size = Some(size_bytes);
                                 }
                             }
                         }
                         
+                        // Live recordings report bytes so far rather than a percent
+                        if live {
+                            if let Some((downloaded, speed)) = parse_live_progress(&line) {
+                                update_recording(&throttle, &id, downloaded, speed, current_title.clone(), current_index, total_count);
+                                continue;
+                            }
+                        }
+                        
                         // Parse progress
                         if let Some((percent, speed, eta, pi, pc)) = parse_progress(&line) {
                             if pi.is_some() { current_index = pi; }
                             if pc.is_some() { total_count = pc; }
+                            last_percent = percent;
                             
                             let progress = DownloadProgress {
                                 id: id.clone(),
@@ -341,22 +1409,35 @@ pub async fn download_video(
                                 speed,
                                 eta,
                                 status: "downloading".to_string(),
+                                phase: "downloading".to_string(),
                                 title: current_title.clone(),
                                 playlist_index: current_index,
                                 playlist_count: total_count,
+                                overall_percent: overall_percent(current_index, total_count, percent),
                                 filesize: None,
                                 resolution: None,
                                 format_ext: None,
+                                indeterminate: false,
                             };
-                            app.emit("download-progress", progress).ok();
+                            throttle.update(progress);
                         }
                     }
                     CommandEvent::Stderr(bytes) => {
                         let stderr_line = String::from_utf8_lossy(&bytes).trim().to_string();
+                        stderr_buffer.push_str(&stderr_line);
+                        stderr_buffer.push('\n');
+                        
+                        if live {
+                            if let Some((downloaded, speed)) = parse_live_progress(&stderr_line) {
+                                update_recording(&throttle, &id, downloaded, speed, current_title.clone(), current_index, total_count);
+                                continue;
+                            }
+                        }
                         
                         if let Some((percent, speed, eta, pi, pc)) = parse_progress(&stderr_line) {
                             if pi.is_some() { current_index = pi; }
                             if pc.is_some() { total_count = pc; }
+                            last_percent = percent;
                             
                             let progress = DownloadProgress {
                                 id: id.clone(),
@@ -364,14 +1445,17 @@ pub async fn download_video(
                                 speed,
                                 eta,
                                 status: "downloading".to_string(),
+                                phase: "downloading".to_string(),
                                 title: current_title.clone(),
                                 playlist_index: current_index,
                                 playlist_count: total_count,
+                                overall_percent: overall_percent(current_index, total_count, percent),
                                 filesize: None,
                                 resolution: None,
                                 format_ext: None,
+                                indeterminate: false,
                             };
-                            app.emit("download-progress", progress).ok();
+                            throttle.update(progress);
                         }
                         
                         if should_log_stderr && !stderr_line.is_empty() {
@@ -381,15 +1465,21 @@ pub async fn download_video(
                     CommandEvent::Error(err) => {
                         let error_msg = format!("Process error: {}", err);
                         add_log_internal("error", &error_msg, None, Some(&url)).ok();
-                        return Err(error_msg);
+                        return Err(DownloadError::Unknown(error_msg));
                     }
                     CommandEvent::Terminated(status) => {
-                        if CANCEL_FLAG.load(Ordering::SeqCst) {
+                        if CANCEL_FLAG.load(Ordering::SeqCst) && !stopping {
                             add_log_internal("info", "Download cancelled by user", None, Some(&url)).ok();
-                            return Err("Download cancelled".to_string());
+                            return Err(DownloadError::Cancelled);
+                        }
+                        
+                        if status.code == Some(0) && size_limit_skipped && final_filepath.is_none() {
+                            add_log_internal("error", &DownloadError::FileTooLarge.to_string(), None, Some(&url)).ok();
+                            return Err(DownloadError::FileTooLarge);
                         }
                         
-                        if status.code == Some(0) {
+                        // An interrupted live recording exits non-zero but keeps the muxed partial file
+                        if status.code == Some(0) || stopping {
                             let actual_filesize = final_filepath.as_ref()
                                 .and_then(|fp| std::fs::metadata(fp).ok())
                                 .map(|m| m.len());
@@ -423,71 +1513,46 @@ pub async fn download_video(
                             );
                             add_log_internal("success", &success_msg, Some(&details), Some(&url)).ok();
                             
-                            // Save to history (update existing or create new)
-                            if let Some(ref filepath) = final_filepath {
-                                if let Some(ref hist_id) = history_id {
-                                    // Update existing history entry (re-download)
-                                    update_history_download(
-                                        hist_id.clone(),
-                                        filepath.clone(),
-                                        reported_filesize,
-                                        quality_display.clone(),
-                                        Some(format.clone()),
-                                    ).ok();
-                                } else {
-                                    // Create new history entry
-                                    let source = detect_source(&url);
-                                    let thumbnail = generate_thumbnail_url(&url);
-                                    
-                                    add_history_internal(
-                                        url.clone(),
-                                        display_title.clone().unwrap_or_else(|| "Unknown".to_string()),
-                                        thumbnail,
-                                        filepath.clone(),
-                                        reported_filesize,
-                                        None,
-                                        quality_display.clone(),
-                                        Some(format.clone()),
-                                        source,
-                                    ).ok();
-                                }
-                            }
-                            
                             let progress = DownloadProgress {
                                 id: id.clone(),
                                 percent: 100.0,
                                 speed: String::new(),
                                 eta: String::new(),
-                                status: "finished".to_string(),
+                                status: if suspect { "suspect" } else { "finished" }.to_string(),
+                                phase: "finished".to_string(),
                                 title: display_title,
                                 playlist_index: current_index,
                                 playlist_count: total_count,
+                                overall_percent: total_count.map(|_| 100.0),
                                 filesize: reported_filesize,
                                 resolution: quality_display.clone(),
                                 format_ext: Some(format.clone()),
+                                indeterminate: false,
                             };
-                            app.emit("download-progress", progress).ok();
-                            return Ok(());
+                            throttle.emit_now(progress);
+                            report_ytdlp_warnings(&app, Some(&id), &stderr_buffer);
+                            return Ok(files);
                         } else {
-                            add_log_internal("error", "Download failed", None, Some(&url)).ok();
-                            return Err("Download failed".to_string());
+                            let error = classify_download_failure(&stderr_buffer);
+                            add_log_internal("error", &format!("Download failed: {}", error), None, Some(&url)).ok();
+                            return Err(error);
                         }
                     }
                     _ => {}
                 }
             }
-            Ok(())
+            Ok(files)
         }
-        Err(_) => {
+        None => {
             // Fallback to system yt-dlp
             let process = Command::new("yt-dlp")
-                .args(&args)
+                .args(args)
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
                 .spawn()
                 .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
             
-            handle_tokio_download(app, id, process, quality, format, url, should_log_stderr).await
+            handle_tokio_download(app, id, process, quality, format, url, should_log_stderr, history_id, live, title).await
         }
     }
 }
@@ -500,17 +1565,53 @@ async fn handle_tokio_download(
     format: String,
     url: String,
     should_log_stderr: bool,
-) -> Result<(), String> {
+    history_id: Option<String>,
+    live: bool,
+    title: Option<String>,
+) -> Result<Vec<String>, DownloadError> {
+    let _recording_guard = process.id().filter(|_| live).map(LiveRecordingGuard::new);
     let stdout = process.stdout.take().ok_or("Failed to get stdout")?;
-    let stderr = process.stderr.take();
     let mut reader = BufReader::new(stdout).lines();
     
-    let mut current_title: Option<String> = None;
+    // Drain stderr concurrently so a full pipe can't stall yt-dlp; keep it for error classification
+    let stderr_task = process.stderr.take().map(|stderr_handle| {
+        let url = url.clone();
+        tokio::spawn(async move {
+            let mut buffer = String::new();
+            let mut stderr_reader = BufReader::new(stderr_handle).lines();
+            while let Ok(Some(stderr_line)) = stderr_reader.next_line().await {
+                let stderr_line = stderr_line.trim();
+                if stderr_line.is_empty() {
+                    continue;
+                }
+                if should_log_stderr {
+                    add_log_internal("stderr", stderr_line, None, Some(&url)).ok();
+                }
+                buffer.push_str(stderr_line);
+                buffer.push('\n');
+            }
+            buffer
+        })
+    });
+    
+    let mut current_title: Option<String> = title;
     let mut current_index: Option<u32> = None;
     let mut total_count: Option<u32> = None;
     let mut total_filesize: u64 = 0;
     let mut current_stream_size: Option<u64> = None;
     let mut final_filepath: Option<String> = None;
+    // Every file written, in order, and the history index last saved
+    let mut files: Vec<String> = Vec::new();
+    let mut recorded_index: Option<Option<u32>> = None;
+    let mut size_limit_skipped = false;
+    // Set when a finished file is much smaller than yt-dlp expected
+    let mut suspect = false;
+    let mut current_phase: Option<&'static str> = None;
+    // Set once a live recording has been asked to stop gracefully
+    let mut stopping = false;
+    // Reported again when the download is paused
+    let mut last_percent = 0.0;
+    let throttle = ProgressThrottle::new(&app);
     
     let quality_display = match quality.as_str() {
         "8k" => Some("8K".to_string()),
@@ -525,17 +1626,57 @@ async fn handle_tokio_download(
         _ => None,
     };
     
-    while let Ok(Some(line)) = reader.next_line().await {
-        if CANCEL_FLAG.load(Ordering::SeqCst) {
+    let mut pause_check = tokio::time::interval(PAUSE_CHECK_INTERVAL);
+    loop {
+        // The timer lets a stalled download notice Stop and Pause too
+        let line = tokio::select! {
+            line = reader.next_line() => match line {
+                Ok(Some(line)) => Some(line),
+                _ => break,
+            },
+            _ = pause_check.tick() => None,
+        };
+        if CANCEL_FLAG.load(Ordering::SeqCst) && !stopping {
+            // Let a live recording finish muxing what it has recorded so far
+            if live && process.id().is_some_and(interrupt_recording) {
+                stopping = true;
+                add_log_internal("info", "Stopping live recording", None, Some(&url)).ok();
+                continue;
+            }
             process.kill().await.ok();
             kill_all_download_processes();
-            return Err("Download cancelled".to_string());
+            add_log_internal("info", "Download cancelled by user", None, Some(&url)).ok();
+            return Err(DownloadError::Cancelled);
+        }
+        
+        // Stop like Ctrl+C so yt-dlp keeps its .part files for `--continue`
+        if !live && take_pause_request(&id) {
+            let interrupted = process.id().is_some_and(interrupt_recording);
+            if !interrupted || tokio::time::timeout(PAUSE_EXIT_TIMEOUT, process.wait()).await.is_err() {
+                process.kill().await.ok();
+            }
+            add_log_internal("info", "Download paused", None, Some(&url)).ok();
+            emit_paused(&throttle, &id, last_percent, current_title, current_index, total_count);
+            return Err(DownloadError::Paused);
+        }
+        
+        let Some(line) = line else {
+            continue;
+        };
+        
+        // Live recordings report bytes so far rather than a percent
+        if live {
+            if let Some((downloaded, speed)) = parse_live_progress(&line) {
+                update_recording(&throttle, &id, downloaded, speed, current_title.clone(), current_index, total_count);
+                continue;
+            }
         }
         
         // Parse progress and emit events
         if let Some((percent, speed, eta, pi, pc)) = parse_progress(&line) {
             if pi.is_some() { current_index = pi; }
             if pc.is_some() { total_count = pc; }
+            last_percent = percent;
             
             let progress = DownloadProgress {
                 id: id.clone(),
@@ -543,57 +1684,111 @@ async fn handle_tokio_download(
                 speed,
                 eta,
                 status: "downloading".to_string(),
+                phase: "downloading".to_string(),
                 title: current_title.clone(),
                 playlist_index: current_index,
                 playlist_count: total_count,
+                overall_percent: overall_percent(current_index, total_count, percent),
                 filesize: None,
                 resolution: None,
                 format_ext: None,
+                indeterminate: false,
             };
-            app.emit("download-progress", progress).ok();
+            throttle.update(progress);
         }
         
+        // Remember where yt-dlp writes, for stop_all_downloads cleanup
+        track_destination(&id, &line);
+        
         // Extract title
-        if line.contains("[download] Destination:") {
-            if let Some(start) = line.rfind('/') {
-                let filename = &line[start + 1..];
-                if let Some(end) = filename.rfind('.') {
-                    current_title = Some(filename[..end].to_string());
-                }
+        if let Some(title) = parse_destination_title(&line) {
+            current_title = Some(title);
+        }
+        
+        // Report post-processing (merge/extract/convert) instead of sitting at the last percent
+        if let Some(phase) = detect_postprocess_phase(&line) {
+            if current_phase != Some(phase) {
+                current_phase = Some(phase);
+                let progress = DownloadProgress {
+                    id: id.clone(),
+                    percent: 99.0,
+                    speed: String::new(),
+                    eta: String::new(),
+                    status: "processing".to_string(),
+                    phase: phase.to_string(),
+                    title: current_title.clone(),
+                    playlist_index: current_index,
+                    playlist_count: total_count,
+                    overall_percent: overall_percent(current_index, total_count, 99.0),
+                    filesize: None,
+                    resolution: None,
+                    format_ext: None,
+                    indeterminate: false,
+                };
+                throttle.emit_now(progress);
             }
         }
         
-        // Capture final filepath
-        let trimmed = line.trim();
-        if !trimmed.is_empty() 
-            && !trimmed.starts_with('[') 
-            && (trimmed.ends_with(".mp3") || trimmed.ends_with(".m4a") 
-                || trimmed.ends_with(".opus") || trimmed.ends_with(".mp4")
-                || trimmed.ends_with(".mkv") || trimmed.ends_with(".webm"))
-        {
-            final_filepath = Some(trimmed.to_string());
+        // Item over --max-filesize: yt-dlp skips it without failing
+        if is_max_filesize_skip(&line) {
+            size_limit_skipped = true;
+            continue;
+        }
+        
+        // Item already in the download archive
+        if let Some(title) = parse_archive_skip(&line) {
+            let progress = DownloadProgress {
+                id: id.clone(),
+                percent: 100.0,
+                speed: String::new(),
+                eta: String::new(),
+                status: "skipped".to_string(),
+                phase: "skipped".to_string(),
+                title: Some(title),
+                playlist_index: current_index,
+                playlist_count: total_count,
+                overall_percent: overall_percent(current_index, total_count, 100.0),
+                filesize: None,
+                resolution: None,
+                format_ext: None,
+                indeterminate: false,
+            };
+            throttle.emit_now(progress);
+            continue;
+        }
+        
+        // Item finished post-processing: save it to history
+        if let Some(item) = parse_finished_item(&line) {
+            suspect |= check_finished_size(&item, &url);
+            // With keep_separate an item finishes once per stream; the video comes first
+            let history_index = total_count.and(current_index);
+            if recorded_index != Some(history_index) {
+                save_finished_item(
+                    &item,
+                    &id,
+                    &url,
+                    history_index,
+                    history_id.as_deref(),
+                    quality_display.clone(),
+                    &format,
+                );
+                recorded_index = Some(history_index);
+            }
+            current_title = Some(item.title);
+            files.push(item.filepath.clone());
+            final_filepath = Some(item.filepath);
+            current_phase = None;
+            continue;
         }
         
         // Parse filesize
-        if line.contains(" of ") && (line.contains("MiB") || line.contains("GiB")) {
-            if let Some(re) = regex::Regex::new(r"of\s+(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB)").ok() {
-                if let Some(caps) = re.captures(&line) {
-                    if let (Some(num), Some(unit)) = (caps.get(1), caps.get(2)) {
-                        if let Ok(size) = num.as_str().parse::<f64>() {
-                            let size_bytes = match unit.as_str() {
-                                "GiB" => (size * 1024.0 * 1024.0 * 1024.0) as u64,
-                                "MiB" => (size * 1024.0 * 1024.0) as u64,
-                                "KiB" => (size * 1024.0) as u64,
-                                _ => size as u64,
-                            };
-                            if current_stream_size != Some(size_bytes) {
-                                if let Some(prev_size) = current_stream_size {
-                                    total_filesize += prev_size;
-                                }
-                                current_stream_size = Some(size_bytes);
-                            }
-                        }
+        if line.contains(" of ") {
+            if let Some(size_bytes) = parse_stream_size(&line) {
+                if current_stream_size != Some(size_bytes) {
+                    if let Some(prev_size) = current_stream_size {
+                        total_filesize += prev_size;
                     }
+                    current_stream_size = Some(size_bytes);
                 }
             }
         }
@@ -601,19 +1796,18 @@ async fn handle_tokio_download(
     
     let status = process.wait().await.map_err(|e| format!("Process error: {}", e))?;
     
-    // Process stderr
-    if should_log_stderr {
-        if let Some(stderr_handle) = stderr {
-            let mut stderr_reader = BufReader::new(stderr_handle).lines();
-            while let Ok(Some(stderr_line)) = stderr_reader.next_line().await {
-                if !stderr_line.trim().is_empty() {
-                    add_log_internal("stderr", stderr_line.trim(), None, Some(&url)).ok();
-                }
-            }
-        }
+    let stderr_output = match stderr_task {
+        Some(task) => task.await.unwrap_or_default(),
+        None => String::new(),
+    };
+    
+    if status.success() && size_limit_skipped && final_filepath.is_none() {
+        add_log_internal("error", &DownloadError::FileTooLarge.to_string(), None, Some(&url)).ok();
+        return Err(DownloadError::FileTooLarge);
     }
     
-    if status.success() {
+    // An interrupted live recording exits non-zero but keeps the muxed partial file
+    if status.success() || stopping {
         let actual_filesize = final_filepath.as_ref()
             .and_then(|fp| std::fs::metadata(fp).ok())
             .map(|m| m.len());
@@ -637,56 +1831,1152 @@ async fn handle_tokio_download(
         );
         add_log_internal("success", &success_msg, Some(&details), Some(&url)).ok();
         
-        // Save to history
-        if let Some(ref filepath) = final_filepath {
-            let source = detect_source(&url);
-            let thumbnail = generate_thumbnail_url(&url);
-            
-            add_history_internal(
-                url.clone(),
-                current_title.clone().unwrap_or_else(|| "Unknown".to_string()),
-                thumbnail,
-                filepath.clone(),
-                reported_filesize,
-                None,
-                quality_display.clone(),
-                Some(format.clone()),
-                source,
-            ).ok();
-        }
-        
         let progress = DownloadProgress {
             id: id.clone(),
             percent: 100.0,
             speed: String::new(),
             eta: String::new(),
-            status: "finished".to_string(),
+            status: if suspect { "suspect" } else { "finished" }.to_string(),
+            phase: "finished".to_string(),
             title: current_title,
             playlist_index: current_index,
             playlist_count: total_count,
+            overall_percent: total_count.map(|_| 100.0),
             filesize: reported_filesize,
             resolution: quality_display,
             format_ext: Some(format),
+            indeterminate: false,
         };
-        app.emit("download-progress", progress).ok();
-        Ok(())
+        throttle.emit_now(progress);
+        report_ytdlp_warnings(&app, Some(&id), &stderr_output);
+        Ok(files)
+    } else {
+        let error = classify_download_failure(&stderr_output);
+        add_log_internal("error", &format!("Download failed: {}", error), None, Some(&url)).ok();
+        Err(error)
+    }
+}
+
+/// Default number of batch jobs downloading at once
+const DEFAULT_BATCH_CONCURRENCY: usize = 2;
+/// Upper bound for batch concurrency
+const MAX_BATCH_CONCURRENCY: usize = 8;
+
+/// Queue several downloads at once. Jobs run in the background, at most
+/// `max_concurrent` at a time; each emits `download-progress` under its own id and a
+/// `download-job-finished` event when done. Returns the accepted job ids immediately.
+#[tauri::command]
+pub async fn download_batch(
+    app: AppHandle,
+    jobs: Vec<DownloadJob>,
+    max_concurrent: Option<usize>,
+) -> Result<Vec<String>, String> {
+    if jobs.is_empty() {
+        return Err("No downloads to start".to_string());
+    }
+    
+    let limit = max_concurrent
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+        .clamp(1, MAX_BATCH_CONCURRENCY);
+    let semaphore = Arc::new(Semaphore::new(limit));
+    let accepted: Vec<String> = jobs.iter().map(|job| job.id.clone()).collect();
+    
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Ok(mut batches) = BATCH_CANCELS.lock() {
+        batches.push(Arc::downgrade(&cancelled));
+    }
+    CANCEL_FLAG.store(false, Ordering::SeqCst);
+    
+    for job in jobs {
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        let cancelled = cancelled.clone();
+        tauri::async_runtime::spawn(async move {
+            let id = job.id.clone();
+            let permit = if cancelled.load(Ordering::SeqCst) {
+                None
+            } else {
+                semaphore.acquire_owned().await.ok()
+            };
+            let outcome = match permit {
+                Some(_permit) if !cancelled.load(Ordering::SeqCst) => {
+                    match tracked_download_job(app.clone(), job).await {
+                        Ok(result) => DownloadJobOutcome { id, note: result.note, error: None },
+                        Err(error) => DownloadJobOutcome { id, note: None, error: Some(error) },
+                    }
+                }
+                _ => DownloadJobOutcome { id, note: None, error: Some(DownloadError::Cancelled) },
+            };
+            app.emit("download-job-finished", outcome).ok();
+        });
+    }
+    
+    Ok(accepted)
+}
+
+/// Download `url` with the options of the saved profile `profile_name`.
+/// Progress is reported under `id` like download_video
+#[tauri::command]
+pub async fn download_with_profile(
+    app: AppHandle,
+    id: String,
+    url: String,
+    profile_name: String,
+    output_path: String,
+) -> Result<DownloadResult, DownloadError> {
+    let profile = load_profiles(&app)?
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(profile_name.trim()))
+        .ok_or_else(|| format!("No profile named \"{}\"", profile_name.trim()))?;
+    download_job(app, profile.to_job(id, url, output_path)).await
+}
+
+/// Download `url` as a single video, then summarize its transcript with the saved AI config.
+/// Progress is reported under `id` like download_video, followed by a `summary-ready` event.
+/// The transcript and summary are stored with the history entry. A failed summary is reported
+/// in the result and the event but keeps the finished download. Both use the saved cookie settings
+#[tauri::command]
+pub async fn download_and_summarize(
+    app: AppHandle,
+    id: String,
+    url: String,
+    quality: String,
+    format: String,
+    output_path: String,
+    proxy_url: Option<String>,
+) -> Result<DownloadSummaryResult, DownloadError> {
+    // Checked up front so a disabled AI config doesn't cost a download
+    if !get_ai_config(app.clone()).await?.enabled {
+        return Err("AI features are disabled. Enable them in Settings.".into());
+    }
+    
+    let job = DownloadJob {
+        id: id.clone(),
+        url: url.clone(),
+        output_path,
+        quality,
+        format,
+        proxy_url: proxy_url.clone(),
+        ..Default::default()
+    };
+    let download = download_job(app.clone(), job).await?;
+    
+    // Single-video downloads are recorded in history under their download id
+    let history_id = id.clone();
+    let summary = match summarize_history_entry(&app, &history_id, proxy_url).await {
+        Ok(summary) => SummaryReady { id, history_id, summary: Some(summary), error: None },
+        Err(error) => {
+            add_log_internal("error", "Download finished but summarizing it failed", Some(&error), Some(&url)).ok();
+            SummaryReady { id, history_id, summary: None, error: Some(error) }
+        }
+    };
+    app.emit("summary-ready", &summary).ok();
+    
+    Ok(DownloadSummaryResult { download, summary })
+}
+
+/// Fetch the transcript of history entry `history_id` and summarize it, storing both in history
+async fn summarize_history_entry(app: &AppHandle, history_id: &str, proxy_url: Option<String>) -> Result<String, String> {
+    let config = get_ai_config(app.clone()).await?;
+    let entry = get_history_entry_from_db(history_id)?
+        .ok_or_else(|| "The download was not recorded in history".to_string())?;
+    
+    let download_config = load_download_config(app);
+    let transcript = get_or_fetch_transcript(
+        app.clone(),
+        history_id.to_string(),
+        entry.url.clone(),
+        None,
+        None,
+        download_config.cookie_mode,
+        download_config.cookie_browser,
+        download_config.cookie_browser_profile,
+        download_config.cookie_file_path,
+        proxy_url,
+    ).await?;
+    
+    let result = generate_summary(&config, &transcript, Some(&entry.title))
+        .await
+        .map_err(|e| e.to_string())?;
+    update_history_summary(history_id.to_string(), result.summary.clone(), Some(result.provider), Some(result.model))?;
+    
+    Ok(result.summary)
+}
+
+/// Downloads that ended in an error and can be retried, most recent first
+#[tauri::command]
+pub async fn get_failed_downloads() -> Result<Vec<FailedDownload>, String> {
+    get_failed_downloads_from_db()
+}
+
+/// Run a failed download again with its original parameters and id. Like `download_batch`,
+/// it runs in the background and emits `download-job-finished` when done
+#[tauri::command]
+pub async fn retry_failed_download(app: AppHandle, id: String) -> Result<(), String> {
+    let job = get_failed_download_job(&id)?
+        .ok_or_else(|| "No failed download with this id".to_string())?;
+    add_log_internal("info", "Retrying failed download", None, Some(&job.url)).ok();
+    
+    tauri::async_runtime::spawn(async move {
+        let outcome = match run_download_job(app.clone(), job).await {
+            Ok(note) => DownloadJobOutcome { id, note, error: None },
+            Err(error) => DownloadJobOutcome { id, note: None, error: Some(error) },
+        };
+        app.emit("download-job-finished", outcome).ok();
+    });
+    Ok(())
+}
+
+/// Largest link list import_urls_from_file will read
+const MAX_IMPORT_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Read URLs from a `.txt` (one per line) or `.csv` (first column) file.
+/// Blank lines and `#` comments are ignored; every URL goes through normalize_url.
+#[tauri::command]
+pub async fn import_urls_from_file(path: String) -> Result<ImportedUrls, String> {
+    let file_path = std::path::Path::new(&path);
+    let metadata = tokio::fs::metadata(file_path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    if metadata.len() > MAX_IMPORT_FILE_BYTES {
+        return Err("File is too large to import (max 5 MB)".to_string());
+    }
+    
+    let contents = tokio::fs::read_to_string(file_path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let is_csv = file_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    
+    Ok(parse_url_list(&contents, is_csv))
+}
+
+/// Parse a link list; for CSV only the first column is used and a "url"/"link" header is skipped
+fn parse_url_list(contents: &str, is_csv: bool) -> ImportedUrls {
+    let mut urls: Vec<String> = Vec::new();
+    let mut rejected = 0;
+    
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let candidate = if is_csv {
+            line.split(',').next().unwrap_or("").trim().trim_matches('"').trim()
+        } else {
+            line
+        };
+        if is_csv && index == 0 && matches!(candidate.to_lowercase().as_str(), "url" | "link" | "links" | "urls") {
+            continue;
+        }
+        
+        match normalize_url(candidate) {
+            Ok(normalized) => {
+                if !urls.contains(&normalized.canonical) {
+                    urls.push(normalized.canonical);
+                }
+            }
+            Err(_) => rejected += 1,
+        }
+    }
+    
+    ImportedUrls { urls, rejected }
+}
+
+/// Run a background job (see retry_failed_download) the way download_video runs a single download
+async fn run_download_job(app: AppHandle, job: DownloadJob) -> Result<Option<String>, DownloadError> {
+    download_job(app, job).await.map(|result| result.note)
+}
+
+/// Download a single chapter of a video as a clip, named after the chapter title.
+/// `chapter_index` is zero-based into `VideoInfo.chapters`.
+#[tauri::command]
+pub async fn download_chapter(
+    app: AppHandle,
+    id: String,
+    url: String,
+    chapter_index: usize,
+    quality: String,
+    format: String,
+    output_path: String,
+    video_codec: Option<String>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<Option<String>, DownloadError> {
+    let response = get_video_info(
+        app.clone(),
+        url.clone(),
+        cookie_mode.clone(),
+        cookie_browser.clone(),
+        cookie_browser_profile.clone(),
+        cookie_file_path.clone(),
+        proxy_url.clone(),
+        None,
+        None,
+    ).await?;
+    
+    let chapters = response.info.chapters.unwrap_or_default();
+    let chapter = chapters.get(chapter_index).ok_or_else(|| {
+        format!("Chapter {} is out of range (video has {} chapters)", chapter_index + 1, chapters.len())
+    })?;
+    
+    // The last chapter may lack end_time; fall back to the next chapter or the video end
+    let end_time = chapter.end_time
+        .or_else(|| chapters.get(chapter_index + 1).map(|next| next.start_time))
+        .or(response.info.duration)
+        .ok_or("Could not determine where this chapter ends")?;
+    
+    let job = DownloadJob {
+        id,
+        url,
+        output_path,
+        quality,
+        format,
+        video_codec,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        proxy_url,
+        sections: Some(vec![(chapter.start_time.to_string(), end_time.to_string())]),
+        filename_template: Some(chapter_filename_template(&chapter.title, chapter_index)),
+        title: Some(response.info.title.clone()),
+        ..Default::default()
+    };
+    download_job(app, job).await.map(|result| result.note)
+}
+
+/// Output template "<video title> - <chapter title>.<ext>", escaped for yt-dlp
+fn chapter_filename_template(chapter_title: &str, chapter_index: usize) -> String {
+    let safe_title: String = chapter_title
+        .trim()
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect::<String>()
+        .replace('%', "%%");
+    let safe_title = if safe_title.is_empty() {
+        format!("Chapter {}", chapter_index + 1)
     } else {
-        add_log_internal("error", "Download failed", None, Some(&url)).ok();
-        Err("Download failed".to_string())
+        safe_title
+    };
+    format!("%(title)s - {}.%(ext)s", safe_title)
+}
+
+/// Preview what a download would produce without writing anything: resolved
+/// filenames, selected format ids and size estimates for each item.
+#[tauri::command]
+pub async fn simulate_download(
+    app: AppHandle,
+    url: String,
+    output_path: String,
+    quality: String,
+    format: String,
+    video_codec: String,
+    download_playlist: bool,
+    playlist_limit: Option<u32>,
+    format_id: Option<String>,
+    filename_template: Option<String>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<Vec<PlannedDownload>, String> {
+    let output_format: DownloadFormat = format.parse()?;
+    let quality_preset: Quality = quality.parse()?;
+    let sanitized_path = sanitize_output_path(&output_path)?;
+    let filename_template = match filename_template.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(template) => validate_filename_template(template)?,
+        None => DEFAULT_FILENAME_TEMPLATE.to_string(),
+    };
+    let output_template = format!("{}/{}", sanitized_path, filename_template);
+    
+    let options = YtdlpOptions {
+        cookies: resolve_cookie_source(
+            &app,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+        )?,
+        proxy: validate_proxy_url(proxy_url.as_deref())?,
+    };
+    
+    let is_audio_format = output_format.is_audio() || quality_preset == Quality::Audio;
+    let format_string = match format_id.as_deref().map(str::trim).filter(|f| !f.is_empty()) {
+        Some(id) => build_format_id_selector(id, is_audio_format || cached_format_is_audio_only(&url, &options, id)),
+        None => build_format_string(quality_preset, output_format, &video_codec),
+    };
+    let merges_streams = !is_audio_format && format_string.contains('+');
+    
+    let mut args = vec![
+        "--simulate".to_string(),
+        "--no-warnings".to_string(),
+        "-f".to_string(),
+        format_string,
+        "-o".to_string(),
+        output_template,
+        "--print".to_string(),
+        planned_template(PLANNED_FIELDS),
+    ];
+    
+    if merges_streams {
+        args.push("--merge-output-format".to_string());
+        args.push(output_format.to_string());
+    }
+    
+    if !download_playlist {
+        args.push("--no-playlist".to_string());
+    } else if let Some(limit) = playlist_limit.filter(|l| *l > 0) {
+        args.push("--playlist-end".to_string());
+        args.push(limit.to_string());
+    }
+    
+    args.push(url.clone());
+    
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = run_ytdlp_with_stderr_and_options(&app, &args_ref, &options).await?;
+    
+    let planned: Vec<PlannedDownload> = output.stdout.lines().filter_map(parse_planned_download).collect();
+    
+    if planned.is_empty() && !output.success {
+        return Err(parse_ytdlp_error(&output.stderr)
+            .unwrap_or_else(|| format!("Simulation failed: {}", output.stderr.trim())));
+    }
+    
+    Ok(planned)
+}
+
+/// Estimate the total size of downloading a playlist at the given quality/format.
+/// Sums yt-dlp's size for the format each item would select. Exact `filesize` is
+/// rarely reported for YouTube streams, so most items use `filesize_approx`
+/// (bitrate x duration), which is typically within about 10-20% of the real size.
+#[tauri::command]
+pub async fn estimate_playlist_size(
+    app: AppHandle,
+    url: String,
+    quality: String,
+    format: String,
+    limit: Option<u32>,
+    video_codec: Option<String>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<PlaylistSizeEstimate, String> {
+    let output_format: DownloadFormat = format.parse()?;
+    let quality_preset: Quality = quality.parse()?;
+    let video_codec = video_codec.unwrap_or_else(|| "auto".to_string());
+    let format_string = build_format_string(quality_preset, output_format, &video_codec);
+    
+    let mut args = vec![
+        "--simulate".to_string(),
+        "--no-warnings".to_string(),
+        "--ignore-errors".to_string(),
+        "-f".to_string(),
+        format_string,
+        "--print".to_string(),
+        planned_template(PLANNED_SIZE_FIELDS),
+    ];
+    
+    if let Some(limit) = limit.filter(|l| *l > 0) {
+        args.push("--playlist-end".to_string());
+        args.push(limit.to_string());
+    }
+    
+    args.push(url.clone());
+    
+    let options = YtdlpOptions {
+        cookies: resolve_cookie_source(
+            &app,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+        )?,
+        proxy: validate_proxy_url(proxy_url.as_deref())?,
+    };
+    
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = run_ytdlp_with_stderr_and_options(&app, &args_ref, &options).await?;
+    
+    let sizes: Vec<Option<u64>> = output
+        .stdout
+        .lines()
+        .filter_map(|line| line.trim_end().strip_prefix(PLANNED_MARKER)?.strip_prefix('\t'))
+        .map(|size| size.parse::<f64>().ok().map(|size| size as u64))
+        .collect();
+    
+    if sizes.is_empty() {
+        return Err(parse_ytdlp_error(&output.stderr)
+            .unwrap_or_else(|| format!("Failed to estimate playlist size: {}", output.stderr.trim())));
     }
+    
+    Ok(PlaylistSizeEstimate {
+        total_bytes: sizes.iter().flatten().sum(),
+        item_count: sizes.len() as u32,
+        items_without_estimate: sizes.iter().filter(|size| size.is_none()).count() as u32,
+    })
 }
 
 #[tauri::command]
 pub async fn stop_download() -> Result<(), String> {
     CANCEL_FLAG.store(true, Ordering::SeqCst);
+    cancel_batches();
+    drop_paused_downloads();
     kill_all_download_processes();
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     kill_all_download_processes();
     Ok(())
 }
 
+/// Stop every running download. With `cleanup`, also delete the partial files
+/// (`.part`, `.ytdl`, fragments and unmerged `.fNNN` streams) those downloads were writing.
+/// Returns the number of files removed.
+#[tauri::command]
+pub async fn stop_all_downloads(cleanup: bool) -> Result<u32, String> {
+    // Snapshot before cancelling: each download drops its entry as soon as it returns
+    let destinations: Vec<(PathBuf, bool)> = ACTIVE_DESTINATIONS
+        .lock()
+        .map(|active| {
+            active
+                .values()
+                .flat_map(|download| download.destinations.iter().map(|dest| (dest.clone(), download.keep_streams)))
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    // Stop All means stop now, including live recordings that would otherwise finish muxing
+    CANCEL_FLAG.store(true, Ordering::SeqCst);
+    cancel_batches();
+    // Paused downloads are stopped too
+    drop_paused_downloads();
+    force_kill_download_processes();
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    force_kill_download_processes();
+    
+    if !cleanup {
+        return Ok(0);
+    }
+    
+    let removed: u32 = destinations
+        .iter()
+        .map(|(dest, keep_streams)| remove_partial_files(dest, *keep_streams))
+        .sum();
+    if removed > 0 {
+        add_log_internal("info", &format!("Removed {} partial download file(s)", removed), None, None).ok();
+    }
+    Ok(removed)
+}
+
+/// Delete the leftovers of an interrupted download to `dest`. With `keep_streams`
+/// (keep_separate downloads) finished ".fNNN" streams are the output and stay
+fn remove_partial_files(dest: &std::path::Path, keep_streams: bool) -> u32 {
+    let Some(file_name) = dest.file_name().and_then(|n| n.to_str()) else {
+        return 0;
+    };
+    let mut candidates = vec![
+        dest.with_file_name(format!("{}.part", file_name)),
+        dest.with_file_name(format!("{}.ytdl", file_name)),
+    ];
+    // Separate video/audio streams ("title.f137.mp4") are useless once their merge is cancelled
+    if !keep_streams && is_format_stream_file(file_name) {
+        candidates.push(dest.to_path_buf());
+    }
+    // HLS/DASH fragments: "title.mp4.part-Frag12"
+    let fragment_prefix = format!("{}.part-Frag", file_name);
+    if let Some(entries) = dest.parent().and_then(|dir| std::fs::read_dir(dir).ok()) {
+        candidates.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(&fragment_prefix))
+                }),
+        );
+    }
+    
+    candidates
+        .iter()
+        .filter(|path| path.is_file() && std::fs::remove_file(path).is_ok())
+        .count() as u32
+}
+
+/// Whether a file name is a single-format intermediate like "title.f137.mp4"
+fn is_format_stream_file(file_name: &str) -> bool {
+    let Some((stem, _)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    matches!(
+        stem.rsplit_once(".f"),
+        Some((base, id)) if !base.is_empty() && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+    )
+}
+
+/// Marker line printed per item by simulate_download (tab-separated fields)
+const PLANNED_MARKER: &str = "__YOUWEE_PLAN__";
+const PLANNED_FIELDS: &str = "%(title)s\t%(filename)s\t%(format_id)s\t%(filesize,filesize_approx)s";
+/// Size-only variant used by estimate_playlist_size
+const PLANNED_SIZE_FIELDS: &str = "%(filesize,filesize_approx)s";
+
+/// --print template for the planned `fields`, prefixed with PLANNED_MARKER
+fn planned_template(fields: &str) -> String {
+    format!("{}\t{}", PLANNED_MARKER, fields)
+}
+
+/// Parse a line printed with PLANNED_FIELDS
+fn parse_planned_download(line: &str) -> Option<PlannedDownload> {
+    let mut fields = line.trim_end().strip_prefix(PLANNED_MARKER)?.strip_prefix('\t')?.split('\t');
+    let title = fields.next()?.to_string();
+    let filename = fields.next()?.to_string();
+    let format_id = fields.next()?.to_string();
+    // Sizes may be "NA" or a float for approximate values
+    let filesize_estimate = fields.next()
+        .and_then(|size| size.parse::<f64>().ok())
+        .map(|size| size as u64);
+    
+    Some(PlannedDownload {
+        title,
+        filename,
+        format_id,
+        filesize_estimate,
+    })
+}
+
+/// Output template used when no filename_template is given
+const DEFAULT_FILENAME_TEMPLATE: &str = "%(title)s.%(ext)s";
+
+/// Get the named filename template presets
+#[tauri::command]
+pub fn get_filename_presets() -> Vec<FilenamePreset> {
+    [
+        ("default", "Title", DEFAULT_FILENAME_TEMPLATE),
+        ("title_id", "Title [ID]", "%(title)s [%(id)s].%(ext)s"),
+        ("playlist_index", "Playlist index - Title", "%(playlist_index)s - %(title)s.%(ext)s"),
+        ("playlist_folder", "Playlist folder / Index - Title", "%(playlist_title)s/%(playlist_index)s - %(title)s.%(ext)s"),
+        ("uploader_folder", "Uploader folder / Title", "%(uploader)s/%(title)s.%(ext)s"),
+        ("date_title", "Upload date - Title", "%(upload_date>%Y-%m-%d)s - %(title)s.%(ext)s"),
+    ]
+    .into_iter()
+    .map(|(id, label, template)| FilenamePreset {
+        id: id.to_string(),
+        label: label.to_string(),
+        template: template.to_string(),
+    })
+    .collect()
+}
+
+/// Validate a user-supplied output template. It must stay inside the output folder
+/// and keep the `%(ext)s` field so yt-dlp can name merged/converted files.
+fn validate_filename_template(template: &str) -> Result<String, String> {
+    if !template.contains("%(ext)s") {
+        return Err("Filename template must contain %(ext)s".to_string());
+    }
+    // Reject absolute paths, UNC shares and Windows drive paths like C:\Videos
+    let is_drive_path = template.chars().nth(1) == Some(':');
+    if template.starts_with(['/', '\\']) || is_drive_path {
+        return Err("Filename template must be relative to the output folder".to_string());
+    }
+    if template.split(['/', '\\']).any(|part| part == "..") {
+        return Err("Filename template must not contain '..'".to_string());
+    }
+    Ok(template.to_string())
+}
+
+/// Shortest filename length accepted for --trim-filenames (room for a title stem plus extension)
+const MIN_FILENAME_LEN: u32 = 16;
+/// Most filesystems cap a single path component at 255 bytes
+const MAX_FILENAME_LEN: u32 = 255;
+
+/// Validate a playlist item spec of indices and ranges, e.g. "1,3,7-9".
+/// Returns the spec with whitespace removed.
+fn validate_playlist_items(spec: &str) -> Result<String, String> {
+    let normalized: String = spec.chars().filter(|c| !c.is_whitespace()).collect();
+    let invalid = || format!("Invalid playlist items \"{}\": use numbers and ranges like 1,3,7-9", spec.trim());
+    
+    for part in normalized.split(',') {
+        let parse_index = |value: &str| value.parse::<u32>().ok().filter(|n| *n > 0);
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = parse_index(start).zip(parse_index(end)).ok_or_else(invalid)?;
+                if start > end {
+                    return Err(invalid());
+                }
+            }
+            None => {
+                parse_index(part).ok_or_else(invalid)?;
+            }
+        }
+    }
+    
+    Ok(normalized)
+}
+
+/// Upper bound for --concurrent-fragments; more only gets throttled by the server
+const MAX_CONCURRENT_FRAGMENTS: u32 = 16;
+
+fn validate_concurrent_fragments(fragments: u32) -> Result<u32, String> {
+    if (1..=MAX_CONCURRENT_FRAGMENTS).contains(&fragments) {
+        Ok(fragments)
+    } else {
+        Err(format!("Concurrent fragments must be between 1 and {}", MAX_CONCURRENT_FRAGMENTS))
+    }
+}
+
+/// Map an audio bitrate setting to a yt-dlp `--audio-quality` value.
+/// "auto" means best (0); 0-9 are VBR levels; "192K" (or legacy "192") is a bitrate.
+fn validate_audio_bitrate(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("auto") {
+        return Ok("0".to_string());
+    }
+    
+    let digits = value.strip_suffix(['K', 'k']).unwrap_or(value);
+    let kbps: u32 = digits
+        .parse()
+        .map_err(|_| format!("Invalid audio bitrate \"{}\": use 0-9 or a value like 192K", value))?;
+    
+    if digits.len() == value.len() && kbps <= 9 {
+        Ok(kbps.to_string())
+    } else if (8..=512).contains(&kbps) {
+        Ok(format!("{}K", kbps))
+    } else {
+        Err(format!("Invalid audio bitrate \"{}\": bitrate must be between 8K and 512K", value))
+    }
+}
+
+/// Build filename sanitizing args: --restrict-filenames, --windows-filenames and --trim-filenames
+fn build_filename_args(
+    restrict_filenames: bool,
+    windows_safe: bool,
+    max_filename_len: Option<u32>,
+) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    
+    if restrict_filenames {
+        args.push("--restrict-filenames".to_string());
+    }
+    if windows_safe {
+        args.push("--windows-filenames".to_string());
+    }
+    if let Some(len) = max_filename_len {
+        if !(MIN_FILENAME_LEN..=MAX_FILENAME_LEN).contains(&len) {
+            return Err(format!(
+                "Max filename length must be between {} and {} characters",
+                MIN_FILENAME_LEN, MAX_FILENAME_LEN
+            ));
+        }
+        args.push("--trim-filenames".to_string());
+        args.push(len.to_string());
+    }
+    
+    Ok(args)
+}
+
+/// Title from a "[download] Destination: <path>" line, independent of the output template
+fn parse_destination_title(line: &str) -> Option<String> {
+    let (_, path) = line.split_once("[download] Destination:")?;
+    let file_name = path.trim().rsplit(['/', '\\']).next()?;
+    let (stem, _) = file_name.rsplit_once('.')?;
+    // Drop the per-format suffix of intermediate files ("title.f137" -> "title")
+    let stem = match stem.rsplit_once(".f") {
+        Some((base, id)) if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => stem,
+    };
+    Some(stem.to_string())
+}
+
+/// Whether `format_id` is audio-only (vcodec "none") in the cached get_video_info response
+/// for `url` fetched with `options`. False when the video is not cached or does not list the id
+fn cached_format_is_audio_only(url: &str, options: &YtdlpOptions, format_id: &str) -> bool {
+    let Ok(normalized) = normalize_url(url) else {
+        return false;
+    };
+    get_cached_info(&info_cache_key(&normalized.canonical, options)).is_some_and(|response| {
+        response
+            .formats
+            .iter()
+            .any(|f| f.format_id == format_id && f.vcodec.as_deref() == Some("none"))
+    })
+}
+
+/// Build the -f selector for an explicit format id
+/// Video formats get "+bestaudio" so video-only streams still have sound; for formats that
+/// already carry audio yt-dlp keeps a single audio track since multistreams are disabled.
+/// `is_audio` is set for audio output and for audio-only format ids, which are used as-is
+fn build_format_id_selector(format_id: &str, is_audio: bool) -> String {
+    // Audio picks and full selector expressions are used as-is
+    if is_audio || format_id.contains(['+', '/', '[']) {
+        return format_id.to_string();
+    }
+    format!("{}+bestaudio/{}", format_id, format_id)
+}
+
+/// Build the keep_separate -f selector for an explicit format id: "137" downloads 137 and
+/// the best audio, "137+251" downloads exactly those two streams, each to its own file
+fn build_separate_format_id_selector(format_id: &str) -> Result<String, String> {
+    if format_id.contains(['/', '[', ',']) {
+        return Err("Keeping audio and video separate needs a plain format id like \"137\" or \"137+251\"".to_string());
+    }
+    if format_id.contains('+') {
+        Ok(format_id.replace('+', ","))
+    } else {
+        Ok(format!("{},bestaudio", format_id))
+    }
+}
+
+/// Insert the format id before the extension of an output template ("%(title)s.%(ext)s" ->
+/// "%(title)s.f%(format_id)s.%(ext)s"), the same naming yt-dlp uses for unmerged streams
+fn separate_stream_template(template: &str) -> String {
+    match template.strip_suffix(".%(ext)s") {
+        Some(base) => format!("{}.f%(format_id)s.%(ext)s", base),
+        None => format!("{}.f%(format_id)s", template),
+    }
+}
+
+/// --write-info-json/--write-description args. yt-dlp names these files after the main
+/// output template, so they land next to the media with its extension replaced
+fn build_metadata_file_args(write_info_json: bool, write_description: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    if write_info_json {
+        args.push("--write-info-json".to_string());
+    }
+    if write_description {
+        args.push("--write-description".to_string());
+    }
+    args
+}
+
+/// The .info.json/.description files that exist next to the downloaded media files
+fn existing_sidecar_files(files: &[String], write_info_json: bool, write_description: bool) -> Vec<String> {
+    let extensions = [("info.json", write_info_json), ("description", write_description)];
+    let mut sidecars: Vec<String> = Vec::new();
+    for file in files {
+        for (extension, enabled) in extensions {
+            let path = std::path::Path::new(file).with_extension(extension);
+            let path = path.to_string_lossy().to_string();
+            if enabled && !sidecars.contains(&path) && std::path::Path::new(&path).exists() {
+                sidecars.push(path);
+            }
+        }
+    }
+    sidecars
+}
+
+/// Playlist subfolder; `S` sanitizes the title as a filename (no path separators or reserved characters)
+const PLAYLIST_FOLDER_TEMPLATE: &str = "%(playlist_title,playlist_id|Playlist)S";
+/// File name inside a playlist folder when no filename_template is given
+const PLAYLIST_ITEM_TEMPLATE: &str = "%(playlist_index)s - %(title)s.%(ext)s";
+
+/// Put an output template inside a folder named after the playlist. The default template
+/// becomes "<index> - <title>"; templates that already use the playlist title are left as they are
+fn playlist_folder_template(template: &str) -> String {
+    if template.contains("%(playlist_title") {
+        return template.to_string();
+    }
+    let item = if template == DEFAULT_FILENAME_TEMPLATE {
+        PLAYLIST_ITEM_TEMPLATE
+    } else {
+        template
+    };
+    format!("{}/{}", PLAYLIST_FOLDER_TEMPLATE, item)
+}
+
+/// Download steps that run through FFmpeg
+#[derive(Clone, Copy)]
+struct FfmpegSteps {
+    extract_audio: bool,
+    /// Separate video and audio streams are merged into one file
+    merge: bool,
+    /// Thumbnail, subtitles, metadata or chapters are embedded
+    embed: bool,
+    remove_segments: bool,
+    clip: bool,
+}
+
+/// The first step that needs FFmpeg, worded for "FFmpeg is required to ...";
+/// None when yt-dlp can write the file on its own
+fn ffmpeg_required_step(steps: &FfmpegSteps) -> Option<&'static str> {
+    if steps.extract_audio {
+        Some("extract audio")
+    } else if steps.merge {
+        Some("merge video and audio")
+    } else if steps.clip {
+        Some("download clips")
+    } else if steps.remove_segments {
+        Some("remove SponsorBlock segments")
+    } else if steps.embed {
+        Some("embed thumbnails, subtitles or metadata")
+    } else {
+        None
+    }
+}
+
+/// Validate (start, end) timestamp pairs and build --download-sections args
+fn build_section_args(sections: &[(String, String)]) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    
+    for (start, end) in sections {
+        let start_secs = parse_timestamp(start)
+            .ok_or_else(|| format!("Invalid section start time: '{}'", start))?;
+        let end_secs = parse_timestamp(end)
+            .ok_or_else(|| format!("Invalid section end time: '{}'", end))?;
+        if start_secs >= end_secs {
+            return Err(format!("Section start ({}) must be before end ({})", start, end));
+        }
+        args.push("--download-sections".to_string());
+        args.push(format!("*{}-{}", start.trim(), end.trim()));
+    }
+    
+    // Re-encode around cut points so clips start cleanly
+    if !args.is_empty() {
+        args.push("--force-keyframes-at-cuts".to_string());
+    }
+    
+    Ok(args)
+}
+
+/// Default download archive for a URL: app-data/archives/<hash of url>.txt
+fn default_archive_path(app: &AppHandle, url: &str) -> Result<PathBuf, String> {
+    use sha2::{Digest, Sha256};
+    
+    let archive_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("archives");
+    std::fs::create_dir_all(&archive_dir)
+        .map_err(|e| format!("Failed to create archive directory: {}", e))?;
+    
+    let hash = hex::encode(Sha256::digest(url.trim().as_bytes()));
+    Ok(archive_dir.join(format!("{}.txt", &hash[..16])))
+}
+
+/// Whether a line is yt-dlp's "[download] File is larger than max-filesize (...). Aborting." notice
+fn is_max_filesize_skip(line: &str) -> bool {
+    line.trim_start().starts_with("[download] File is larger than max-filesize")
+}
+
+/// Parse yt-dlp's "[download] <title> has already been recorded in the archive" line
+fn parse_archive_skip(line: &str) -> Option<String> {
+    line.trim()
+        .strip_prefix("[download] ")?
+        .strip_suffix(" has already been recorded in the archive")
+        .map(|title| title.to_string())
+}
+
+/// Check manually chosen subtitle languages (comma-separated `--sub-langs`) against the
+/// video's subtitle list. Returns the languages that are missing; errors if none are available.
+/// Lists with patterns like "en.*", "all" or "-live_chat" can't be checked and are accepted as-is
+fn check_subtitle_langs(langs: &str, available: &[SubtitleInfo]) -> Result<Vec<String>, String> {
+    let requested: Vec<&str> = langs
+        .split(',')
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty())
+        .collect();
+    let is_plain = |lang: &str| lang != "all" && lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') && !lang.starts_with('-');
+    if requested.is_empty() || !requested.iter().all(|lang| is_plain(lang)) {
+        return Ok(Vec::new());
+    }
+    
+    let missing: Vec<String> = requested
+        .iter()
+        .filter(|lang| !available.iter().any(|sub| sub.lang == **lang))
+        .map(|lang| lang.to_string())
+        .collect();
+    if missing.len() == requested.len() {
+        return Err(format!("No subtitles available in the requested languages: {}", missing.join(", ")));
+    }
+    Ok(missing)
+}
+
+/// SponsorBlock categories accepted by yt-dlp
+const SPONSORBLOCK_CATEGORIES: &[&str] = &[
+    "all", "sponsor", "intro", "outro", "selfpromo", "preview",
+    "filler", "interaction", "music_offtopic", "poi_highlight", "chapter",
+];
+
+/// Build --sponsorblock-mark/--sponsorblock-remove args
+fn build_sponsorblock_args(mode: &SponsorBlockMode) -> Result<Vec<String>, String> {
+    let (flag, categories) = match mode {
+        SponsorBlockMode::Off => return Ok(Vec::new()),
+        SponsorBlockMode::Mark(categories) => ("--sponsorblock-mark", categories),
+        SponsorBlockMode::Remove(categories) => ("--sponsorblock-remove", categories),
+    };
+    
+    for category in categories {
+        if !SPONSORBLOCK_CATEGORIES.contains(&category.as_str()) {
+            return Err(format!("Unknown SponsorBlock category: '{}'", category));
+        }
+    }
+    
+    let value = if categories.is_empty() {
+        "all".to_string()
+    } else {
+        categories.join(",")
+    };
+    
+    Ok(vec![flag.to_string(), value])
+}
+
+/// Marker for the line yt-dlp prints once an item is fully post-processed
+const FINISHED_MARKER: &str = "__YOUWEE_DONE__";
+/// --print fields after the marker: extractor, webpage_url, duration, thumbnail, expected size, channel, filepath, title
+const FINISHED_FIELDS: &str =
+    "%(extractor)s\t%(webpage_url)s\t%(duration)s\t%(thumbnail)s\t%(filesize,filesize_approx)s\t%(channel,uploader)s\t%(filepath)s\t%(title)s";
+/// Field of FINISHED_FIELDS carrying yt-dlp's size estimate for the selected format(s)
+const EXPECTED_SIZE_FIELD: &str = "%(filesize,filesize_approx)s";
+/// A finished file smaller than this fraction of the expected size is reported as suspect.
+/// Muxing, embedding and container overhead move the size a little, never by half
+const MIN_EXPECTED_SIZE_RATIO: f64 = 0.5;
+
+/// The after_move print template. When the output is re-encoded or cut (audio extraction,
+/// sections, SponsorBlock removal) or is a live recording, yt-dlp's estimate says nothing
+/// about the final size, so the expected size is left out and the file is not checked
+fn finished_template(verify_size: bool) -> String {
+    let fields = if verify_size {
+        FINISHED_FIELDS.to_string()
+    } else {
+        FINISHED_FIELDS.replace(EXPECTED_SIZE_FIELD, "NA")
+    };
+    format!("{}\t{}", FINISHED_MARKER, fields)
+}
+
+/// Whether a finished file is far smaller than yt-dlp expected, i.e. likely truncated
+fn is_suspect_size(actual: u64, expected: u64) -> bool {
+    expected > 0 && (actual as f64) < expected as f64 * MIN_EXPECTED_SIZE_RATIO
+}
+
+/// A downloaded item as reported by the after_move print
+struct FinishedItem {
+    extractor: Option<String>,
+    url: Option<String>,
+    duration: Option<u64>,
+    thumbnail: Option<String>,
+    /// yt-dlp's filesize (or filesize_approx) for the selected format(s)
+    expected_size: Option<u64>,
+    /// Channel name, or the uploader on sites without channels
+    channel: Option<String>,
+    filepath: String,
+    title: String,
+}
+
+fn parse_finished_item(line: &str) -> Option<FinishedItem> {
+    let rest = line.trim().strip_prefix(FINISHED_MARKER)?.strip_prefix('\t')?;
+    let mut fields = rest.splitn(8, '\t').map(|f| {
+        let f = f.trim();
+        (!f.is_empty() && f != "NA").then(|| f.to_string())
+    });
+    
+    let extractor = fields.next().flatten();
+    let url = fields.next().flatten();
+    let duration = fields.next().flatten().and_then(|d| d.parse::<f64>().ok()).map(|d| d as u64);
+    let thumbnail = fields.next().flatten();
+    let expected_size = fields.next().flatten().and_then(|s| s.parse::<f64>().ok()).map(|s| s as u64);
+    let channel = fields.next().flatten();
+    let filepath = fields.next().flatten()?;
+    let title = fields.next().flatten().unwrap_or_else(|| {
+        std::path::Path::new(&filepath)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Unknown")
+            .to_string()
+    });
+    
+    Some(FinishedItem { extractor, url, duration, thumbnail, expected_size, channel, filepath, title })
+}
+
+/// Compare a finished file with yt-dlp's expected size and log it when it looks truncated.
+/// Returns true when the file is much smaller than expected (see `is_suspect_size`)
+fn check_finished_size(item: &FinishedItem, url: &str) -> bool {
+    let filesize = std::fs::metadata(&item.filepath).ok().map(|m| m.len());
+    let suspect = match (filesize, item.expected_size) {
+        (Some(actual), Some(expected)) => is_suspect_size(actual, expected),
+        _ => false,
+    };
+    if suspect {
+        let details = format!(
+            "File: {} · Size: {} · Expected: {}",
+            item.filepath,
+            filesize.map(format_size).unwrap_or_default(),
+            item.expected_size.map(format_size).unwrap_or_default(),
+        );
+        add_log_internal("error", &format!("Downloaded file may be incomplete: {}", item.title), Some(&details), Some(url)).ok();
+    }
+    suspect
+}
+
+/// Save a finished item to history with its actual size on disk.
+/// The download id becomes the row id (suffixed with the index for playlist items) so later
+/// summary updates line up; re-downloads update the existing row instead
+fn save_finished_item(
+    item: &FinishedItem,
+    id: &str,
+    url: &str,
+    playlist_index: Option<u32>,
+    history_id: Option<&str>,
+    quality_display: Option<String>,
+    format: &str,
+) {
+    let filesize = std::fs::metadata(&item.filepath).ok().map(|m| m.len());
+    
+    if let Some(hist_id) = history_id {
+        update_history_download(
+            hist_id.to_string(),
+            staged_history_path(id, &item.filepath, None),
+            filesize,
+            quality_display,
+            Some(format.to_string()),
+        ).ok();
+        return;
+    }
+    
+    let item_url = item.url.clone().unwrap_or_else(|| url.to_string());
+    let entry_id = match playlist_index {
+        Some(index) => format!("{}-{}", id, index),
+        None => id.to_string(),
+    };
+    let entry = HistoryEntry {
+        filepath: staged_history_path(id, &item.filepath, Some(&entry_id)),
+        id: entry_id,
+        source: item.extractor.as_deref()
+            .map(source_from_extractor)
+            .or_else(|| detect_source(&item_url)),
+        channel: item.channel.clone(),
+        thumbnail: item.thumbnail.clone().or_else(|| generate_thumbnail_url(&item_url)),
+        url: item_url,
+        title: item.title.clone(),
+        filesize,
+        duration: item.duration,
+        quality: quality_display,
+        format: Some(format.to_string()),
+        downloaded_at: chrono::Utc::now().to_rfc3339(),
+        file_exists: true,
+        summary: None,
+        summary_provider: None,
+        summary_model: None,
+        tags: Vec::new(),
+    };
+    insert_history(&entry).ok();
+}
+
+/// Map a yt-dlp extractor name to the history source filter values
+fn source_from_extractor(extractor: &str) -> String {
+    let name = extractor.split(':').next().unwrap_or_default().to_lowercase();
+    match name.as_str() {
+        "youtube" | "tiktok" | "facebook" | "instagram" | "twitter" => name,
+        _ => "other".to_string(),
+    }
+}
+
 fn detect_source(url: &str) -> Option<String> {
-    if url.contains("youtube.com") || url.contains("youtu.be") {
+    if is_youtube_url(url) {
         Some("youtube".to_string())
     } else if url.contains("tiktok.com") {
         Some("tiktok".to_string())
@@ -715,3 +3005,235 @@ fn generate_thumbnail_url(url: &str) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_subtitle_langs() {
+        let available = vec![
+            SubtitleInfo { lang: "en".to_string(), name: "English".to_string(), is_auto: false },
+            SubtitleInfo { lang: "vi".to_string(), name: "Vietnamese".to_string(), is_auto: true },
+        ];
+        assert!(check_subtitle_langs("en,vi", &available).unwrap().is_empty());
+        assert_eq!(check_subtitle_langs("en, fr", &available).unwrap(), vec!["fr"]);
+        assert!(check_subtitle_langs("fr,de", &available).is_err());
+        // Patterns are left to yt-dlp
+        assert!(check_subtitle_langs("fr.*,de", &available).unwrap().is_empty());
+        assert!(check_subtitle_langs("all,-live_chat", &available).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_overall_percent() {
+        assert_eq!(overall_percent(None, None, 50.0), None);
+        assert_eq!(overall_percent(Some(1), Some(4), 0.0), Some(0.0));
+        assert_eq!(overall_percent(Some(3), Some(4), 50.0), Some(62.5));
+        assert_eq!(overall_percent(Some(4), Some(4), 100.0), Some(100.0));
+        assert_eq!(overall_percent(Some(1), Some(0), 50.0), None);
+    }
+
+    #[test]
+    fn test_filename_args_default_is_empty() {
+        assert!(build_filename_args(false, false, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_filename_args_all_flags() {
+        let args = build_filename_args(true, true, Some(120)).unwrap();
+        assert_eq!(
+            args,
+            vec!["--restrict-filenames", "--windows-filenames", "--trim-filenames", "120"]
+        );
+    }
+
+    #[test]
+    fn test_filename_args_single_flags() {
+        assert_eq!(build_filename_args(true, false, None).unwrap(), vec!["--restrict-filenames"]);
+        assert_eq!(build_filename_args(false, true, None).unwrap(), vec!["--windows-filenames"]);
+        assert_eq!(build_filename_args(false, false, Some(255)).unwrap(), vec!["--trim-filenames", "255"]);
+    }
+
+    #[test]
+    fn test_filename_args_rejects_out_of_range_length() {
+        assert!(build_filename_args(false, false, Some(0)).is_err());
+        assert!(build_filename_args(false, false, Some(MIN_FILENAME_LEN - 1)).is_err());
+        assert!(build_filename_args(false, false, Some(MAX_FILENAME_LEN + 1)).is_err());
+        assert!(build_filename_args(false, false, Some(MIN_FILENAME_LEN)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_url_list_text() {
+        let contents = "# exported links\n\nhttps://youtu.be/dQw4w9WgXcQ?si=x\nnot a url\nhttps://www.youtube.com/watch?v=dQw4w9WgXcQ\nhttps://vimeo.com/12345\n";
+        let imported = parse_url_list(contents, false);
+        
+        assert_eq!(imported.urls, vec![
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://vimeo.com/12345",
+        ]);
+        assert_eq!(imported.rejected, 1);
+    }
+
+    #[test]
+    fn test_parse_url_list_csv() {
+        let contents = "url,title\n\"https://vimeo.com/1\",First\nhttps://vimeo.com/2,Second\n,Missing\n";
+        let imported = parse_url_list(contents, true);
+        
+        assert_eq!(imported.urls, vec!["https://vimeo.com/1", "https://vimeo.com/2"]);
+        assert_eq!(imported.rejected, 1);
+    }
+
+    #[test]
+    fn test_validate_audio_bitrate() {
+        assert_eq!(validate_audio_bitrate("auto").unwrap(), "0");
+        assert_eq!(validate_audio_bitrate("5").unwrap(), "5");
+        assert_eq!(validate_audio_bitrate("192K").unwrap(), "192K");
+        assert_eq!(validate_audio_bitrate("128").unwrap(), "128K");
+        assert!(validate_audio_bitrate("fast").is_err());
+        assert!(validate_audio_bitrate("2000K").is_err());
+    }
+
+    #[test]
+    fn test_validate_concurrent_fragments() {
+        assert_eq!(validate_concurrent_fragments(1).unwrap(), 1);
+        assert_eq!(validate_concurrent_fragments(MAX_CONCURRENT_FRAGMENTS).unwrap(), MAX_CONCURRENT_FRAGMENTS);
+        assert!(validate_concurrent_fragments(0).is_err());
+        assert!(validate_concurrent_fragments(MAX_CONCURRENT_FRAGMENTS + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_playlist_items() {
+        assert_eq!(validate_playlist_items("1,3,7-9").unwrap(), "1,3,7-9");
+        assert_eq!(validate_playlist_items(" 5 - 10 ").unwrap(), "5-10");
+        assert!(validate_playlist_items("0").is_err());
+        assert!(validate_playlist_items("9-7").is_err());
+        assert!(validate_playlist_items("1,,2").is_err());
+        assert!(validate_playlist_items("1-").is_err());
+        assert!(validate_playlist_items("a-b").is_err());
+    }
+
+    #[test]
+    fn test_is_format_stream_file() {
+        assert!(is_format_stream_file("My Video.f137.mp4"));
+        assert!(is_format_stream_file("clip.f251.webm"));
+        assert!(!is_format_stream_file("My Video.mp4"));
+        assert!(!is_format_stream_file("podcast.flac"));
+        assert!(!is_format_stream_file("notes.f.txt"));
+    }
+
+    #[test]
+    fn test_parse_finished_item_expected_size() {
+        let line = format!("{}\tyoutube\thttps://youtu.be/x\t12.5\tNA\t1048576\tSome Channel\t/tmp/a.mp4\tTab\tTitle", FINISHED_MARKER);
+        let item = parse_finished_item(&line).unwrap();
+        assert_eq!(item.expected_size, Some(1048576));
+        assert_eq!(item.channel.as_deref(), Some("Some Channel"));
+        assert_eq!(item.duration, Some(12));
+        assert_eq!(item.filepath, "/tmp/a.mp4");
+        assert_eq!(item.title, "Tab\tTitle");
+
+        let unverified = finished_template(false);
+        assert!(!unverified.contains(EXPECTED_SIZE_FIELD));
+        assert_eq!(unverified.split('\t').count(), finished_template(true).split('\t').count());
+    }
+
+    #[test]
+    fn test_parse_planned_download() {
+        assert!(planned_template(PLANNED_FIELDS).starts_with(PLANNED_MARKER));
+        let line = format!("{}\tMy Video\t/tmp/My Video.mp4\t137+140\t12345.6\n", PLANNED_MARKER);
+        let planned = parse_planned_download(&line).unwrap();
+        assert_eq!(planned.title, "My Video");
+        assert_eq!(planned.filename, "/tmp/My Video.mp4");
+        assert_eq!(planned.format_id, "137+140");
+        assert_eq!(planned.filesize_estimate, Some(12345));
+
+        let unknown_size = format!("{}\tLive\t/tmp/Live.mp4\t96\tNA", PLANNED_MARKER);
+        assert_eq!(parse_planned_download(&unknown_size).unwrap().filesize_estimate, None);
+        assert!(parse_planned_download("[download] Destination: /tmp/My Video.mp4").is_none());
+    }
+
+    #[test]
+    fn test_format_id_selector() {
+        assert_eq!(build_format_id_selector("137", false), "137+bestaudio/137");
+        assert_eq!(build_format_id_selector("251", true), "251");
+        assert_eq!(build_format_id_selector("137+251", false), "137+251");
+        assert!(!cached_format_is_audio_only("https://example.com/not-cached", &YtdlpOptions::default(), "251"));
+    }
+
+    #[test]
+    fn test_separate_stream_selectors() {
+        assert_eq!(build_separate_format_id_selector("137").unwrap(), "137,bestaudio");
+        assert_eq!(build_separate_format_id_selector("137+251").unwrap(), "137,251");
+        assert!(build_separate_format_id_selector("137+bestaudio/best").is_err());
+        assert_eq!(separate_stream_template("%(title)s.%(ext)s"), "%(title)s.f%(format_id)s.%(ext)s");
+    }
+
+    #[test]
+    fn test_remove_partial_files_keeps_separate_streams() {
+        let dir = std::env::temp_dir().join(format!("youwee_partial_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stream = dir.join("My Video.f137.mp4");
+        let part = dir.join("My Video.f137.mp4.part");
+
+        std::fs::write(&stream, "video").unwrap();
+        std::fs::write(&part, "partial").unwrap();
+        assert_eq!(remove_partial_files(&stream, true), 1);
+        assert!(stream.exists());
+        assert!(!part.exists());
+
+        assert_eq!(remove_partial_files(&stream, false), 1);
+        assert!(!stream.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_metadata_file_args() {
+        assert!(build_metadata_file_args(false, false).is_empty());
+        assert_eq!(build_metadata_file_args(true, false), vec!["--write-info-json"]);
+        assert_eq!(build_metadata_file_args(true, true), vec!["--write-info-json", "--write-description"]);
+    }
+
+    #[test]
+    fn test_existing_sidecar_files() {
+        let dir = std::env::temp_dir().join(format!("youwee_sidecar_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let media = dir.join("My.Video.mp4");
+        std::fs::write(dir.join("My.Video.info.json"), "{}").unwrap();
+        let files = vec![media.to_string_lossy().to_string()];
+
+        let sidecars = existing_sidecar_files(&files, true, true);
+        assert_eq!(sidecars, vec![dir.join("My.Video.info.json").to_string_lossy().to_string()]);
+        assert!(existing_sidecar_files(&files, false, true).is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_playlist_folder_template() {
+        assert_eq!(
+            playlist_folder_template(DEFAULT_FILENAME_TEMPLATE),
+            "%(playlist_title,playlist_id|Playlist)S/%(playlist_index)s - %(title)s.%(ext)s"
+        );
+        assert_eq!(
+            playlist_folder_template("%(title)s [%(id)s].%(ext)s"),
+            "%(playlist_title,playlist_id|Playlist)S/%(title)s [%(id)s].%(ext)s"
+        );
+        let preset = "%(playlist_title)s/%(playlist_index)s - %(title)s.%(ext)s";
+        assert_eq!(playlist_folder_template(preset), preset);
+    }
+
+    #[test]
+    fn test_ffmpeg_required_step() {
+        let steps = FfmpegSteps { extract_audio: false, merge: false, embed: false, remove_segments: false, clip: false };
+        assert_eq!(ffmpeg_required_step(&steps), None);
+        assert_eq!(ffmpeg_required_step(&FfmpegSteps { merge: true, ..steps }), Some("merge video and audio"));
+        assert_eq!(ffmpeg_required_step(&FfmpegSteps { clip: true, embed: true, ..steps }), Some("download clips"));
+        assert_eq!(ffmpeg_required_step(&FfmpegSteps { extract_audio: true, embed: true, ..steps }), Some("extract audio"));
+    }
+
+    #[test]
+    fn test_is_suspect_size() {
+        assert!(is_suspect_size(400, 1000));
+        assert!(!is_suspect_size(500, 1000));
+        assert!(!is_suspect_size(1200, 1000));
+        assert!(!is_suspect_size(0, 0));
+    }
+}
@@ -1,8 +1,10 @@
-use crate::types::HistoryEntry;
+use crate::types::{DownloadStats, HistoryEntry, HistoryPage};
 use crate::database::{
     add_history_internal, get_history_from_db, delete_history_from_db,
     clear_history_from_db, get_history_count_from_db, update_history_summary,
-    add_history_with_summary
+    add_history_with_summary, search_history_from_db, export_history_to_file,
+    add_history_tag_in_db, remove_history_tag_in_db, get_history_by_tag_from_db,
+    get_download_stats_from_db
 };
 
 #[tauri::command]
@@ -20,9 +22,15 @@ pub fn add_history(
     add_history_internal(url, title, thumbnail, filepath, filesize, duration, quality, format, source)
 }
 
+/// Store a summary; provider/model identify the AI that generated it, if any
 #[tauri::command]
-pub fn update_summary(id: String, summary: String) -> Result<(), String> {
-    update_history_summary(id, summary)
+pub fn update_summary(
+    id: String,
+    summary: String,
+    summary_provider: Option<String>,
+    summary_model: Option<String>,
+) -> Result<(), String> {
+    update_history_summary(id, summary, summary_provider, summary_model)
 }
 
 /// Add a summary-only history entry (for videos summarized without downloading)
@@ -34,8 +42,34 @@ pub fn add_summary_only_history(
     duration: Option<u64>,
     source: Option<String>,
     summary: String,
+    summary_provider: Option<String>,
+    summary_model: Option<String>,
 ) -> Result<String, String> {
-    add_history_with_summary(url, title, thumbnail, duration, source, summary)
+    add_history_with_summary(url, title, thumbnail, duration, source, summary, summary_provider, summary_model)
+}
+
+/// Tag a history entry; returns the entry's tags
+#[tauri::command]
+pub fn add_history_tag(id: String, tag: String) -> Result<Vec<String>, String> {
+    add_history_tag_in_db(&id, &tag)
+}
+
+/// Remove a tag from a history entry; returns the entry's remaining tags
+#[tauri::command]
+pub fn remove_history_tag(id: String, tag: String) -> Result<Vec<String>, String> {
+    remove_history_tag_in_db(&id, &tag)
+}
+
+/// History entries with the given tag, newest first
+#[tauri::command]
+pub fn get_history_by_tag(tag: String) -> Result<Vec<HistoryEntry>, String> {
+    get_history_by_tag_from_db(&tag)
+}
+
+/// Export the whole history to `path` as "json" or "csv"; returns the number of entries written
+#[tauri::command]
+pub fn export_history(path: String, format: String) -> Result<usize, String> {
+    export_history_to_file(&path, &format)
 }
 
 #[tauri::command]
@@ -47,6 +81,17 @@ pub fn get_history(
     get_history_from_db(limit, offset, source)
 }
 
+/// Get a page of download history, optionally filtered by title/URL
+#[tauri::command]
+pub fn get_download_history(
+    limit: u32,
+    offset: u32,
+    query: Option<String>,
+) -> Result<HistoryPage, String> {
+    let (entries, total) = search_history_from_db(limit, offset, query)?;
+    Ok(HistoryPage { entries, total })
+}
+
 #[tauri::command]
 pub fn delete_history(id: String) -> Result<(), String> {
     delete_history_from_db(id)
@@ -62,6 +107,12 @@ pub fn get_history_count() -> Result<i64, String> {
     get_history_count_from_db()
 }
 
+/// Totals, format/source/channel counts and daily downloads for the statistics view
+#[tauri::command]
+pub fn get_download_stats() -> Result<DownloadStats, String> {
+    get_download_stats_from_db()
+}
+
 #[tauri::command]
 pub fn check_file_exists(filepath: String) -> bool {
     std::path::Path::new(&filepath).exists()
@@ -69,39 +120,70 @@ pub fn check_file_exists(filepath: String) -> bool {
 
 #[tauri::command]
 pub async fn open_file_location(filepath: String) -> Result<(), String> {
-    let path = std::path::Path::new(&filepath);
-    
+    reveal_in_folder(filepath).await
+}
+
+/// Validate a downloaded file path before handing it to the OS, rejecting anything
+/// that is not an existing absolute path or that resolves to a filesystem root
+fn validate_download_path(path: &str) -> Result<std::path::PathBuf, String> {
+    let path = std::path::Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err("Path must be absolute".to_string());
+    }
     if !path.exists() {
         return Err("File not found".to_string());
     }
-    
+    // Only validate against the canonical form: on Windows it is a `\\?\` path Explorer rejects
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+    if resolved.parent().is_none() {
+        return Err("Refusing to open a filesystem root".to_string());
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Open a downloaded file with the OS default application
+#[tauri::command]
+pub async fn open_download_location(path: String) -> Result<(), String> {
+    let path = validate_download_path(&path)?;
+    tauri_plugin_opener::open_path(&path, None::<&str>)
+        .map_err(|e| format!("Failed to open file: {}", e))
+}
+
+/// Show a downloaded file in the system file manager, selecting it where the platform supports that
+#[tauri::command]
+pub async fn reveal_in_folder(path: String) -> Result<(), String> {
+    let path = validate_download_path(&path)?;
+
     #[cfg(target_os = "macos")]
     {
         tokio::process::Command::new("open")
             .arg("-R")
-            .arg(&filepath)
+            .arg(&path)
             .spawn()
             .map_err(|e| format!("Failed to open location: {}", e))?;
     }
-    
+
     #[cfg(target_os = "windows")]
     {
         tokio::process::Command::new("explorer")
             .arg("/select,")
-            .arg(&filepath)
+            .arg(&path)
             .spawn()
             .map_err(|e| format!("Failed to open location: {}", e))?;
     }
-    
+
     #[cfg(target_os = "linux")]
     {
-        let dir = path.parent().unwrap_or(path);
+        // xdg-open cannot select a file, so open the containing folder instead
+        let dir = if path.is_dir() { path.as_path() } else { path.parent().unwrap_or(&path) };
         tokio::process::Command::new("xdg-open")
             .arg(dir)
             .spawn()
             .map_err(|e| format!("Failed to open location: {}", e))?;
     }
-    
+
     Ok(())
 }
 
@@ -11,6 +11,7 @@ use rusqlite::params;
 
 use crate::database::get_db;
 use crate::services::{get_ffmpeg_path, generate_raw, AIConfig};
+use crate::utils::parse_timestamp;
 
 // Store for active processing jobs
 static ACTIVE_JOBS: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> = 
@@ -911,6 +912,502 @@ pub async fn cancel_ffmpeg(job_id: String) -> Result<(), String> {
     }
 }
 
+/// Run FFmpeg with `-progress` output, emitting `processing-progress` events for `job_id`.
+/// The job can be cancelled with `cancel_ffmpeg`; a cancelled or failed run removes the output file.
+async fn run_ffmpeg_job(
+    app: &AppHandle,
+    job_id: &str,
+    args: &[String],
+    total_duration_secs: f64,
+    output_path: &str,
+) -> Result<(), String> {
+    run_ffmpeg_passes(app, job_id, &[FfmpegPass { args, output_path }], total_duration_secs).await
+}
+
+/// One FFmpeg run of a multi-pass job and the file it writes
+struct FfmpegPass<'a> {
+    args: &'a [String],
+    output_path: &'a str,
+}
+
+/// Run `passes` one after another as a single cancellable job; progress is split evenly
+/// between them. Stops at the first pass that fails or is cancelled.
+async fn run_ffmpeg_passes(
+    app: &AppHandle,
+    job_id: &str,
+    passes: &[FfmpegPass<'_>],
+    total_duration_secs: f64,
+) -> Result<(), String> {
+    let ffmpeg_path = get_ffmpeg_path(app).await
+        .ok_or("FFmpeg not found. Please install FFmpeg from Settings.")?;
+    
+    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    {
+        // Job ids come from the frontend; replacing a running job's sender would orphan it
+        let mut jobs = ACTIVE_JOBS.lock().await;
+        if jobs.contains_key(job_id) {
+            return Err(format!("Job {} is already running", job_id));
+        }
+        jobs.insert(job_id.to_string(), cancel_tx);
+    }
+    
+    let mut result = Ok(());
+    for (pass_index, pass) in passes.iter().enumerate() {
+        result = run_ffmpeg_pass(
+            app,
+            &ffmpeg_path,
+            job_id,
+            pass,
+            total_duration_secs,
+            (pass_index, passes.len()),
+            &mut cancel_rx,
+        ).await;
+        if result.is_err() {
+            break;
+        }
+    }
+    
+    // A cancelled job was already removed by cancel_ffmpeg and its id may be reused by now;
+    // only remove the entry whose receiver is this job's
+    drop(cancel_rx);
+    {
+        let mut jobs = ACTIVE_JOBS.lock().await;
+        if jobs.get(job_id).is_some_and(|tx| tx.is_closed()) {
+            jobs.remove(job_id);
+        }
+    }
+    result?;
+    
+    app.emit("processing-progress", ProcessingProgress {
+        job_id: job_id.to_string(),
+        percent: 100.0,
+        frame: 0,
+        total_frames: 0,
+        fps: 0.0,
+        speed: "done".to_string(),
+        time: "".to_string(),
+        size: "".to_string(),
+    }).ok();
+    Ok(())
+}
+
+/// Run a single pass of a job until it exits or `cancel_rx` fires
+async fn run_ffmpeg_pass(
+    app: &AppHandle,
+    ffmpeg_path: &Path,
+    job_id: &str,
+    pass: &FfmpegPass<'_>,
+    total_duration_secs: f64,
+    (pass_index, pass_count): (usize, usize),
+    cancel_rx: &mut tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), String> {
+    let mut child = Command::new(ffmpeg_path)
+        .args(["-hide_banner", "-nostats", "-progress", "pipe:2", "-y"])
+        .args(pass.args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start FFmpeg: {}", e))?;
+    
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let mut reader = BufReader::new(stderr).lines();
+    let app_clone = app.clone();
+    let job_id_clone = job_id.to_string();
+    
+    // Progress parsing task; returns the last error-looking lines for the failure message
+    let progress_task = tokio::spawn(async move {
+        let mut progress = ProcessingProgress {
+            job_id: job_id_clone,
+            percent: 0.0,
+            frame: 0,
+            total_frames: 0,
+            fps: 0.0,
+            speed: String::new(),
+            time: String::new(),
+            size: String::new(),
+        };
+        let mut error_lines: Vec<String> = Vec::new();
+        
+        while let Ok(Some(line)) = reader.next_line().await {
+            let Some((key, value)) = line.split_once('=') else {
+                error_lines.push(line);
+                continue;
+            };
+            let value = value.trim();
+            
+            match key {
+                "frame" => progress.frame = value.parse().unwrap_or(progress.frame),
+                "fps" => progress.fps = value.parse().unwrap_or(progress.fps),
+                "speed" => progress.speed = value.to_string(),
+                "total_size" => {
+                    let bytes: i64 = value.parse().unwrap_or(0);
+                    progress.size = format!("{:.1} MB", bytes as f64 / 1_000_000.0);
+                }
+                "out_time" => {
+                    // "00:01:05.050000" -> "00:01:05"
+                    progress.time = value.split('.').next().unwrap_or(value).to_string();
+                    if let Some(secs) = parse_timestamp(value) {
+                        if total_duration_secs > 0.0 {
+                            let pass_fraction = (secs / total_duration_secs).clamp(0.0, 1.0);
+                            progress.percent = (pass_index as f64 + pass_fraction) / pass_count as f64 * 100.0;
+                        }
+                    }
+                }
+                "progress" => {
+                    app_clone.emit("processing-progress", &progress).ok();
+                }
+                _ => {}
+            }
+        }
+        
+        error_lines
+    });
+    
+    tokio::select! {
+        status = child.wait() => {
+            let error_lines = progress_task.await.unwrap_or_default();
+            
+            match status {
+                Ok(exit_status) if exit_status.success() => Ok(()),
+                Ok(exit_status) => {
+                    tokio::fs::remove_file(pass.output_path).await.ok();
+                    let detail = error_lines.last().map(|l| format!(": {}", l.trim())).unwrap_or_default();
+                    Err(format!("FFmpeg exited with code {:?}{}", exit_status.code(), detail))
+                }
+                Err(e) => {
+                    tokio::fs::remove_file(pass.output_path).await.ok();
+                    Err(format!("FFmpeg process error: {}", e))
+                }
+            }
+        }
+        Ok(()) = &mut *cancel_rx => {
+            child.kill().await.ok();
+            progress_task.abort();
+            tokio::fs::remove_file(pass.output_path).await.ok();
+            Err("Processing cancelled".to_string())
+        }
+    }
+}
+
+/// Resolve the output file for a job: `output_path` may be a file path or a directory,
+/// in which case the file is named after the input with `suffix` and the new extension.
+fn resolve_output_file(input_path: &str, output_path: &str, suffix: &str, ext: &str) -> Result<String, String> {
+    let input = Path::new(input_path);
+    if !input.is_file() {
+        return Err(format!("Input file not found: {}", input_path));
+    }
+    
+    let output = Path::new(output_path);
+    let output = if output.is_dir() {
+        let stem = input.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "output".to_string());
+        output.join(format!("{}{}.{}", stem, suffix, ext))
+    } else {
+        output.to_path_buf()
+    };
+    
+    if output == input {
+        return Err("Output file must be different from the input file".to_string());
+    }
+    
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// FFmpeg codec arguments for converting to a target container/format
+fn conversion_codec_args(output_format: &str) -> Option<&'static [&'static str]> {
+    let args: &'static [&'static str] = match output_format {
+        // Matroska holds any codec, so streams are copied untouched
+        "mkv" => &["-map", "0", "-c", "copy"],
+        "mp4" | "mov" => &["-c:v", "libx264", "-preset", "medium", "-crf", "20", "-c:a", "aac", "-b:a", "192k", "-movflags", "+faststart"],
+        "webm" => &["-c:v", "libvpx-vp9", "-crf", "32", "-b:v", "0", "-c:a", "libopus", "-b:a", "128k"],
+        "mp3" => &["-vn", "-c:a", "libmp3lame", "-q:a", "2"],
+        "m4a" => &["-vn", "-c:a", "aac", "-b:a", "192k"],
+        "opus" => &["-vn", "-c:a", "libopus", "-b:a", "128k"],
+        "flac" => &["-vn", "-c:a", "flac"],
+        "wav" => &["-vn", "-c:a", "pcm_s16le"],
+        _ => return None,
+    };
+    Some(args)
+}
+
+/// Convert an already-downloaded file to another format (e.g. mp4 -> mkv, m4a -> mp3).
+/// Progress is emitted as `processing-progress` keyed by `id`. Returns the output path.
+#[tauri::command]
+pub async fn convert_media(
+    app: AppHandle,
+    input_path: String,
+    output_format: String,
+    output_path: String,
+    id: Option<String>,
+) -> Result<String, String> {
+    let output_format = output_format.trim().to_lowercase();
+    let codec_args = conversion_codec_args(&output_format)
+        .ok_or_else(|| format!("Unsupported output format: {}", output_format))?;
+    
+    let output_file = resolve_output_file(&input_path, &output_path, "", &output_format)?;
+    let job_id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    
+    // Duration is only needed for the progress percentage
+    let total_duration = get_video_metadata(app.clone(), input_path.clone()).await
+        .map(|m| m.duration)
+        .unwrap_or(0.0);
+    
+    let mut args = vec!["-i".to_string(), input_path.clone()];
+    args.extend(codec_args.iter().map(|s| s.to_string()));
+    args.push(output_file.clone());
+    
+    run_ffmpeg_job(&app, &job_id, &args, total_duration, &output_file).await?;
+    
+    Ok(output_file)
+}
+
+/// Codecs ffprobe reports for embedded cover art, which is not a real video stream
+const COVER_ART_CODECS: &[&str] = &["mjpeg", "png"];
+
+fn has_video_stream(meta: &VideoMetadata) -> bool {
+    meta.video_codec != "none" && !COVER_ART_CODECS.contains(&meta.video_codec.as_str())
+}
+
+fn file_extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Whether the concat demuxer can join `inputs` with stream copy into a `.{output_ext}` file:
+/// same container and codecs, and the same frame size for video
+fn can_stream_copy(inputs: &[VideoMetadata], output_ext: &str) -> bool {
+    let Some(first) = inputs.first() else {
+        return false;
+    };
+    inputs.iter().all(|m| {
+        file_extension(&m.path) == output_ext
+            && m.format == first.format
+            && m.video_codec == first.video_codec
+            && m.audio_codec == first.audio_codec
+            && m.has_audio == first.has_audio
+            && m.width == first.width
+            && m.height == first.height
+    })
+}
+
+/// Concat demuxer list file: one `file '<path>'` line per input.
+/// Single quotes in paths are closed, escaped and reopened as `'\''`
+fn concat_list_content(paths: &[String]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("file '{}'\n", p.replace('\'', "'\\''")))
+        .collect()
+}
+
+/// FFmpeg codec arguments for re-encoding joined inputs to a target format
+fn concat_codec_args(output_format: &str, has_video: bool) -> Option<&'static [&'static str]> {
+    match (has_video, output_format) {
+        // The filter graph output can't be stream copied, so Matroska gets H.264/AAC too
+        (true, "mkv") => Some(&["-c:v", "libx264", "-preset", "medium", "-crf", "20", "-c:a", "aac", "-b:a", "192k"]),
+        (true, "mp4" | "mov" | "webm") | (false, "mp3" | "m4a" | "opus" | "flac" | "wav") => conversion_codec_args(output_format),
+        _ => None,
+    }
+}
+
+/// Join already-downloaded files into one, in the given order (e.g. the parts of a split stream).
+/// When every input shares the output's container and codecs they are joined with the concat
+/// demuxer without re-encoding; otherwise they are re-encoded, scaling video to the first input's size.
+/// `output_path` may be a file or a directory, in which case the file is named after the first input.
+/// Progress over the total duration is emitted as `processing-progress` keyed by `id`. Returns the output path.
+#[tauri::command]
+pub async fn concat_media(
+    app: AppHandle,
+    input_paths: Vec<String>,
+    output_path: String,
+    id: Option<String>,
+) -> Result<String, String> {
+    if input_paths.len() < 2 {
+        return Err("Select at least two files to join".to_string());
+    }
+    
+    let first_ext = file_extension(&input_paths[0]);
+    let output_file = resolve_output_file(&input_paths[0], &output_path, "_joined", &first_ext)?;
+    if input_paths.iter().any(|p| Path::new(p) == Path::new(&output_file)) {
+        return Err("Output file must be different from the input files".to_string());
+    }
+    let output_ext = file_extension(&output_file);
+    
+    let mut inputs = Vec::with_capacity(input_paths.len());
+    for path in &input_paths {
+        if !Path::new(path).is_file() {
+            return Err(format!("Input file not found: {}", path));
+        }
+        let meta = get_video_metadata(app.clone(), path.clone()).await
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        inputs.push(meta);
+    }
+    let total_duration: f64 = inputs.iter().map(|m| m.duration).sum();
+    let job_id = id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    
+    if can_stream_copy(&inputs, &output_ext) {
+        let list_path = std::env::temp_dir().join(format!("youwee_concat_{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&list_path, concat_list_content(&input_paths))
+            .map_err(|e| format!("Failed to write concat list: {}", e))?;
+        
+        // -safe 0 allows absolute paths in the list
+        let args = vec![
+            "-f".to_string(), "concat".to_string(),
+            "-safe".to_string(), "0".to_string(),
+            "-i".to_string(), list_path.to_string_lossy().to_string(),
+            "-map".to_string(), "0".to_string(),
+            "-c".to_string(), "copy".to_string(),
+            output_file.clone(),
+        ];
+        let result = run_ffmpeg_job(&app, &job_id, &args, total_duration, &output_file).await;
+        std::fs::remove_file(&list_path).ok();
+        result?;
+        return Ok(output_file);
+    }
+    
+    // Mixed codecs or frame sizes can't go through the concat demuxer, so decode each input
+    // separately and join them with the concat filter
+    let has_video = inputs.iter().any(has_video_stream);
+    if has_video && !inputs.iter().all(has_video_stream) {
+        return Err("Can't join video files with audio-only files".to_string());
+    }
+    let has_audio = inputs.iter().any(|m| m.has_audio);
+    if has_audio && !inputs.iter().all(|m| m.has_audio) {
+        return Err("Can't join files with and without audio".to_string());
+    }
+    let codec_args = concat_codec_args(&output_ext, has_video)
+        .ok_or_else(|| format!("Unsupported output format for joining: {}", output_ext))?;
+    
+    let mut args = Vec::new();
+    for path in &input_paths {
+        args.extend(["-i".to_string(), path.clone()]);
+    }
+    
+    let mut filter = String::new();
+    let mut concat_inputs = String::new();
+    for index in 0..inputs.len() {
+        if has_video {
+            // Letterbox every input into the first one's frame size
+            let (width, height) = (inputs[0].width, inputs[0].height);
+            filter.push_str(&format!(
+                "[{index}:v:0]scale={width}:{height}:force_original_aspect_ratio=decrease,pad={width}:{height}:(ow-iw)/2:(oh-ih)/2,setsar=1[v{index}];"
+            ));
+            concat_inputs.push_str(&format!("[v{}]", index));
+        }
+        if has_audio {
+            concat_inputs.push_str(&format!("[{}:a:0]", index));
+        }
+    }
+    filter.push_str(&format!(
+        "{}concat=n={}:v={}:a={}",
+        concat_inputs,
+        inputs.len(),
+        has_video as u8,
+        has_audio as u8
+    ));
+    if has_video {
+        filter.push_str("[v]");
+    }
+    if has_audio {
+        filter.push_str("[a]");
+    }
+    
+    args.extend(["-filter_complex".to_string(), filter]);
+    if has_video {
+        args.extend(["-map".to_string(), "[v]".to_string()]);
+    }
+    if has_audio {
+        args.extend(["-map".to_string(), "[a]".to_string()]);
+    }
+    args.extend(codec_args.iter().map(|s| s.to_string()));
+    args.push(output_file.clone());
+    
+    run_ffmpeg_job(&app, &job_id, &args, total_duration, &output_file).await?;
+    
+    Ok(output_file)
+}
+
+/// Maximum width and frame rate for exported GIFs
+const GIF_MAX_WIDTH: u32 = 480;
+const GIF_FPS: u32 = 15;
+
+/// Export a clip between `start` and `end` as a GIF or MP4.
+/// GIFs use a generated palette (palettegen + paletteuse) at a capped width and frame rate.
+/// Progress is emitted as `processing-progress` keyed by `id`. Returns the output path.
+#[tauri::command]
+pub async fn export_clip(
+    app: AppHandle,
+    id: String,
+    input_path: String,
+    start: String,
+    end: String,
+    output_format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let start_secs = parse_timestamp(&start)
+        .ok_or_else(|| format!("Invalid start time: {}", start))?;
+    let end_secs = parse_timestamp(&end)
+        .ok_or_else(|| format!("Invalid end time: {}", end))?;
+    if start_secs >= end_secs {
+        return Err("Start time must be before end time".to_string());
+    }
+    let clip_duration = end_secs - start_secs;
+    
+    let output_format = output_format.trim().to_lowercase();
+    if output_format != "gif" && output_format != "mp4" {
+        return Err(format!("Unsupported clip format: {} (use gif or mp4)", output_format));
+    }
+    
+    let output_file = resolve_output_file(&input_path, &output_path, "_clip", &output_format)?;
+    
+    // Seek before -i for speed; -t keeps the clip length exact
+    let mut args = vec![
+        "-ss".to_string(), format!("{:.3}", start_secs),
+        "-t".to_string(), format!("{:.3}", clip_duration),
+        "-i".to_string(), input_path.clone(),
+    ];
+    
+    let palette_path = std::env::temp_dir()
+        .join(format!("youwee_palette_{}.png", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .to_string();
+    let mut palette_args = Vec::new();
+    
+    if output_format == "gif" {
+        let filters = format!("fps={},scale='min({},iw)':-1:flags=lanczos", GIF_FPS, GIF_MAX_WIDTH);
+        
+        // First pass: build an optimized palette for this clip
+        palette_args = args.clone();
+        palette_args.extend(["-vf".to_string(), format!("{},palettegen", filters), palette_path.clone()]);
+        
+        // Second pass: render the GIF using the palette
+        args.extend([
+            "-i".to_string(), palette_path.clone(),
+            "-lavfi".to_string(), format!("{} [x]; [x][1:v] paletteuse", filters),
+        ]);
+    } else {
+        args.extend([
+            "-c:v", "libx264", "-preset", "medium", "-crf", "20",
+            "-c:a", "aac", "-b:a", "192k",
+            "-movflags", "+faststart",
+        ].map(String::from));
+    }
+    args.push(output_file.clone());
+    
+    // Both passes run under the clip's job id so cancel_ffmpeg stops either one
+    let render = FfmpegPass { args: &args, output_path: &output_file };
+    let result = if palette_args.is_empty() {
+        run_ffmpeg_passes(&app, &id, &[render], clip_duration).await
+    } else {
+        let palette = FfmpegPass { args: &palette_args, output_path: &palette_path };
+        run_ffmpeg_passes(&app, &id, &[palette, render], clip_duration).await
+    };
+    std::fs::remove_file(&palette_path).ok();
+    result?;
+    
+    Ok(output_file)
+}
+
 /// Get processing history from database
 #[tauri::command]
 pub async fn get_processing_history(_app: AppHandle, limit: i32) -> Result<Vec<ProcessingJob>, String> {
@@ -1308,3 +1805,17 @@ pub async fn cleanup_previews(app: AppHandle) -> Result<u32, String> {
     
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat_list_content_escapes_quotes() {
+        let paths = vec!["/tmp/part 1.mp4".to_string(), "/tmp/it's part 2.mp4".to_string()];
+        assert_eq!(
+            concat_list_content(&paths),
+            "file '/tmp/part 1.mp4'\nfile '/tmp/it'\\''s part 2.mp4'\n"
+        );
+    }
+}
@@ -1,16 +1,57 @@
+use std::collections::HashMap;
 use std::process::Stdio;
+use std::sync::LazyLock;
 use std::time::Duration;
-use tauri::AppHandle;
-use tauri_plugin_shell::ShellExt;
+use tauri::{AppHandle, Emitter};
 use tauri_plugin_shell::process::CommandEvent;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::Mutex;
 use tokio::time::timeout;
 use uuid::Uuid;
-use crate::types::{VideoInfo, FormatOption, VideoInfoResponse, PlaylistVideoEntry, SubtitleInfo};
-use crate::services::{parse_ytdlp_error, run_ytdlp_json_with_cookies, run_ytdlp_with_stderr_and_cookies, build_cookie_args};
-use crate::database::add_log_internal;
+use crate::types::{Chapter, Comment, VideoInfo, FormatOption, VideoInfoResponse, PlaylistVideoEntry, PlaylistEntries, PlaylistEntryProgress, PlaylistFetchDone, SubtitleInfo, GroupedFormat, GroupedFormats, FormatAvailability, AvailableResolutions, SubtitleDownloadResult, UrlKind};
+use crate::utils::{human_filesize, normalize_url, parse_timestamp, sanitize_output_path, validate_thumbnail_format, DownloadFormat, Quality};
+use crate::services::{cache_info, clear_info_cache_internal, get_cached_info, get_ffmpeg_path, info_cache_key, parse_ytdlp_error, run_ytdlp_json_with_cookies, run_ytdlp_json_with_options, run_ytdlp_with_stderr_and_cookies, run_ytdlp_with_stderr_and_options, spawn_ytdlp_sidecar, validate_proxy_url, WhisperResponseFormat, YtdlpOptions};
+use super::config::{load_download_config, normalize_lang_list, resolve_cookie_source};
+use super::ai::get_ai_config;
+use super::whisper::{transcribe_url_internal, whisper_api_key};
+use crate::database::{add_log_internal, get_history_transcript, update_history_transcript};
 
-/// Get video transcript/subtitles for AI summarization
+/// Transcript languages used when neither the request nor the saved config names any
+const DEFAULT_TRANSCRIPT_LANGS: &[&str] = &["en"];
+
+/// Languages to try for a transcript, most preferred first: the requested ones,
+/// else the saved `preferred_langs`, else DEFAULT_TRANSCRIPT_LANGS
+fn transcript_langs(app: &AppHandle, languages: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let requested = normalize_lang_list(&languages.unwrap_or_default())?;
+    if !requested.is_empty() {
+        return Ok(requested);
+    }
+    let preferred = normalize_lang_list(&load_download_config(app).preferred_langs).unwrap_or_default();
+    if !preferred.is_empty() {
+        return Ok(preferred);
+    }
+    Ok(DEFAULT_TRANSCRIPT_LANGS.iter().map(|l| l.to_string()).collect())
+}
+
+/// Position of a subtitle file ("transcript.<lang>.vtt") in the preference list; a region
+/// variant like "en-US" counts as "en". Files in no listed language sort last
+fn subtitle_file_rank(file_name: &str, langs: &[String]) -> usize {
+    let Some(lang) = file_name.rsplit('.').nth(1) else {
+        return langs.len();
+    };
+    langs
+        .iter()
+        .position(|preferred| {
+            lang.eq_ignore_ascii_case(preferred)
+                || lang.to_ascii_lowercase().starts_with(&format!("{}-", preferred.to_ascii_lowercase()))
+        })
+        .unwrap_or(langs.len())
+}
+
+/// Get video transcript/subtitles for AI summarization.
+/// Languages are tried in preference order; within a language yt-dlp takes uploaded
+/// subtitles over automatic captions
 #[tauri::command]
 pub async fn get_video_transcript(
     app: AppHandle,
@@ -21,6 +62,7 @@ pub async fn get_video_transcript(
     cookie_browser_profile: Option<String>,
     cookie_file_path: Option<String>,
     proxy_url: Option<String>,
+    allow_whisper_fallback: Option<bool>,
 ) -> Result<String, String> {
     // Log the URL being processed
     #[cfg(debug_assertions)]
@@ -28,6 +70,8 @@ pub async fn get_video_transcript(
     
     add_log_internal("info", &format!("Fetching transcript for AI summary"), None, Some(&url)).ok();
     
+    let lang_list = transcript_langs(&app, languages)?;
+    
     // Create unique temp directory for this request (using UUID to prevent any contamination)
     let request_id = Uuid::new_v4();
     let temp_dir = std::env::temp_dir().join(format!("youwee_subs_{}", request_id));
@@ -45,11 +89,6 @@ pub async fn get_video_transcript(
     let url_for_subs = url.clone();
     let url_for_info = url.clone();
     
-    // Use provided languages or default
-    let lang_list: Vec<String> = languages.unwrap_or_else(|| {
-        vec!["en".to_string()]
-    });
-    
     #[cfg(debug_assertions)]
     println!("[TRANSCRIPT] Languages to try: {:?}", lang_list);
     
@@ -75,7 +114,6 @@ pub async fn get_video_transcript(
             "--no-warnings",
             "--no-check-certificates",
             "--no-cache-dir",
-            "--socket-timeout", "30",
             &url_for_subs,
         ];
         
@@ -162,22 +200,10 @@ pub async fn get_video_transcript(
     #[cfg(debug_assertions)]
     println!("[TRANSCRIPT] Total subtitle files found: {}", subtitle_files.len());
     
-    // Sort files: prefer English and shorter names (manual subs)
-    subtitle_files.sort_by(|a, b| {
-        let a_name = a.file_name().unwrap_or_default().to_string_lossy();
-        let b_name = b.file_name().unwrap_or_default().to_string_lossy();
-        
-        let a_is_en = a_name.contains(".en.") || a_name.contains(".en-");
-        let b_is_en = b_name.contains(".en.") || b_name.contains(".en-");
-        
-        if a_is_en && !b_is_en {
-            return std::cmp::Ordering::Less;
-        }
-        if !a_is_en && b_is_en {
-            return std::cmp::Ordering::Greater;
-        }
-        
-        a_name.len().cmp(&b_name.len())
+    // Sort files by language preference, then shorter names (plain codes before variants)
+    subtitle_files.sort_by_key(|path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        (subtitle_file_rank(&name, &lang_list), name.len())
     });
     
     // Try to parse each subtitle file
@@ -201,6 +227,25 @@ pub async fn get_video_transcript(
     // Clean up subtitle files
     std::fs::remove_dir_all(&temp_dir).ok();
     
+    // Transcribe the audio with Whisper when there are no subtitles at all
+    if allow_whisper_fallback.unwrap_or(false) && !rate_limited && specific_error.is_none() {
+        let options = YtdlpOptions::from_settings(
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+            proxy_url.as_deref(),
+        )?;
+        let language = lang_list.first().map(|s| s.as_str());
+        match try_whisper_transcript(&app, &url, language, &options).await {
+            Ok(transcript) if !transcript.trim().is_empty() => return Ok(transcript),
+            Ok(_) => {}
+            Err(e) => {
+                add_log_internal("stderr", &format!("Whisper fallback failed: {}", e), None, Some(&url)).ok();
+            }
+        }
+    }
+    
     #[cfg(debug_assertions)]
     println!("[TRANSCRIPT] No subtitles found, trying description fallback for URL: {}", &url_for_info);
     
@@ -210,13 +255,12 @@ pub async fn get_video_transcript(
     tokio::time::sleep(Duration::from_secs(2)).await;
     
     // No subtitles found - try to get title and description as fallback
-    let info_args = vec![
+    let info_args = [
         "--skip-download",
         "--no-playlist",  // Important: only get single video, not playlist
         "--print", "%(title)s|||%(description)s",
         "--no-warnings",
         "--no-cache-dir",
-        "--socket-timeout", "30",
         &url_for_info,
     ];
     
@@ -291,6 +335,58 @@ pub async fn get_video_transcript(
     Err(error_msg.to_string())
 }
 
+/// Return the transcript stored with a history entry, fetching and storing it when missing.
+/// `refresh` ignores the stored copy, e.g. after picking different subtitle languages.
+#[tauri::command]
+pub async fn get_or_fetch_transcript(
+    app: AppHandle,
+    history_id: String,
+    url: String,
+    refresh: Option<bool>,
+    languages: Option<Vec<String>>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<String, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(transcript) = get_history_transcript(&history_id)?.filter(|t| !t.trim().is_empty()) {
+            return Ok(transcript);
+        }
+    }
+    
+    let transcript = get_video_transcript(
+        app,
+        url,
+        languages,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        proxy_url,
+        None,
+    ).await?;
+    update_history_transcript(history_id, transcript.clone())?;
+    
+    Ok(transcript)
+}
+
+/// Transcribe a video's audio with Whisper using the saved AI config
+async fn try_whisper_transcript(
+    app: &AppHandle,
+    url: &str,
+    language: Option<&str>,
+    options: &YtdlpOptions,
+) -> Result<String, String> {
+    let config = get_ai_config(app.clone()).await?;
+    let api_key = whisper_api_key(&config)
+        .ok_or_else(|| "Whisper fallback needs an OpenAI API key in AI settings".to_string())?;
+    
+    add_log_internal("info", "No subtitles available, transcribing audio with Whisper", None, Some(url)).ok();
+    transcribe_url_internal(app, url, WhisperResponseFormat::Text, &api_key, language, options).await
+}
+
 /// Check if video description contains relevant content (lyrics, transcript, etc.)
 /// Returns false if it's mostly promotional content, links, or author info
 fn is_description_content_relevant(title: &str, description: &str) -> bool {
@@ -419,6 +515,54 @@ fn parse_subtitle_file(content: &str) -> String {
     texts.join(" ")
 }
 
+/// Parse VTT or SRT subtitle content into `(start_seconds, text)` cues, keeping the timing
+/// that `parse_subtitle_file` throws away. Consecutive duplicate lines (common in
+/// auto-generated captions) are collapsed into the first cue that shows them.
+pub(crate) fn parse_subtitle_file_with_timestamps(content: &str) -> Vec<(f64, String)> {
+    let tag_re = regex::Regex::new(r"<[^>]+>").ok();
+    let mut cues: Vec<(f64, String)> = Vec::new();
+    let mut current_start: Option<f64> = None;
+    
+    for line in content.lines() {
+        let line = line.trim();
+        
+        if line.is_empty() || line.starts_with("WEBVTT") || line.starts_with("NOTE") {
+            continue;
+        }
+        
+        // Timing line: VTT uses "00:01:02.345 --> ...", SRT "00:01:02,345 --> ..."
+        if let Some((start, _)) = line.split_once("-->") {
+            current_start = parse_timestamp(&start.trim().replace(',', "."));
+            continue;
+        }
+        
+        // Text before the first cue is header metadata (Kind:, Language:, ...)
+        let Some(start) = current_start else {
+            continue;
+        };
+        
+        if line.chars().all(|c| c.is_ascii_digit())
+            || line.starts_with("align:")
+            || line.starts_with("position:")
+            || line.contains("::")
+        {
+            continue;
+        }
+        
+        let clean_line = match &tag_re {
+            Some(re) => re.replace_all(line, "").to_string(),
+            None => line.to_string(),
+        };
+        let clean_line = clean_line.trim();
+        
+        if !clean_line.is_empty() && !cues.last().map(|(_, text)| text == clean_line).unwrap_or(false) {
+            cues.push((start, clean_line.to_string()));
+        }
+    }
+    
+    cues
+}
+
 #[tauri::command]
 pub async fn get_video_info(
     app: AppHandle,
@@ -428,57 +572,45 @@ pub async fn get_video_info(
     cookie_browser_profile: Option<String>,
     cookie_file_path: Option<String>,
     proxy_url: Option<String>,
+    use_cache: Option<bool>,
+    // Truncate the description to this many characters; 0 or None returns it in full
+    description_max_len: Option<usize>,
 ) -> Result<VideoInfoResponse, String> {
+    let url = normalize_url(&url)?.canonical;
+    let options = YtdlpOptions {
+        cookies: resolve_cookie_source(
+            &app,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+        )?,
+        proxy: validate_proxy_url(proxy_url.as_deref())?,
+    };
+    let cache_key = info_cache_key(&url, &options);
+    let use_cache = use_cache.unwrap_or(true);
+    if use_cache {
+        if let Some(mut response) = get_cached_info(&cache_key) {
+            response.cached = true;
+            truncate_info_description(&mut response, description_max_len);
+            return Ok(response);
+        }
+    }
+    
     let args = [
         "--dump-json",
         "--no-download",
         "--no-playlist",
         "--no-warnings",
-        "--socket-timeout", "15",
         &url,
     ];
     
-    let json_output = run_ytdlp_json_with_cookies(
-        &app,
-        &args,
-        cookie_mode.as_deref(),
-        cookie_browser.as_deref(),
-        cookie_browser_profile.as_deref(),
-        cookie_file_path.as_deref(),
-        proxy_url.as_deref(),
-    ).await?;
+    let json_output = run_ytdlp_json_with_options(&app, &args, &options).await?;
     
     let json: serde_json::Value = serde_json::from_str(&json_output)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
     
-    let is_playlist = json.get("_type").and_then(|v| v.as_str()) == Some("playlist");
-    let playlist_count = if is_playlist {
-        json.get("playlist_count").and_then(|v| v.as_u64()).map(|v| v as u32)
-    } else {
-        None
-    };
-    
-    let info = VideoInfo {
-        id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-        title: json.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string(),
-        thumbnail: json.get("thumbnail").and_then(|v| v.as_str()).map(|s| s.to_string()),
-        duration: json.get("duration").and_then(|v| v.as_f64()),
-        channel: json.get("channel").and_then(|v| v.as_str()).map(|s| s.to_string()),
-        uploader: json.get("uploader").and_then(|v| v.as_str()).map(|s| s.to_string()),
-        upload_date: json.get("upload_date").and_then(|v| v.as_str()).map(|s| s.to_string()),
-        view_count: json.get("view_count").and_then(|v| v.as_u64()),
-        description: json.get("description").and_then(|v| v.as_str()).map(|s| {
-            if s.len() > 200 {
-                format!("{}...", &s[..200])
-            } else {
-                s.to_string()
-            }
-        }),
-        is_playlist,
-        playlist_count,
-        extractor: json.get("extractor").and_then(|v| v.as_str()).map(|s| s.to_string()),
-        extractor_key: json.get("extractor_key").and_then(|v| v.as_str()).map(|s| s.to_string()),
-    };
+    let info = parse_video_info(&json);
     
     let formats = if let Some(formats_arr) = json.get("formats").and_then(|v| v.as_array()) {
         formats_arr.iter().filter_map(|f| {
@@ -505,177 +637,836 @@ pub async fn get_video_info(
         Vec::new()
     };
     
-    Ok(VideoInfoResponse { info, formats })
+    let mut response = VideoInfoResponse { info, formats, cached: false };
+    // The cache keeps the full description so any later length can be served from it
+    if use_cache {
+        cache_info(&cache_key, &response);
+    }
+    truncate_info_description(&mut response, description_max_len);
+    
+    Ok(response)
+}
+
+/// Apply get_video_info's `description_max_len` (0 or None keeps the full description)
+fn truncate_info_description(response: &mut VideoInfoResponse, max_len: Option<usize>) {
+    if let (Some(description), Some(max_len)) = (response.info.description.as_mut(), max_len.filter(|&len| len > 0)) {
+        *description = truncate_description(description, max_len);
+    }
 }
 
+/// Clear the in-memory and persisted video info cache
 #[tauri::command]
-pub async fn get_playlist_entries(
+pub fn clear_info_cache() -> Result<(), String> {
+    clear_info_cache_internal()
+}
+
+/// Fetch the full yt-dlp metadata for a URL as pretty-printed JSON, for bug reports and
+/// power users. `fields` limits the output to those top-level keys, since the complete
+/// dump (formats, thumbnails, subtitles) can run to megabytes.
+#[tauri::command]
+pub async fn get_raw_info(
     app: AppHandle,
     url: String,
-    limit: Option<u32>,
+    fields: Option<Vec<String>>,
     cookie_mode: Option<String>,
     cookie_browser: Option<String>,
     cookie_browser_profile: Option<String>,
     cookie_file_path: Option<String>,
     proxy_url: Option<String>,
-) -> Result<Vec<PlaylistVideoEntry>, String> {
-    let mut args = vec![
-        "--flat-playlist".to_string(),
-        "--dump-json".to_string(),
-        "--no-warnings".to_string(),
-        "--socket-timeout".to_string(), "30".to_string(),
-    ];
+) -> Result<String, String> {
+    let url = normalize_url(&url)?.canonical;
+    let options = YtdlpOptions {
+        cookies: resolve_cookie_source(
+            &app,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+        )?,
+        proxy: validate_proxy_url(proxy_url.as_deref())?,
+    };
     
-    if let Some(l) = limit {
-        if l > 0 {
-            args.push("--playlist-end".to_string());
-            args.push(l.to_string());
-        }
-    }
+    let args = ["--dump-single-json", "--no-warnings", &url];
+    let json_output = run_ytdlp_json_with_options(&app, &args, &options).await?;
     
-    // Add cookie args
-    let cookie_args = build_cookie_args(
-        cookie_mode.as_deref(),
-        cookie_browser.as_deref(),
-        cookie_browser_profile.as_deref(),
-        cookie_file_path.as_deref(),
-    );
-    args.extend(cookie_args);
+    let json: serde_json::Value = serde_json::from_str(&json_output)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let json = match fields.filter(|f| !f.is_empty()) {
+        Some(fields) => project_fields(json, &fields),
+        None => json,
+    };
     
-    // Add proxy args
-    if let Some(proxy) = proxy_url.as_ref() {
-        if !proxy.is_empty() {
-            args.push("--proxy".to_string());
-            args.push(proxy.clone());
-        }
+    serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to format JSON: {}", e))
+}
+
+/// Keep only the requested top-level keys of a JSON object; unknown keys are ignored
+fn project_fields(json: serde_json::Value, fields: &[String]) -> serde_json::Value {
+    match json {
+        serde_json::Value::Object(mut map) => serde_json::Value::Object(
+            fields
+                .iter()
+                .filter_map(|field| map.remove(field).map(|value| (field.clone(), value)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Comments fetched when `get_video_comments` is called without a limit
+const DEFAULT_MAX_COMMENTS: u32 = 100;
+/// Upper bound for `max_comments`; extraction pages through comments and gets slow fast
+const MAX_COMMENTS_LIMIT: u32 = 5000;
+
+/// Running comment fetches by request id, so `cancel_video_comments` can stop them
+static COMMENT_FETCHES: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Fetch up to `max_comments` top comments of a video (default 100).
+/// Comment extraction is slow, so it can be stopped with `cancel_video_comments(id)`.
+#[tauri::command]
+pub async fn get_video_comments(
+    app: AppHandle,
+    id: String,
+    url: String,
+    max_comments: Option<u32>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<Vec<Comment>, String> {
+    let url = normalize_url(&url)?.canonical;
+    let max_comments = max_comments.unwrap_or(DEFAULT_MAX_COMMENTS);
+    if max_comments == 0 || max_comments > MAX_COMMENTS_LIMIT {
+        return Err(format!("max_comments must be between 1 and {}", MAX_COMMENTS_LIMIT));
     }
+    let options = YtdlpOptions {
+        cookies: resolve_cookie_source(
+            &app,
+            cookie_mode.as_deref(),
+            cookie_browser.as_deref(),
+            cookie_browser_profile.as_deref(),
+            cookie_file_path.as_deref(),
+        )?,
+        proxy: validate_proxy_url(proxy_url.as_deref())?,
+    };
     
-    args.push(url.clone());
+    let extractor_args = format!("youtube:max_comments={}", max_comments);
+    let args = [
+        "--skip-download",
+        "--no-playlist",
+        "--write-comments",
+        "--extractor-args", &extractor_args,
+        "--print", "%(comments)j",
+        "--no-warnings",
+        &url,
+    ];
     
-    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    COMMENT_FETCHES.lock().await.insert(id.clone(), cancel_tx);
     
-    let sidecar_result = app.shell().sidecar("yt-dlp");
+    // Dropping the fetch on cancel also kills yt-dlp. A dropped sender (the id was
+    // reused by a newer fetch) disables the branch instead of cancelling
+    let result = tokio::select! {
+        output = run_ytdlp_json_with_options(&app, &args, &options) => output,
+        Ok(()) = cancel_rx => Err("Comment fetch cancelled".to_string()),
+    };
+    COMMENT_FETCHES.lock().await.remove(&id);
     
-    let output = match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, _child) = sidecar
-                .args(&args_ref)
-                .spawn()
-                .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
-            
-            let mut output = String::new();
-            
-            while let Some(event) = rx.recv().await {
-                match event {
-                    CommandEvent::Stdout(bytes) => {
-                        output.push_str(&String::from_utf8_lossy(&bytes));
-                    }
-                    CommandEvent::Stderr(_) => {}
-                    CommandEvent::Error(err) => {
-                        return Err(format!("Process error: {}", err));
-                    }
-                    CommandEvent::Terminated(status) => {
-                        if status.code != Some(0) && output.is_empty() {
-                            return Err("Failed to fetch playlist info".to_string());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            
-            output
-        }
-        Err(_) => {
-            let result = Command::new("yt-dlp")
-                .args(&args)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .await
-                .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
-            
-            String::from_utf8_lossy(&result.stdout).to_string()
+    Ok(parse_comments(&result?, max_comments as usize))
+}
+
+/// Stop the running `get_video_comments` call started with `id`
+#[tauri::command]
+pub async fn cancel_video_comments(id: String) -> Result<(), String> {
+    match COMMENT_FETCHES.lock().await.remove(&id) {
+        Some(cancel_tx) => {
+            cancel_tx.send(()).ok();
+            Ok(())
         }
+        None => Err("No comment fetch running with this id".to_string()),
+    }
+}
+
+/// Parse the `%(comments)j` print. Sites without comments print "NA" or null, and
+/// individual comments often lack author, likes or timestamp; comments without text are skipped
+fn parse_comments(output: &str, max_comments: usize) -> Vec<Comment> {
+    let Ok(serde_json::Value::Array(items)) = serde_json::from_str::<serde_json::Value>(output.trim()) else {
+        return Vec::new();
     };
     
-    let mut entries: Vec<PlaylistVideoEntry> = Vec::new();
+    items
+        .iter()
+        .filter_map(|item| {
+            let text = item.get("text").and_then(|v| v.as_str()).map(str::trim).filter(|t| !t.is_empty())?;
+            Some(Comment {
+                author: item.get("author").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                text: text.to_string(),
+                like_count: item.get("like_count").and_then(|v| v.as_f64()).filter(|n| *n >= 0.0).map(|n| n as u64),
+                timestamp: item.get("timestamp").and_then(|v| v.as_f64()).map(|n| n as i64),
+            })
+        })
+        .take(max_comments)
+        .collect()
+}
+
+/// Get available formats grouped into video-only, audio-only and combined streams.
+/// Each bucket is sorted best-first and holds one format per resolution.
+#[tauri::command]
+pub async fn get_grouped_formats(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<GroupedFormats, String> {
+    let response = get_video_info(
+        app,
+        url,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        proxy_url,
+        None,
+        None,
+    ).await?;
     
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    Ok(group_formats(response.formats))
+}
+
+/// Split formats into buckets, sort each by height then bitrate (descending) and
+/// keep only the best format per resolution.
+fn group_formats(formats: Vec<FormatOption>) -> GroupedFormats {
+    let has_codec = |codec: &Option<String>| codec.as_deref().is_some_and(|c| c != "none");
+    
+    let mut video_only = Vec::new();
+    let mut audio_only = Vec::new();
+    let mut combined = Vec::new();
+    
+    for format in formats {
+        let has_video = format.height.is_some() && has_codec(&format.vcodec);
+        let has_audio = has_codec(&format.acodec);
         
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-            let id = json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-            
-            if id.is_empty() {
-                continue;
-            }
-            
-            let title = json.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string();
-            let video_url = format!("https://www.youtube.com/watch?v={}", id);
-            
-            let thumbnail = json.get("thumbnail")
-                .or_else(|| json.get("thumbnails").and_then(|t| t.as_array()).and_then(|arr| arr.first()))
-                .and_then(|v| {
-                    if v.is_string() {
-                        v.as_str().map(|s| s.to_string())
-                    } else {
-                        v.get("url").and_then(|u| u.as_str()).map(|s| s.to_string())
-                    }
-                });
-            
-            let duration = json.get("duration").and_then(|v| v.as_f64());
-            let channel = json.get("channel")
-                .or_else(|| json.get("uploader"))
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            
-            entries.push(PlaylistVideoEntry {
-                id,
-                title,
-                url: video_url,
-                thumbnail,
-                duration,
-                channel,
-            });
+        match (has_video, has_audio) {
+            (true, true) => combined.push(format),
+            (true, false) => video_only.push(format),
+            // No height means an audio stream, even when yt-dlp leaves acodec unset
+            (false, _) if format.height.is_none() || has_audio => audio_only.push(format),
+            // Storyboards and other image-only tracks
+            _ => {}
         }
     }
     
-    if entries.is_empty() {
-        return Err("No videos found in playlist".to_string());
-    }
+    let by_quality = |a: &FormatOption, b: &FormatOption| {
+        b.height
+            .cmp(&a.height)
+            .then_with(|| b.tbr.unwrap_or(0.0).total_cmp(&a.tbr.unwrap_or(0.0)))
+    };
     
-    Ok(entries)
+    let finish = |mut bucket: Vec<FormatOption>, key: fn(&FormatOption) -> String| {
+        bucket.sort_by(by_quality);
+        let mut seen = std::collections::HashSet::new();
+        bucket
+            .into_iter()
+            .filter(|f| seen.insert(key(f)))
+            .map(|format| GroupedFormat {
+                filesize_human: format.filesize.or(format.filesize_approx).map(human_filesize),
+                format,
+            })
+            .collect::<Vec<_>>()
+    };
+    
+    GroupedFormats {
+        video_only: finish(video_only, |f| f.height.unwrap_or(0).to_string()),
+        combined: finish(combined, |f| f.height.unwrap_or(0).to_string()),
+        // Audio has no resolution; dedupe by container and rounded bitrate instead
+        audio_only: finish(audio_only, |f| format!("{}-{}", f.ext, f.tbr.unwrap_or(0.0).round())),
+    }
 }
 
+/// Check whether the requested quality exists before downloading, so the UI can warn
+/// (e.g. "Only up to 1080p available") instead of yt-dlp silently picking a lower one.
+/// Reuses get_video_info, so the formats usually come from the info cache.
 #[tauri::command]
-pub async fn get_available_subtitles(
+pub async fn check_format_available(
     app: AppHandle,
     url: String,
+    quality: String,
+    format: String,
     cookie_mode: Option<String>,
     cookie_browser: Option<String>,
     cookie_browser_profile: Option<String>,
     cookie_file_path: Option<String>,
     proxy_url: Option<String>,
-) -> Result<Vec<SubtitleInfo>, String> {
-    let args = [
-        "--list-subs",
-        "--skip-download",
-        "--no-warnings",
-        &url,
-    ];
+) -> Result<FormatAvailability, String> {
+    let output_format: DownloadFormat = format.parse()?;
+    let quality_preset: Quality = quality.parse()?;
+    let response = get_video_info(
+        app,
+        url,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        proxy_url,
+        None,
+        None,
+    ).await?;
     
-    let output = run_ytdlp_json_with_cookies(
-        &app,
-        &args,
-        cookie_mode.as_deref(),
-        cookie_browser.as_deref(),
+    Ok(format_availability(&response.formats, quality_preset, output_format))
+}
+
+/// Compare the requested quality against the tallest video stream on offer
+fn format_availability(formats: &[FormatOption], quality: Quality, format: DownloadFormat) -> FormatAvailability {
+    let has_codec = |codec: &Option<String>| codec.as_deref().is_some_and(|c| c != "none");
+    let max_height = formats
+        .iter()
+        .filter(|f| f.vcodec.as_deref() != Some("none"))
+        .filter_map(|f| f.height)
+        .max();
+    
+    // Audio downloads only need some audio stream
+    if quality == Quality::Audio || format.is_audio() {
+        return FormatAvailability {
+            available: formats.iter().any(|f| has_codec(&f.acodec) || f.height.is_none()),
+            max_height,
+            will_fallback_to: None,
+        };
+    }
+    
+    match (quality.height(), max_height) {
+        (_, None) => FormatAvailability {
+            available: false,
+            max_height: None,
+            will_fallback_to: None,
+        },
+        (Some(requested), Some(max)) if max < requested => FormatAvailability {
+            available: false,
+            max_height,
+            will_fallback_to: Some(format!("{}p", max)),
+        },
+        _ => FormatAvailability {
+            available: true,
+            max_height,
+            will_fallback_to: None,
+        },
+    }
+}
+
+/// List the resolutions a video is offered in, for a quality dropdown that doesn't need
+/// the full format list. Reuses get_video_info, so repeat lookups come from the info cache.
+#[tauri::command]
+pub async fn get_available_resolutions(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<AvailableResolutions, String> {
+    let response = get_video_info(
+        app,
+        url,
+        cookie_mode,
+        cookie_browser,
+        cookie_browser_profile,
+        cookie_file_path,
+        proxy_url,
+        None,
+        None,
+    ).await?;
+    
+    Ok(available_resolutions(&response.formats))
+}
+
+/// Distinct heights of real video streams (storyboards report vcodec "none"; a missing vcodec
+/// still counts as video) and whether any audio-only stream exists
+fn available_resolutions(formats: &[FormatOption]) -> AvailableResolutions {
+    let is_video = |f: &FormatOption| f.height.is_some() && f.vcodec.as_deref() != Some("none");
+    
+    let mut heights: Vec<u32> = formats.iter().filter(|f| is_video(f)).filter_map(|f| f.height).collect();
+    heights.sort_unstable();
+    heights.dedup();
+    
+    // Not video: no height means audio even when acodec is unset, and a storyboard
+    // (height but vcodec and acodec "none") is neither
+    let has_audio_only = formats.iter().any(|f| {
+        !is_video(f) && (f.height.is_none() || f.acodec.as_deref().is_some_and(|c| c != "none"))
+    });
+    
+    AvailableResolutions { heights, has_audio_only }
+}
+
+#[tauri::command]
+pub async fn get_playlist_entries(
+    app: AppHandle,
+    url: String,
+    limit: Option<u32>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<PlaylistEntries, String> {
+    let url = normalize_url(&url)?.canonical;
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
         cookie_browser_profile.as_deref(),
         cookie_file_path.as_deref(),
         proxy_url.as_deref(),
-    ).await;
+    )?;
+    
+    let fetched = fetch_flat_playlist(&app, &url, limit, &options, |_| {}).await?;
+    let entries = parse_playlist_entries(&fetched.output);
+    
+    if entries.is_empty() && !fetched.truncated {
+        return Err("No videos found in playlist".to_string());
+    }
+    
+    Ok(PlaylistEntries { entries, truncated: fetched.truncated })
+}
+
+/// Stop a running `get_playlist_entries` or `fetch_playlist_streaming` call for `url`.
+/// yt-dlp is killed and the call returns the entries read so far, marked truncated.
+#[tauri::command]
+pub async fn stop_playlist_fetch(url: String) -> Result<(), String> {
+    let url = normalize_url(&url)?.canonical;
+    match PLAYLIST_FETCHES.lock().await.remove(&url) {
+        Some(cancel_tx) => {
+            cancel_tx.send(()).ok();
+            Ok(())
+        }
+        None => Err("No playlist fetch running for this URL".to_string()),
+    }
+}
+
+/// Cheaply tell whether a URL is a single video, a playlist or a channel, so the UI can show
+/// the right options before the full `get_video_info` fetch completes.
+/// Only playlist-level metadata is fetched (`--playlist-items 0` skips every entry)
+#[tauri::command]
+pub async fn classify_url(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<UrlKind, String> {
+    let url = normalize_url(&url)?.canonical;
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    )?;
+    
+    let args = [
+        "--flat-playlist",
+        "--dump-single-json",
+        "--playlist-items",
+        "0",
+        "--no-warnings",
+        &url,
+    ];
+    let output = run_ytdlp_json_with_options(&app, &args, &options).await?;
+    let json: serde_json::Value = serde_json::from_str(output.trim())
+        .map_err(|e| format!("Failed to parse URL info: {}", e))?;
+    
+    Ok(classify_info_json(&json))
+}
+
+/// Classify yt-dlp's single-JSON output. A channel is a playlist whose id is the channel's own id
+/// (YouTube reports both as `_type: playlist` from the same extractor)
+fn classify_info_json(json: &serde_json::Value) -> UrlKind {
+    let field = |name: &str| json.get(name).and_then(|v| v.as_str());
+    if !matches!(field("_type"), Some("playlist" | "multi_video")) {
+        return UrlKind::SingleVideo;
+    }
+    
+    let id = field("id");
+    if id.is_some() && (id == field("channel_id") || id == field("uploader_id")) {
+        return UrlKind::Channel;
+    }
+    
+    UrlKind::Playlist {
+        count: json.get("playlist_count").and_then(|v| v.as_u64()).map(|v| v as u32),
+    }
+}
+
+/// Like `get_playlist_entries`, but emits each entry as an `info-progress` event as soon as
+/// yt-dlp reports it, then `info-progress-done`, so the UI can fill the list progressively.
+/// Returns the number of entries found.
+#[tauri::command]
+pub async fn fetch_playlist_streaming(
+    app: AppHandle,
+    url: String,
+    limit: Option<u32>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<u32, String> {
+    let url = normalize_url(&url)?.canonical;
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    )?;
+    
+    let mut count: u32 = 0;
+    let result = fetch_flat_playlist(&app, &url, limit, &options, |line| {
+        if let Some(entry) = parse_playlist_entry(line) {
+            count += 1;
+            app.emit("info-progress", PlaylistEntryProgress {
+                url: url.clone(),
+                index: count,
+                entry,
+            }).ok();
+        }
+    }).await;
+    
+    let truncated = result.as_ref().is_ok_and(|fetched| fetched.truncated);
+    let error = match &result {
+        Err(e) => Some(e.clone()),
+        Ok(_) if count == 0 && !truncated => Some("No videos found in playlist".to_string()),
+        Ok(_) => None,
+    };
+    app.emit("info-progress-done", PlaylistFetchDone {
+        url: url.clone(),
+        count,
+        error: error.clone(),
+        truncated,
+    }).ok();
+    
+    match error {
+        Some(e) => Err(e),
+        None => Ok(count),
+    }
+}
+
+/// List the videos of a channel tab. `tab` is "videos" (default), "shorts" or "streams";
+/// bare channel URLs are pointed at that tab instead of yt-dlp's list of tabs.
+#[tauri::command]
+pub async fn get_channel_videos(
+    app: AppHandle,
+    url: String,
+    limit: Option<u32>,
+    tab: Option<String>,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<Vec<PlaylistVideoEntry>, String> {
+    let tab_url = channel_tab_url(&url, tab.as_deref().unwrap_or("videos"))?;
+    
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    )?;
+    
+    let fetched = fetch_flat_playlist(&app, &tab_url, limit, &options, |_| {}).await?;
+    let entries = parse_playlist_entries(&fetched.output);
+    
+    if entries.is_empty() && !fetched.truncated {
+        return Err("No videos found on this channel".to_string());
+    }
+    
+    Ok(entries)
+}
+
+/// Channel tabs that can be listed with get_channel_videos
+const CHANNEL_TABS: &[&str] = &["videos", "shorts", "streams"];
+
+/// Build the URL of a YouTube channel tab, e.g. `https://www.youtube.com/@handle/shorts`.
+/// Replaces an existing tab segment; non-YouTube URLs are returned unchanged.
+fn channel_tab_url(url: &str, tab: &str) -> Result<String, String> {
+    let tab = tab.trim().to_lowercase();
+    if !CHANNEL_TABS.contains(&tab.as_str()) {
+        return Err(format!("Unsupported channel tab: {} (expected videos, shorts or streams)", tab));
+    }
+    
+    let base = url.trim().split(['?', '#']).next().unwrap_or("").trim_end_matches('/');
+    if !base.contains("youtube.com/") {
+        return Ok(url.trim().to_string());
+    }
+    
+    let (host, path) = base.split_once("youtube.com/").unwrap_or((base, ""));
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let channel_len = match segments.first() {
+        Some(first) if first.starts_with('@') => 1,
+        Some(&"channel") | Some(&"c") | Some(&"user") if segments.len() >= 2 => 2,
+        _ => return Err("Not a YouTube channel URL".to_string()),
+    };
+    segments.truncate(channel_len);
+    
+    Ok(format!("{}youtube.com/{}/{}", host, segments.join("/"), tab))
+}
+
+// Cancel senders of running flat-playlist fetches, keyed by URL; see stop_playlist_fetch
+static PLAYLIST_FETCHES: LazyLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Output of a flat-playlist run
+struct FlatPlaylistOutput {
+    /// JSON lines, one entry per line
+    output: String,
+    /// Stopped early by `stop_playlist_fetch`; `output` holds only complete lines read so far
+    truncated: bool,
+}
+
+/// Resolves once `stop_playlist_fetch` fires. Never resolves if the sender was dropped
+/// without sending, e.g. replaced by a newer fetch of the same URL
+async fn playlist_fetch_stopped(cancel_rx: &mut Option<tokio::sync::oneshot::Receiver<()>>) {
+    if let Some(rx) = cancel_rx.as_mut() {
+        if rx.await.is_ok() {
+            return;
+        }
+        *cancel_rx = None;
+    }
+    std::future::pending::<()>().await
+}
+
+/// Run yt-dlp in flat-playlist mode and return its JSON-lines output.
+/// `on_line` is called with each complete line as it arrives.
+/// The run can be stopped with `stop_playlist_fetch(url)`, which keeps the lines read so far.
+async fn fetch_flat_playlist<F>(
+    app: &AppHandle,
+    url: &str,
+    limit: Option<u32>,
+    options: &YtdlpOptions,
+    on_line: F,
+) -> Result<FlatPlaylistOutput, String>
+where
+    F: FnMut(&str),
+{
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    PLAYLIST_FETCHES.lock().await.insert(url.to_string(), cancel_tx);
+    let result = run_flat_playlist(app, url, limit, options, Some(cancel_rx), on_line).await;
+    PLAYLIST_FETCHES.lock().await.remove(url);
+    result
+}
+
+async fn run_flat_playlist<F>(
+    app: &AppHandle,
+    url: &str,
+    limit: Option<u32>,
+    options: &YtdlpOptions,
+    mut cancel_rx: Option<tokio::sync::oneshot::Receiver<()>>,
+    mut on_line: F,
+) -> Result<FlatPlaylistOutput, String>
+where
+    F: FnMut(&str),
+{
+    let mut args = vec![
+        "--flat-playlist".to_string(),
+        "--dump-json".to_string(),
+        "--no-warnings".to_string(),
+    ];
+    
+    if let Some(l) = limit {
+        if l > 0 {
+            args.push("--playlist-end".to_string());
+            args.push(l.to_string());
+        }
+    }
+    
+    args.extend(options.to_args());
+    args.push(url.to_string());
+    
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    
+    let mut truncated = false;
+    let output = match spawn_ytdlp_sidecar(app, &args_ref)? {
+        Some((mut rx, child)) => {
+            let mut output = String::new();
+            // Start of the line not yet passed to `on_line`; stdout chunks may split a line
+            let mut pending = 0;
+            
+            loop {
+                let event = tokio::select! {
+                    event = rx.recv() => event,
+                    _ = playlist_fetch_stopped(&mut cancel_rx) => {
+                        child.kill().ok();
+                        truncated = true;
+                        // Drop the incomplete last line
+                        output.truncate(pending);
+                        break;
+                    }
+                };
+                let Some(event) = event else {
+                    break;
+                };
+                match event {
+                    CommandEvent::Stdout(bytes) => {
+                        output.push_str(&String::from_utf8_lossy(&bytes));
+                        while let Some(end) = output[pending..].find('\n') {
+                            on_line(&output[pending..pending + end]);
+                            pending += end + 1;
+                        }
+                    }
+                    CommandEvent::Stderr(_) => {}
+                    CommandEvent::Error(err) => {
+                        return Err(format!("Process error: {}", err));
+                    }
+                    CommandEvent::Terminated(status) => {
+                        if status.code != Some(0) && output.is_empty() {
+                            return Err("Failed to fetch playlist info".to_string());
+                        }
+                        on_line(&output[pending..]);
+                    }
+                    _ => {}
+                }
+            }
+            
+            output
+        }
+        None => {
+            let mut child = Command::new("yt-dlp")
+                .args(&args)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
+            let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+            let mut lines = BufReader::new(stdout).lines();
+            
+            let mut output = String::new();
+            loop {
+                tokio::select! {
+                    line = lines.next_line() => match line {
+                        Ok(Some(line)) => {
+                            on_line(&line);
+                            output.push_str(&line);
+                            output.push('\n');
+                        }
+                        _ => break,
+                    },
+                    _ = playlist_fetch_stopped(&mut cancel_rx) => {
+                        child.kill().await.ok();
+                        truncated = true;
+                        break;
+                    }
+                }
+            }
+            if !truncated {
+                child.wait().await.ok();
+            }
+            output
+        }
+    };
+    
+    Ok(FlatPlaylistOutput { output, truncated })
+}
+
+/// Parse flat-playlist JSON lines into entries, skipping lines without an id
+fn parse_playlist_entries(output: &str) -> Vec<PlaylistVideoEntry> {
+    output.lines().filter_map(parse_playlist_entry).collect()
+}
+
+/// Parse one flat-playlist JSON line; `None` for blank lines, invalid JSON or entries without an id
+fn parse_playlist_entry(line: &str) -> Option<PlaylistVideoEntry> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    
+    let json = serde_json::from_str::<serde_json::Value>(line).ok()?;
+    let id = json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    
+    if id.is_empty() {
+        return None;
+    }
+    
+    let title = json.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string();
+    let video_url = format!("https://www.youtube.com/watch?v={}", id);
+    
+    let thumbnail = json.get("thumbnail")
+        .or_else(|| json.get("thumbnails").and_then(|t| t.as_array()).and_then(|arr| arr.first()))
+        .and_then(|v| {
+            if v.is_string() {
+                v.as_str().map(|s| s.to_string())
+            } else {
+                v.get("url").and_then(|u| u.as_str()).map(|s| s.to_string())
+            }
+        });
+    
+    let duration = json.get("duration").and_then(|v| v.as_f64());
+    let channel = json.get("channel")
+        .or_else(|| json.get("uploader"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    
+    Some(PlaylistVideoEntry {
+        id,
+        title,
+        url: video_url,
+        thumbnail,
+        duration,
+        channel,
+    })
+}
+
+#[tauri::command]
+pub async fn get_available_subtitles(
+    app: AppHandle,
+    url: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<Vec<SubtitleInfo>, String> {
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    );
+    let mut subtitles = match options {
+        Ok(options) => list_subtitles(&app, &url, &options).await.unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    
+    if subtitles.is_empty() {
+        subtitles = vec![
+            SubtitleInfo { lang: "en".to_string(), name: "English".to_string(), is_auto: false },
+            SubtitleInfo { lang: "vi".to_string(), name: "Vietnamese".to_string(), is_auto: false },
+            SubtitleInfo { lang: "ja".to_string(), name: "Japanese".to_string(), is_auto: false },
+            SubtitleInfo { lang: "ko".to_string(), name: "Korean".to_string(), is_auto: false },
+            SubtitleInfo { lang: "zh".to_string(), name: "Chinese".to_string(), is_auto: false },
+        ];
+    }
+    
+    Ok(subtitles)
+}
+
+/// Subtitle and automatic caption languages yt-dlp lists for a video.
+/// Unlike `get_available_subtitles` there is no fallback list: a video without subtitles gives an empty list
+pub(crate) async fn list_subtitles(app: &AppHandle, url: &str, options: &YtdlpOptions) -> Result<Vec<SubtitleInfo>, String> {
+    let args = [
+        "--list-subs",
+        "--skip-download",
+        "--no-warnings",
+        url,
+    ];
+    
+    let output = run_ytdlp_json_with_options(app, &args, options).await?;
     
     let mut subtitles: Vec<SubtitleInfo> = Vec::new();
     
@@ -704,55 +1495,581 @@ pub async fn get_available_subtitles(
         ("uk", "Ukrainian"),
     ].iter().cloned().collect();
     
-    if let Ok(text) = output {
-        let mut is_auto_section = false;
+    let mut is_auto_section = false;
+    
+    for line in output.lines() {
+        let line = line.trim();
         
-        for line in text.lines() {
-            let line = line.trim();
-            
-            if line.contains("automatic captions") || line.contains("auto-generated") {
-                is_auto_section = true;
+        if line.contains("automatic captions") || line.contains("auto-generated") {
+            is_auto_section = true;
+            continue;
+        }
+        
+        if line.contains("subtitles") && !line.contains("auto") {
+            is_auto_section = false;
+            continue;
+        }
+        
+        if line.is_empty() || line.starts_with("Language") || line.starts_with("[") || line.contains("Available") {
+            continue;
+        }
+        
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if let Some(lang_code) = parts.first() {
+            let lang = lang_code.to_string();
+            if subtitles.iter().any(|s| s.lang == lang && s.is_auto == is_auto_section) {
                 continue;
             }
             
-            if line.contains("subtitles") && !line.contains("auto") {
-                is_auto_section = false;
-                continue;
-            }
+            let name = lang_names.get(lang.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| lang.clone());
             
-            if line.is_empty() || line.starts_with("Language") || line.starts_with("[") || line.contains("Available") {
-                continue;
+            subtitles.push(SubtitleInfo {
+                lang,
+                name,
+                is_auto: is_auto_section,
+            });
+        }
+    }
+    
+    Ok(subtitles)
+}
+
+/// Download subtitle tracks only, without the video.
+/// Languages that aren't available are reported in `missing` instead of failing the call.
+#[tauri::command]
+pub async fn download_subtitles(
+    app: AppHandle,
+    url: String,
+    langs: Vec<String>,
+    format: String,
+    output_path: String,
+    auto: bool,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+) -> Result<SubtitleDownloadResult, String> {
+    let langs: Vec<String> = langs
+        .into_iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    if langs.is_empty() {
+        return Err("No subtitle languages selected".to_string());
+    }
+    
+    let sanitized_path = sanitize_output_path(&output_path)?;
+    let output_template = format!("{}/%(title)s.%(ext)s", sanitized_path);
+    let sub_langs = langs.join(",");
+    // Fall back to the best available format rather than skipping the track
+    let sub_format = format!("{}/best", format.trim());
+    
+    let args = [
+        "--skip-download",
+        "--no-playlist",
+        if auto { "--write-auto-subs" } else { "--write-subs" },
+        "--sub-langs", &sub_langs,
+        "--sub-format", &sub_format,
+        "-o", &output_template,
+        "--no-warnings",
+        &url,
+    ];
+    
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    )?;
+    
+    add_log_internal("command", &format!("yt-dlp {}", args.join(" ")), None, Some(&url)).ok();
+    
+    let output = run_ytdlp_with_stderr_and_options(&app, &args, &options).await?;
+    
+    // yt-dlp reports each file as "[info] Writing video subtitles to: <path>"
+    let files: Vec<String> = output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once("Writing video subtitles to:"))
+        .map(|(_, path)| path.trim().to_string())
+        .filter(|path| std::path::Path::new(path).exists())
+        .collect();
+    
+    if files.is_empty() && !output.success {
+        let error = parse_ytdlp_error(&output.stderr)
+            .unwrap_or_else(|| format!("Failed to download subtitles: {}", output.stderr.trim()));
+        add_log_internal("error", &error, None, Some(&url)).ok();
+        return Err(error);
+    }
+    
+    // Subtitle files are named "<title>.<lang>.<ext>"
+    let file_langs: Vec<String> = files
+        .iter()
+        .filter_map(|path| {
+            let stem = std::path::Path::new(path).file_stem()?;
+            let lang = std::path::Path::new(stem).extension()?;
+            Some(lang.to_string_lossy().to_string())
+        })
+        .collect();
+    
+    let (downloaded, missing): (Vec<String>, Vec<String>) = langs.into_iter().partition(|requested| {
+        file_langs.iter().any(|lang| match requested.strip_suffix(".*") {
+            Some(prefix) => lang.starts_with(prefix),
+            None => lang == requested,
+        })
+    });
+    
+    if !missing.is_empty() {
+        add_log_internal(
+            "info",
+            &format!("Subtitles not available for: {}", missing.join(", ")),
+            None,
+            Some(&url),
+        ).ok();
+    }
+    
+    Ok(SubtitleDownloadResult {
+        files,
+        downloaded,
+        missing,
+    })
+}
+
+/// Download the highest-resolution thumbnail without the media, converted to
+/// `thumbnail_format` ("jpg" by default, "png" or "webp").
+/// Playlist URLs write one thumbnail per entry into the folder. If yt-dlp's
+/// thumbnail writer fails, the `thumbnail` URL from the video info is fetched directly
+/// and kept in whatever format the site serves.
+#[tauri::command]
+pub async fn download_thumbnail(
+    app: AppHandle,
+    url: String,
+    output_path: String,
+    cookie_mode: Option<String>,
+    cookie_browser: Option<String>,
+    cookie_browser_profile: Option<String>,
+    cookie_file_path: Option<String>,
+    proxy_url: Option<String>,
+    thumbnail_format: Option<String>,
+) -> Result<Vec<String>, String> {
+    let validated_proxy = validate_proxy_url(proxy_url.as_deref())?;
+    let sanitized_path = sanitize_output_path(&output_path)?;
+    let output_template = format!("{}/%(title)s.%(ext)s", sanitized_path);
+    let convert_to = validate_thumbnail_format(thumbnail_format.as_deref())?;
+    // The conversion postprocessor runs ffmpeg; without it only an explicit format is an error
+    let ffmpeg_dir = get_ffmpeg_path(&app)
+        .await
+        .and_then(|path| path.parent().map(|dir| dir.to_string_lossy().to_string()));
+    if ffmpeg_dir.is_none() && thumbnail_format.is_some() {
+        return Err("FFmpeg is required to convert thumbnails. Please install FFmpeg from Settings.".to_string());
+    }
+    
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        validated_proxy.as_deref(),
+    )?;
+    
+    let mut args = vec![
+        "--skip-download",
+        "--write-thumbnail",
+        "--convert-thumbnails", convert_to,
+        "-o", &output_template,
+        "--no-warnings",
+    ];
+    if let Some(dir) = ffmpeg_dir.as_deref() {
+        args.extend(["--ffmpeg-location", dir]);
+    }
+    args.push(&url);
+    
+    add_log_internal("command", &format!("yt-dlp {}", args.join(" ")), None, Some(&url)).ok();
+    
+    let output = run_ytdlp_with_stderr_and_options(&app, &args, &options).await?;
+    
+    // yt-dlp reports "[info] Writing video thumbnail <n> to: <path>", then converts it
+    let files: Vec<String> = output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once("Writing video thumbnail"))
+        .filter_map(|(_, rest)| rest.split_once(" to: "))
+        .filter_map(|(_, path)| {
+            let written = std::path::Path::new(path.trim());
+            let converted = written.with_extension(convert_to);
+            if converted.exists() {
+                Some(converted.to_string_lossy().to_string())
+            } else if written.exists() {
+                Some(written.to_string_lossy().to_string())
+            } else {
+                None
             }
-            
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if let Some(lang_code) = parts.first() {
-                let lang = lang_code.to_string();
-                if subtitles.iter().any(|s| s.lang == lang && s.is_auto == is_auto_section) {
-                    continue;
-                }
-                
-                let name = lang_names.get(lang.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| lang.clone());
-                
-                subtitles.push(SubtitleInfo {
-                    lang,
-                    name,
-                    is_auto: is_auto_section,
-                });
+        })
+        .collect();
+    
+    if !files.is_empty() {
+        return Ok(files);
+    }
+    
+    #[cfg(debug_assertions)]
+    println!("[THUMBNAIL] yt-dlp wrote no thumbnails, fetching thumbnail URLs directly");
+    
+    // Fallback: resolve each entry's title and thumbnail URL, then fetch them ourselves
+    let print_args = [
+        "--skip-download",
+        "--print", "%(title)s\t%(thumbnail)s",
+        "--no-warnings",
+        &url,
+    ];
+    let listing = run_ytdlp_with_stderr_and_options(&app, &print_args, &options).await?;
+    let entries: Vec<(String, String)> = listing
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(_, thumbnail)| thumbnail.starts_with("http"))
+        .map(|(title, thumbnail)| (title.to_string(), thumbnail.to_string()))
+        .collect();
+    
+    if entries.is_empty() {
+        let error = parse_ytdlp_error(&output.stderr)
+            .or_else(|| parse_ytdlp_error(&listing.stderr))
+            .unwrap_or_else(|| "No thumbnail available for this URL".to_string());
+        add_log_internal("error", &error, None, Some(&url)).ok();
+        return Err(error);
+    }
+    
+    let mut builder = reqwest::Client::builder()
+        .user_agent("Youwee/0.4.0")
+        .timeout(Duration::from_secs(30));
+    if let Some(proxy) = validated_proxy.as_deref() {
+        builder = builder.proxy(
+            reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy: {}", e))?,
+        );
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    
+    let mut files = Vec::new();
+    for (title, thumbnail_url) in entries {
+        match fetch_thumbnail(&client, &thumbnail_url, &sanitized_path, &title).await {
+            Ok(path) => files.push(path),
+            Err(e) => {
+                add_log_internal("error", &e, Some(&thumbnail_url), Some(&url)).ok();
             }
         }
     }
     
-    if subtitles.is_empty() {
-        subtitles = vec![
-            SubtitleInfo { lang: "en".to_string(), name: "English".to_string(), is_auto: false },
-            SubtitleInfo { lang: "vi".to_string(), name: "Vietnamese".to_string(), is_auto: false },
-            SubtitleInfo { lang: "ja".to_string(), name: "Japanese".to_string(), is_auto: false },
-            SubtitleInfo { lang: "ko".to_string(), name: "Korean".to_string(), is_auto: false },
-            SubtitleInfo { lang: "zh".to_string(), name: "Chinese".to_string(), is_auto: false },
-        ];
+    if files.is_empty() {
+        return Err("Failed to download thumbnail".to_string());
     }
     
-    Ok(subtitles)
+    Ok(files)
+}
+
+/// Fetch a thumbnail URL into `dir`, named after the video title
+async fn fetch_thumbnail(
+    client: &reqwest::Client,
+    thumbnail_url: &str,
+    dir: &str,
+    title: &str,
+) -> Result<String, String> {
+    let response = client
+        .get(thumbnail_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch thumbnail: {}", e))?;
+    
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch thumbnail: HTTP {}", response.status()));
+    }
+    
+    // Keep the served image type; query strings are common on thumbnail URLs
+    let ext = thumbnail_url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_lowercase())
+        .filter(|ext| matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp"))
+        .unwrap_or_else(|| "jpg".to_string());
+    
+    let stem: String = title
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let path = std::path::Path::new(dir).join(format!("{}.{}", stem.trim(), ext));
+    
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+    
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Build VideoInfo from yt-dlp's --dump-json output
+fn parse_video_info(json: &serde_json::Value) -> VideoInfo {
+    let is_playlist = json.get("_type").and_then(|v| v.as_str()) == Some("playlist");
+    let playlist_count = if is_playlist {
+        json.get("playlist_count").and_then(|v| v.as_u64()).map(|v| v as u32)
+    } else {
+        None
+    };
+    
+    VideoInfo {
+        id: json.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+        title: json.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string(),
+        thumbnail: json.get("thumbnail").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        duration: json.get("duration").and_then(|v| v.as_f64()),
+        channel: json.get("channel").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        uploader: json.get("uploader").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        upload_date: json.get("upload_date").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        view_count: json.get("view_count").and_then(|v| v.as_u64()),
+        like_count: json.get("like_count").and_then(|v| v.as_u64()),
+        channel_follower_count: json.get("channel_follower_count").and_then(|v| v.as_u64()),
+        description: json.get("description").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        is_playlist,
+        playlist_count,
+        extractor: json.get("extractor").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        extractor_key: json.get("extractor_key").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        chapters: parse_chapters(json),
+    }
+}
+
+/// Parse the `chapters` array; None when the video has no chapters
+fn parse_chapters(json: &serde_json::Value) -> Option<Vec<Chapter>> {
+    let chapters: Vec<Chapter> = json
+        .get("chapters")
+        .and_then(|v| v.as_array())?
+        .iter()
+        .filter_map(|c| {
+            Some(Chapter {
+                title: c.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                start_time: c.get("start_time").and_then(|v| v.as_f64())?,
+                end_time: c.get("end_time").and_then(|v| v.as_f64()),
+            })
+        })
+        .collect();
+    
+    if chapters.is_empty() {
+        None
+    } else {
+        Some(chapters)
+    }
+}
+
+/// Truncate a description to at most `max_chars` characters, appending "..." when cut.
+/// Counts chars rather than bytes so multibyte text never gets sliced mid-character.
+fn truncate_description(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &s[..end]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtitle_file_rank() {
+        let langs = vec!["vi".to_string(), "en".to_string()];
+        assert_eq!(subtitle_file_rank("transcript.vi.vtt", &langs), 0);
+        assert_eq!(subtitle_file_rank("transcript.en-US.vtt", &langs), 1);
+        assert_eq!(subtitle_file_rank("transcript.eng.vtt", &langs), 2);
+        assert_eq!(subtitle_file_rank("transcript", &langs), 2);
+        
+        let normalized = normalize_lang_list(&[" vi ".to_string(), "".to_string(), "VI".to_string(), "pt-BR".to_string()]).unwrap();
+        assert_eq!(normalized, vec!["vi", "pt-BR"]);
+        assert!(normalize_lang_list(&["en,all".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let output = r#"[
+            {"author": "@alice", "text": " First! ", "like_count": 12, "timestamp": 1700000000},
+            {"text": "No author or likes"},
+            {"author": "@bob", "text": ""},
+            {"author": "@carol", "text": "Third", "like_count": null}
+        ]"#;
+        let comments = parse_comments(output, 10);
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].author.as_deref(), Some("@alice"));
+        assert_eq!(comments[0].text, "First!");
+        assert_eq!(comments[0].like_count, Some(12));
+        assert_eq!(comments[0].timestamp, Some(1700000000));
+        assert_eq!(comments[1].author, None);
+        assert_eq!(comments[2].like_count, None);
+        
+        assert_eq!(parse_comments(output, 1).len(), 1);
+        assert!(parse_comments("NA", 10).is_empty());
+    }
+
+    #[test]
+    fn test_truncate_description_multibyte() {
+        let description = "動画の説明🎬🍜 Xin chào thế giới ".repeat(20);
+        let truncated = truncate_description(&description, 200);
+        
+        assert!(truncated.ends_with("..."));
+        assert_eq!(truncated.trim_end_matches("...").chars().count(), 200);
+        assert!(description.starts_with(truncated.trim_end_matches("...")));
+    }
+
+    #[test]
+    fn test_truncate_description_short() {
+        assert_eq!(truncate_description("短い説明 😀", 200), "短い説明 😀");
+        assert_eq!(truncate_description("", 200), "");
+    }
+
+    #[test]
+    fn test_parse_video_info_includes_extractor() {
+        let json = serde_json::json!({
+            "id": "dQw4w9WgXcQ",
+            "title": "Example",
+            "extractor": "youtube",
+            "extractor_key": "Youtube",
+            "like_count": 1200,
+        });
+        let info = parse_video_info(&json);
+        
+        assert_eq!(info.extractor.as_deref(), Some("youtube"));
+        assert_eq!(info.extractor_key.as_deref(), Some("Youtube"));
+        assert_eq!(info.like_count, Some(1200));
+        assert_eq!(info.channel_follower_count, None);
+        assert!(!info.is_playlist);
+        
+        let serialized = serde_json::to_value(&info).unwrap();
+        assert_eq!(serialized["extractor"], "youtube");
+    }
+
+    #[test]
+    fn test_classify_info_json() {
+        let video = serde_json::json!({ "id": "dQw4w9WgXcQ", "title": "Video", "channel_id": "UCabc" });
+        assert_eq!(classify_info_json(&video), UrlKind::SingleVideo);
+        
+        let playlist = serde_json::json!({
+            "_type": "playlist", "id": "PLxyz", "channel_id": "UCabc", "playlist_count": 42, "entries": []
+        });
+        assert_eq!(classify_info_json(&playlist), UrlKind::Playlist { count: Some(42) });
+        
+        let mix = serde_json::json!({ "_type": "playlist", "id": "RDxyz" });
+        assert_eq!(classify_info_json(&mix), UrlKind::Playlist { count: None });
+        
+        let channel = serde_json::json!({ "_type": "playlist", "id": "UCabc", "channel_id": "UCabc" });
+        assert_eq!(classify_info_json(&channel), UrlKind::Channel);
+    }
+
+    #[test]
+    fn test_format_availability() {
+        let formats: Vec<FormatOption> = serde_json::from_value(serde_json::json!([
+            { "format_id": "140", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.2" },
+            { "format_id": "137", "ext": "mp4", "height": 1080, "vcodec": "avc1", "acodec": "none" },
+            { "format_id": "22", "ext": "mp4", "height": 720, "vcodec": "avc1", "acodec": "mp4a.40.2" },
+        ])).unwrap();
+        
+        let capped = format_availability(&formats, Quality::P4k, DownloadFormat::Mp4);
+        assert!(!capped.available);
+        assert_eq!(capped.max_height, Some(1080));
+        assert_eq!(capped.will_fallback_to.as_deref(), Some("1080p"));
+        
+        assert!(format_availability(&formats, Quality::P1080, DownloadFormat::Mkv).available);
+        assert!(format_availability(&formats, Quality::Best, DownloadFormat::Mp4).available);
+        assert!(format_availability(&formats, Quality::Audio, DownloadFormat::Mp3).available);
+        assert!(!format_availability(&formats[..1], Quality::P720, DownloadFormat::Mp4).available);
+    }
+
+    #[test]
+    fn test_available_resolutions() {
+        let formats: Vec<FormatOption> = serde_json::from_value(serde_json::json!([
+            { "format_id": "sb0", "ext": "mhtml", "height": 45, "vcodec": "none", "acodec": "none" },
+            { "format_id": "140", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.2" },
+            { "format_id": "137", "ext": "mp4", "height": 1080, "vcodec": "avc1", "acodec": "none" },
+            { "format_id": "248", "ext": "webm", "height": 1080, "vcodec": "vp9", "acodec": "none" },
+            { "format_id": "18", "ext": "mp4", "height": 360, "vcodec": "avc1", "acodec": "mp4a.40.2" },
+        ])).unwrap();
+        
+        assert_eq!(
+            available_resolutions(&formats),
+            AvailableResolutions { heights: vec![360, 1080], has_audio_only: true }
+        );
+        assert!(!available_resolutions(&formats[2..]).has_audio_only);
+        
+        // Some extractors omit vcodec on progressive formats
+        let formats: Vec<FormatOption> = serde_json::from_value(serde_json::json!([
+            { "format_id": "hd", "ext": "mp4", "height": 720 },
+        ])).unwrap();
+        assert_eq!(
+            available_resolutions(&formats),
+            AvailableResolutions { heights: vec![720], has_audio_only: false }
+        );
+    }
+
+    #[test]
+    fn test_parse_subtitle_file_with_timestamps() {
+        let vtt = "WEBVTT\nKind: captions\nLanguage: en\n\n00:00:01.000 --> 00:00:03.000 align:start position:0%\nhello <c>world</c>\n\n00:00:03.000 --> 00:00:05.000\nhello world\nsecond line\n\n01:02:03.500 --> 01:02:05.000\nlater\n";
+        assert_eq!(
+            parse_subtitle_file_with_timestamps(vtt),
+            vec![(1.0, "hello world".to_string()), (3.0, "second line".to_string()), (3723.5, "later".to_string())]
+        );
+        
+        let srt = "1\n00:00:10,250 --> 00:00:12,000\nFirst\n\n2\n00:01:00,000 --> 00:01:02,000\nSecond\n";
+        assert_eq!(
+            parse_subtitle_file_with_timestamps(srt),
+            vec![(10.25, "First".to_string()), (60.0, "Second".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_project_fields() {
+        let json = serde_json::json!({ "id": "abc", "title": "Example", "formats": [1, 2, 3] });
+        let fields = vec!["title".to_string(), "id".to_string(), "missing".to_string()];
+        
+        assert_eq!(project_fields(json, &fields), serde_json::json!({ "title": "Example", "id": "abc" }));
+    }
+
+    #[test]
+    fn test_group_formats() {
+        let formats: Vec<FormatOption> = serde_json::from_value(serde_json::json!([
+            { "format_id": "sb0", "ext": "mhtml", "height": 45, "vcodec": "none", "acodec": "none" },
+            { "format_id": "139", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.5", "tbr": 48.0 },
+            { "format_id": "140", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.2", "tbr": 129.5, "filesize": 47395635 },
+            { "format_id": "140-drc", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.2", "tbr": 129.6 },
+            { "format_id": "136", "ext": "mp4", "height": 720, "vcodec": "avc1", "acodec": "none", "tbr": 1500.0 },
+            { "format_id": "247", "ext": "webm", "height": 720, "vcodec": "vp9", "acodec": "none", "tbr": 1800.0 },
+            { "format_id": "137", "ext": "mp4", "height": 1080, "vcodec": "avc1", "acodec": "none", "filesize_approx": 2048 },
+            { "format_id": "18", "ext": "mp4", "height": 360, "vcodec": "avc1", "acodec": "mp4a.40.2" },
+        ])).unwrap();
+        fn ids(bucket: &[GroupedFormat]) -> Vec<&str> {
+            bucket.iter().map(|g| g.format.format_id.as_str()).collect()
+        }
+        
+        let grouped = group_formats(formats);
+        assert_eq!(ids(&grouped.video_only), vec!["137", "247"]);
+        assert_eq!(ids(&grouped.combined), vec!["18"]);
+        assert_eq!(ids(&grouped.audio_only), vec!["140-drc", "139"]);
+        assert_eq!(grouped.video_only[0].filesize_human.as_deref(), Some("2.0 KiB"));
+        assert_eq!(grouped.combined[0].filesize_human, None);
+    }
+
+    #[test]
+    fn test_channel_tab_url() {
+        assert_eq!(
+            channel_tab_url("https://www.youtube.com/@handle", "Shorts").unwrap(),
+            "https://www.youtube.com/@handle/shorts"
+        );
+        assert_eq!(
+            channel_tab_url("https://www.youtube.com/channel/UCabc/videos?view=0", "streams").unwrap(),
+            "https://www.youtube.com/channel/UCabc/streams"
+        );
+        assert_eq!(channel_tab_url("https://vimeo.com/user1", "videos").unwrap(), "https://vimeo.com/user1");
+        assert!(channel_tab_url("https://www.youtube.com/@handle", "community").is_err());
+        assert!(channel_tab_url("https://www.youtube.com/watch?v=abc", "videos").is_err());
+    }
 }
@@ -2,9 +2,11 @@ use std::path::Path;
 use tauri::AppHandle;
 use uuid::Uuid;
 use crate::services::{
-    transcribe_audio, extract_audio_for_whisper, WhisperResponseFormat, WhisperError,
-    run_ytdlp_with_stderr_and_cookies, get_ffmpeg_path,
+    transcribe_audio_with_model, extract_audio_for_whisper, WhisperResponseFormat, WhisperError,
+    run_ytdlp_with_stderr_and_options, get_ffmpeg_path, AIConfig, AIProvider, YtdlpOptions,
+    DEFAULT_WHISPER_MODEL,
 };
+use super::ai::get_ai_config;
 use crate::database::add_log_internal;
 
 /// Transcribe a local video/audio file using OpenAI Whisper API
@@ -20,6 +22,69 @@ pub async fn transcribe_video_with_whisper(
     response_format: String, // "text", "srt", "vtt"
     openai_api_key: String,
     language: Option<String>,
+) -> Result<String, String> {
+    transcribe_file_internal(
+        &app,
+        &video_path,
+        parse_response_format(&response_format),
+        &openai_api_key,
+        DEFAULT_WHISPER_MODEL,
+        language.as_deref(),
+    ).await
+}
+
+/// Transcribe a local audio/video file with a chosen Whisper model
+/// Uses the Whisper key from the saved AI config
+#[tauri::command]
+pub async fn transcribe_audio(
+    app: AppHandle,
+    path: String,
+    model: Option<String>,
+    language: Option<String>,
+) -> Result<String, String> {
+    let config = get_ai_config(app.clone()).await?;
+    let api_key = whisper_api_key(&config).ok_or_else(|| String::from(WhisperError::NoApiKey))?;
+    let model = model.filter(|m| !m.is_empty()).unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+    
+    transcribe_file_internal(
+        &app,
+        &path,
+        WhisperResponseFormat::Text,
+        &api_key,
+        &model,
+        language.as_deref(),
+    ).await
+}
+
+/// Parse a response format string, defaulting to plain text
+fn parse_response_format(response_format: &str) -> WhisperResponseFormat {
+    match response_format.to_lowercase().as_str() {
+        "text" => WhisperResponseFormat::Text,
+        "srt" => WhisperResponseFormat::Srt,
+        "vtt" => WhisperResponseFormat::Vtt,
+        "json" => WhisperResponseFormat::Json,
+        _ => WhisperResponseFormat::Text,
+    }
+}
+
+/// Pick the OpenAI key used for Whisper: the main key for the OpenAI provider,
+/// otherwise the separate Whisper key
+pub(crate) fn whisper_api_key(config: &AIConfig) -> Option<String> {
+    let key = match config.provider {
+        AIProvider::OpenAI => config.api_key.clone(),
+        _ => config.whisper_api_key.clone(),
+    };
+    key.filter(|k| !k.is_empty())
+}
+
+/// Transcribe a local file, extracting audio first for video containers
+async fn transcribe_file_internal(
+    app: &AppHandle,
+    video_path: &str,
+    format: WhisperResponseFormat,
+    openai_api_key: &str,
+    model: &str,
+    language: Option<&str>,
 ) -> Result<String, String> {
     add_log_internal("info", &format!("Starting Whisper transcription for: {}", video_path), None, None).ok();
     
@@ -27,20 +92,11 @@ pub async fn transcribe_video_with_whisper(
         return Err(WhisperError::NoApiKey.into());
     }
     
-    let path = Path::new(&video_path);
+    let path = Path::new(video_path);
     if !path.exists() {
-        return Err(WhisperError::FileNotFound(video_path.clone()).into());
+        return Err(WhisperError::FileNotFound(video_path.to_string()).into());
     }
     
-    // Parse response format
-    let format = match response_format.to_lowercase().as_str() {
-        "text" => WhisperResponseFormat::Text,
-        "srt" => WhisperResponseFormat::Srt,
-        "vtt" => WhisperResponseFormat::Vtt,
-        "json" => WhisperResponseFormat::Json,
-        _ => WhisperResponseFormat::Text,
-    };
-    
     // Check if we need to extract audio (video files)
     let extension = path.extension()
         .and_then(|e| e.to_str())
@@ -58,30 +114,34 @@ pub async fn transcribe_video_with_whisper(
         let audio_output_str = audio_output.to_string_lossy().to_string();
         
         // Get FFmpeg path
-        let ffmpeg_path = get_ffmpeg_path(&app).await;
+        let ffmpeg_path = get_ffmpeg_path(app).await;
         let ffmpeg_path_str = ffmpeg_path.as_ref().map(|p| p.to_string_lossy().to_string());
         
-        extract_audio_for_whisper(
-            &video_path,
+        if let Err(e) = extract_audio_for_whisper(
+            video_path,
             &audio_output_str,
             ffmpeg_path_str.as_deref(),
-        ).await.map_err(|e| e.to_string())?;
+        ).await {
+            std::fs::remove_dir_all(&temp_dir).ok();
+            return Err(e.to_string());
+        }
         
         audio_output_str
     } else {
         // Already an audio file
-        video_path.clone()
+        video_path.to_string()
     };
     
     // Transcribe with Whisper
     add_log_internal("info", "Sending audio to Whisper API...", None, None).ok();
     
-    let result = transcribe_audio(
-        &openai_api_key,
+    let result = transcribe_audio_with_model(
+        openai_api_key,
         &audio_path,
+        model,
         format,
-        language.as_deref(),
-    ).await.map_err(|e| e.to_string())?;
+        language,
+    ).await.map_err(|e| e.to_string());
     
     // Clean up temp audio file if we created one
     if audio_path != video_path {
@@ -90,6 +150,7 @@ pub async fn transcribe_video_with_whisper(
         }
     }
     
+    let result = result?;
     add_log_internal("success", &format!("Whisper transcription complete ({} chars)", result.text.len()), None, None).ok();
     
     Ok(result.text)
@@ -116,50 +177,89 @@ pub async fn transcribe_url_with_whisper(
     // Proxy
     proxy_url: Option<String>,
 ) -> Result<String, String> {
-    add_log_internal("info", &format!("Starting Whisper transcription for URL: {}", url), None, Some(&url)).ok();
+    let options = YtdlpOptions::from_settings(
+        cookie_mode.as_deref(),
+        cookie_browser.as_deref(),
+        cookie_browser_profile.as_deref(),
+        cookie_file_path.as_deref(),
+        proxy_url.as_deref(),
+    )?;
+    
+    transcribe_url_internal(
+        &app,
+        &url,
+        parse_response_format(&response_format),
+        &openai_api_key,
+        language.as_deref(),
+        &options,
+    ).await
+}
+
+/// Download the best audio stream of a URL to a temp dir and transcribe it with Whisper
+/// The temp dir is always removed afterwards
+pub(crate) async fn transcribe_url_internal(
+    app: &AppHandle,
+    url: &str,
+    format: WhisperResponseFormat,
+    openai_api_key: &str,
+    language: Option<&str>,
+    options: &YtdlpOptions,
+) -> Result<String, String> {
+    add_log_internal("info", &format!("Starting Whisper transcription for URL: {}", url), None, Some(url)).ok();
     
     if openai_api_key.is_empty() {
         return Err(WhisperError::NoApiKey.into());
     }
     
-    // Parse response format
-    let format = match response_format.to_lowercase().as_str() {
-        "text" => WhisperResponseFormat::Text,
-        "srt" => WhisperResponseFormat::Srt,
-        "vtt" => WhisperResponseFormat::Vtt,
-        "json" => WhisperResponseFormat::Json,
-        _ => WhisperResponseFormat::Text,
-    };
-    
     // Create temp directory
     let temp_dir = std::env::temp_dir().join(format!("youwee_whisper_{}", Uuid::new_v4()));
     std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
     
+    let result = download_and_transcribe(app, url, &temp_dir, format, openai_api_key, language, options).await;
+    
+    // Clean up temp files
+    std::fs::remove_dir_all(&temp_dir).ok();
+    
+    let text = result?;
+    add_log_internal(
+        "success",
+        &format!("Whisper transcription complete ({} chars)", text.len()),
+        None,
+        Some(url),
+    ).ok();
+    
+    Ok(text)
+}
+
+async fn download_and_transcribe(
+    app: &AppHandle,
+    url: &str,
+    temp_dir: &Path,
+    format: WhisperResponseFormat,
+    openai_api_key: &str,
+    language: Option<&str>,
+    options: &YtdlpOptions,
+) -> Result<String, String> {
     let audio_output = temp_dir.join("audio.mp3");
     let audio_output_str = audio_output.to_string_lossy().to_string();
     
     // Download audio only using yt-dlp
-    add_log_internal("info", "Downloading audio for Whisper transcription...", None, Some(&url)).ok();
+    add_log_internal("info", "Downloading audio for Whisper transcription...", None, Some(url)).ok();
     
     let download_args = vec![
+        "-f", "bestaudio/best",
         "-x", // Extract audio
         "--audio-format", "mp3",
         "--audio-quality", "5", // Medium quality (smaller file)
         "-o", &audio_output_str,
         "--no-playlist",
         "--no-warnings",
-        &url,
+        url,
     ];
     
-    let result = run_ytdlp_with_stderr_and_cookies(
-        &app,
-        &download_args,
-        cookie_mode.as_deref(),
-        cookie_browser.as_deref(),
-        cookie_browser_profile.as_deref(),
-        cookie_file_path.as_deref(),
-        proxy_url.as_deref(),
-    ).await.map_err(|e| format!("Failed to download audio: {}", e))?;
+    let result = run_ytdlp_with_stderr_and_options(app, &download_args, options)
+        .await
+        .map_err(|e| format!("Failed to download audio: {}", e))?;
     
     #[cfg(debug_assertions)]
     {
@@ -178,7 +278,7 @@ pub async fn transcribe_url_with_whisper(
             audio_file = with_ext;
         } else {
             // Try to find any audio file in temp dir
-            if let Ok(entries) = std::fs::read_dir(&temp_dir) {
+            if let Ok(entries) = std::fs::read_dir(temp_dir) {
                 for entry in entries.flatten() {
                     let path = entry.path();
                     if let Some(ext) = path.extension() {
@@ -193,7 +293,6 @@ pub async fn transcribe_url_with_whisper(
     }
     
     if !Path::new(&audio_file).exists() {
-        std::fs::remove_dir_all(&temp_dir).ok();
         return Err("Failed to download audio from URL".to_string());
     }
     
@@ -204,10 +303,10 @@ pub async fn transcribe_url_with_whisper(
     let max_size: u64 = 25 * 1024 * 1024; // 25 MB
     
     if metadata.len() > max_size {
-        add_log_internal("info", "Audio file too large, compressing...", None, Some(&url)).ok();
+        add_log_internal("info", "Audio file too large, compressing...", None, Some(url)).ok();
         
         // Get FFmpeg path
-        let ffmpeg_path = get_ffmpeg_path(&app).await;
+        let ffmpeg_path = get_ffmpeg_path(app).await;
         let ffmpeg_path_str = ffmpeg_path.as_ref().map(|p| p.to_string_lossy().to_string());
         
         let compressed_output = temp_dir.join("audio_compressed.mp3");
@@ -223,25 +322,16 @@ pub async fn transcribe_url_with_whisper(
     }
     
     // Transcribe with Whisper
-    add_log_internal("info", "Sending audio to Whisper API...", None, Some(&url)).ok();
+    add_log_internal("info", "Sending audio to Whisper API...", None, Some(url)).ok();
     
-    let whisper_result = transcribe_audio(
-        &openai_api_key,
+    let whisper_result = transcribe_audio_with_model(
+        openai_api_key,
         &audio_file,
+        DEFAULT_WHISPER_MODEL,
         format,
-        language.as_deref(),
+        language,
     ).await.map_err(|e| e.to_string())?;
     
-    // Clean up temp files
-    std::fs::remove_dir_all(&temp_dir).ok();
-    
-    add_log_internal(
-        "success",
-        &format!("Whisper transcription complete ({} chars)", whisper_result.text.len()),
-        None,
-        Some(&url),
-    ).ok();
-    
     Ok(whisper_result.text)
 }
 
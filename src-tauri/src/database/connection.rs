@@ -1,6 +1,7 @@
 use rusqlite::Connection;
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
+use super::migrations::run_migrations;
 
 // Global database connection wrapped in Mutex for thread safety
 pub static DB_CONNECTION: std::sync::OnceLock<Mutex<Connection>> = std::sync::OnceLock::new();
@@ -23,113 +24,10 @@ pub fn init_database(app: &AppHandle) -> Result<(), String> {
 
     let db_path = app_data_dir.join("logs.db");
 
-    let conn = Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+    let mut conn = Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
 
-    // Create logs table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS logs (
-            id TEXT PRIMARY KEY,
-            timestamp TEXT NOT NULL,
-            log_type TEXT NOT NULL,
-            message TEXT NOT NULL,
-            details TEXT,
-            url TEXT,
-            created_at INTEGER NOT NULL
-        )",
-        [],
-    )
-    .map_err(|e| format!("Failed to create logs table: {}", e))?;
-
-    // Create indexes
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_logs_type ON logs(log_type)",
-        [],
-    )
-    .ok();
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_logs_created ON logs(created_at DESC)",
-        [],
-    )
-    .ok();
-
-    // Create history table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS history (
-            id TEXT PRIMARY KEY,
-            url TEXT NOT NULL,
-            title TEXT NOT NULL,
-            thumbnail TEXT,
-            filepath TEXT NOT NULL,
-            filesize INTEGER,
-            duration INTEGER,
-            quality TEXT,
-            format TEXT,
-            source TEXT,
-            downloaded_at INTEGER NOT NULL,
-            summary TEXT
-        )",
-        [],
-    )
-    .map_err(|e| format!("Failed to create history table: {}", e))?;
-
-    // Migration: Add summary column if it doesn't exist
-    conn.execute("ALTER TABLE history ADD COLUMN summary TEXT", [])
-        .ok(); // Ignore error if column already exists
-
-    // Create history indexes
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_history_downloaded ON history(downloaded_at DESC)",
-        [],
-    )
-    .ok();
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_history_source ON history(source)",
-        [],
-    )
-    .ok();
-
-    // Create processing_jobs table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS processing_jobs (
-            id TEXT PRIMARY KEY,
-            input_path TEXT NOT NULL,
-            output_path TEXT,
-            task_type TEXT NOT NULL,
-            user_prompt TEXT,
-            ffmpeg_command TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'pending',
-            progress REAL DEFAULT 0,
-            error_message TEXT,
-            created_at TEXT NOT NULL,
-            completed_at TEXT
-        )",
-        [],
-    )
-    .map_err(|e| format!("Failed to create processing_jobs table: {}", e))?;
-
-    // Create processing_presets table
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS processing_presets (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            description TEXT,
-            task_type TEXT NOT NULL,
-            prompt_template TEXT NOT NULL,
-            icon TEXT,
-            created_at TEXT NOT NULL
-        )",
-        [],
-    )
-    .map_err(|e| format!("Failed to create processing_presets table: {}", e))?;
-
-    // Create processing indexes
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_processing_jobs_created ON processing_jobs(created_at DESC)",
-        [],
-    )
-    .ok();
+    // Create or upgrade tables
+    run_migrations(&mut conn)?;
 
     DB_CONNECTION
         .set(Mutex::new(conn))
@@ -0,0 +1,138 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use chrono::Utc;
+use super::get_db;
+use crate::types::{DownloadError, DownloadJob, FailedDownload};
+
+/// Record a download as started, replacing an earlier run with the same id
+pub fn save_download_job(job: &DownloadJob) -> Result<(), String> {
+    let conn = get_db()?;
+    insert_download_job(&conn, job, Utc::now().timestamp())
+}
+
+fn insert_download_job(conn: &Connection, job: &DownloadJob, now: i64) -> Result<(), String> {
+    let spec = serde_json::to_string(job)
+        .map_err(|e| format!("Failed to serialize download job: {}", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO download_jobs (id, url, title, spec, status, error_code, error_message, updated_at)
+         VALUES (?1, ?2, ?3, ?4, 'running', NULL, NULL, ?5)",
+        params![job.id, job.url, job.title, spec, now],
+    )
+    .map_err(|e| format!("Failed to save download job: {}", e))?;
+    Ok(())
+}
+
+/// Mark a download as failed so it shows up in `get_failed_downloads_from_db`
+pub fn mark_download_job_failed(id: &str, error: &DownloadError) -> Result<(), String> {
+    let conn = get_db()?;
+    set_download_job_failed(&conn, id, error, Utc::now().timestamp())
+}
+
+fn set_download_job_failed(conn: &Connection, id: &str, error: &DownloadError, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE download_jobs SET status = 'failed', error_code = ?1, error_message = ?2, updated_at = ?3 WHERE id = ?4",
+        params![error.code(), error.to_string(), now, id],
+    )
+    .map_err(|e| format!("Failed to update download job: {}", e))?;
+    Ok(())
+}
+
+/// Forget a download that finished or was cancelled; only failures are kept for retrying
+pub fn delete_download_job(id: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM download_jobs WHERE id = ?1", params![id])
+        .map_err(|e| format!("Failed to delete download job: {}", e))?;
+    Ok(())
+}
+
+/// Parameters of a failed download, to run it again
+pub fn get_failed_download_job(id: &str) -> Result<Option<DownloadJob>, String> {
+    let conn = get_db()?;
+    query_failed_download_job(&conn, id)
+}
+
+fn query_failed_download_job(conn: &Connection, id: &str) -> Result<Option<DownloadJob>, String> {
+    let spec: Option<String> = conn
+        .query_row(
+            "SELECT spec FROM download_jobs WHERE id = ?1 AND status = 'failed'",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read download job: {}", e))?;
+    spec.map(|spec| {
+        serde_json::from_str(&spec).map_err(|e| format!("Failed to read download job: {}", e))
+    })
+    .transpose()
+}
+
+/// Downloads that ended in an error, most recent first
+pub fn get_failed_downloads_from_db() -> Result<Vec<FailedDownload>, String> {
+    let conn = get_db()?;
+    query_failed_downloads(&conn)
+}
+
+fn query_failed_downloads(conn: &Connection) -> Result<Vec<FailedDownload>, String> {
+    let map_err = |e: rusqlite::Error| format!("Failed to read failed downloads: {}", e);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, title, error_code, error_message, updated_at FROM download_jobs
+             WHERE status = 'failed' ORDER BY updated_at DESC",
+        )
+        .map_err(map_err)?;
+    let rows = stmt
+        .query_map([], |row| {
+            let failed_at: i64 = row.get(5)?;
+            Ok(FailedDownload {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                error_code: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                error_message: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                failed_at: chrono::DateTime::from_timestamp(failed_at, 0)
+                    .map(|d| d.to_rfc3339())
+                    .unwrap_or_default(),
+            })
+        })
+        .map_err(map_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(map_err)?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str) -> DownloadJob {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "output_path": "/tmp",
+            "quality": "1080",
+            "format": "mp4",
+            "title": "Some video",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_failed_download_jobs() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        super::super::migrations::run_migrations(&mut conn).unwrap();
+        insert_download_job(&conn, &job("a"), 100).unwrap();
+        insert_download_job(&conn, &job("b"), 100).unwrap();
+        set_download_job_failed(&conn, "a", &DownloadError::NetworkTimeout, 200).unwrap();
+
+        // Running jobs are neither listed nor retryable
+        assert!(query_failed_download_job(&conn, "b").unwrap().is_none());
+        let failed = query_failed_downloads(&conn).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, "a");
+        assert_eq!(failed[0].error_code, "network_timeout");
+        assert_eq!(failed[0].title.as_deref(), Some("Some video"));
+
+        let retry = query_failed_download_job(&conn, "a").unwrap().unwrap();
+        assert_eq!(retry.quality, "1080");
+        assert_eq!(retry.output_path, "/tmp");
+    }
+}
@@ -1,7 +1,7 @@
 use super::get_db;
-use crate::types::HistoryEntry;
+use crate::types::{DailyCount, DownloadStats, HistoryEntry, StatCount};
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
 
 /// Add a history entry (internal use)
 pub fn add_history_internal(
@@ -15,17 +15,47 @@ pub fn add_history_internal(
     format: Option<String>,
     source: Option<String>,
 ) -> Result<String, String> {
+    let entry = HistoryEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        url,
+        title,
+        thumbnail,
+        filepath,
+        filesize,
+        duration,
+        quality,
+        format,
+        source,
+        channel: None,
+        downloaded_at: Utc::now().to_rfc3339(),
+        file_exists: true,
+        summary: None,
+        summary_provider: None,
+        summary_model: None,
+        tags: Vec::new(),
+    };
+    insert_history(&entry)?;
+    Ok(entry.id)
+}
+
+/// Insert (or replace) a history row with the entry's id
+/// `downloaded_at` is RFC 3339; the current time is used if it doesn't parse
+pub fn insert_history(entry: &HistoryEntry) -> Result<(), String> {
     let conn = get_db()?;
-    let id = uuid::Uuid::new_v4().to_string();
-    let now = Utc::now().timestamp();
+    let downloaded_at = chrono::DateTime::parse_from_rfc3339(&entry.downloaded_at)
+        .map(|d| d.timestamp())
+        .unwrap_or_else(|_| Utc::now().timestamp());
 
     // Get max entries from default (500)
     let max_entries: i64 = 500;
 
     conn.execute(
-        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-        params![id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, now],
+        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, channel)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            entry.id, entry.url, entry.title, entry.thumbnail, entry.filepath, entry.filesize,
+            entry.duration, entry.quality, entry.format, entry.source, downloaded_at, entry.channel
+        ],
     ).map_err(|e| format!("Failed to add history: {}", e))?;
 
     // Prune old entries
@@ -34,20 +64,51 @@ pub fn add_history_internal(
         params![max_entries],
     ).ok();
 
-    Ok(id)
+    Ok(())
 }
 
-/// Update summary for a history entry
-pub fn update_history_summary(id: String, summary: String) -> Result<(), String> {
+/// Update summary for a history entry, recording the provider and model that wrote it
+/// (None for summaries not generated here, e.g. edited by hand)
+pub fn update_history_summary(
+    id: String,
+    summary: String,
+    provider: Option<String>,
+    model: Option<String>,
+) -> Result<(), String> {
     let conn = get_db()?;
     conn.execute(
-        "UPDATE history SET summary = ?1 WHERE id = ?2",
-        params![summary, id],
+        "UPDATE history SET summary = ?1, summary_provider = ?2, summary_model = ?3 WHERE id = ?4",
+        params![summary, provider, model, id],
     )
     .map_err(|e| format!("Failed to update summary: {}", e))?;
     Ok(())
 }
 
+/// Store the transcript a summary was generated from
+pub fn update_history_transcript(id: String, transcript: String) -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute(
+        "UPDATE history SET transcript = ?1 WHERE id = ?2",
+        params![transcript, id],
+    )
+    .map_err(|e| format!("Failed to update transcript: {}", e))?;
+    Ok(())
+}
+
+/// Stored transcript of a history entry, if any
+/// Kept out of HistoryEntry so history lists don't carry full transcripts
+pub fn get_history_transcript(id: &str) -> Result<Option<String>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT transcript FROM history WHERE id = ?1",
+        params![id],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| format!("Failed to read transcript: {}", e))
+}
+
 /// Update a history entry with download info (for re-downloads)
 pub fn update_history_download(
     id: String,
@@ -74,6 +135,8 @@ pub fn add_history_with_summary(
     duration: Option<u64>,
     source: Option<String>,
     summary: String,
+    summary_provider: Option<String>,
+    summary_model: Option<String>,
 ) -> Result<String, String> {
     let conn = get_db()?;
     let id = uuid::Uuid::new_v4().to_string();
@@ -83,14 +146,140 @@ pub fn add_history_with_summary(
     let filepath = "";
 
     conn.execute(
-        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-        params![id, url, title, thumbnail, filepath, Option::<u64>::None, duration, Option::<String>::None, Option::<String>::None, source, now, summary],
+        "INSERT OR REPLACE INTO history (id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![id, url, title, thumbnail, filepath, Option::<u64>::None, duration, Option::<String>::None, Option::<String>::None, source, now, summary, summary_provider, summary_model],
     ).map_err(|e| format!("Failed to add history: {}", e))?;
 
     Ok(id)
 }
 
+/// Map a history row (id, url, title, thumbnail, filepath, filesize, duration, quality,
+/// format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel) to a HistoryEntry
+fn parse_history_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    let filepath: String = row.get(4)?;
+    let file_exists = std::path::Path::new(&filepath).exists();
+    let downloaded_at: i64 = row.get(10)?;
+    let dt = chrono::DateTime::from_timestamp(downloaded_at, 0)
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_default();
+
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        thumbnail: row.get(3)?,
+        filepath,
+        filesize: row.get(5)?,
+        duration: row.get(6)?,
+        quality: row.get(7)?,
+        format: row.get(8)?,
+        source: row.get(9)?,
+        channel: row.get(15)?,
+        downloaded_at: dt,
+        file_exists,
+        summary: row.get(11)?,
+        summary_provider: row.get(12)?,
+        summary_model: row.get(13)?,
+        tags: parse_tags(row.get::<_, Option<String>>(14)?.as_deref()),
+    })
+}
+
+/// Longest tag accepted by add_history_tag
+const MAX_TAG_LEN: usize = 50;
+
+/// Split the comma-separated `tags` column
+fn parse_tags(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Trim a tag and check it can be stored in the comma-separated column
+fn validate_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is empty".to_string());
+    }
+    if tag.contains(',') {
+        return Err("Tags cannot contain commas".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(format!("Tags can be at most {} characters", MAX_TAG_LEN));
+    }
+    Ok(tag.to_string())
+}
+
+/// Read, change and store the tags of one entry; returns the updated tags
+fn modify_history_tags(id: &str, change: impl FnOnce(&mut Vec<String>)) -> Result<Vec<String>, String> {
+    let conn = get_db()?;
+    let current: Option<String> = conn
+        .query_row("SELECT tags FROM history WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+        .map_err(|e| format!("Failed to read tags: {}", e))?
+        .ok_or_else(|| "History entry not found".to_string())?;
+
+    let mut tags = parse_tags(current.as_deref());
+    change(&mut tags);
+
+    let stored = if tags.is_empty() { None } else { Some(tags.join(",")) };
+    conn.execute("UPDATE history SET tags = ?1 WHERE id = ?2", params![stored, id])
+        .map_err(|e| format!("Failed to update tags: {}", e))?;
+    Ok(tags)
+}
+
+/// Add a tag to a history entry (no-op if it already has it, ignoring case)
+pub fn add_history_tag_in_db(id: &str, tag: &str) -> Result<Vec<String>, String> {
+    let tag = validate_tag(tag)?;
+    modify_history_tags(id, |tags| {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            tags.push(tag);
+        }
+    })
+}
+
+/// Remove a tag from a history entry, ignoring case
+pub fn remove_history_tag_in_db(id: &str, tag: &str) -> Result<Vec<String>, String> {
+    let tag = tag.trim();
+    modify_history_tags(id, |tags| tags.retain(|t| !t.eq_ignore_ascii_case(tag)))
+}
+
+/// History entries carrying `tag` (ignoring case), newest first
+pub fn get_history_by_tag_from_db(tag: &str) -> Result<Vec<HistoryEntry>, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = get_db()?;
+    query_history_by_tag(&conn, tag)
+}
+
+/// Entries whose comma-separated `tags` column contains `tag`, ignoring case
+fn query_history_by_tag(conn: &Connection, tag: &str) -> Result<Vec<HistoryEntry>, String> {
+    // Escape LIKE wildcards so the tag matches literally
+    let escaped = tag.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    let pattern = format!("%,{},%", escaped);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel
+             FROM history WHERE ',' || tags || ',' LIKE ?1 COLLATE NOCASE ESCAPE '\\' ORDER BY downloaded_at DESC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let entries = stmt
+        .query_map(params![pattern], parse_history_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read history: {}", e))?;
+    Ok(entries)
+}
+
 /// Get history entries
 pub fn get_history_from_db(
     limit: Option<i64>,
@@ -103,7 +292,7 @@ pub fn get_history_from_db(
     let offset = offset.unwrap_or(0);
 
     let mut query = String::from(
-        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary 
+        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel
          FROM history WHERE 1=1"
     );
 
@@ -122,39 +311,14 @@ pub fn get_history_from_db(
         .prepare(&query)
         .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
-    fn parse_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
-        let filepath: String = row.get(4)?;
-        let file_exists = std::path::Path::new(&filepath).exists();
-        let downloaded_at: i64 = row.get(10)?;
-        let dt = chrono::DateTime::from_timestamp(downloaded_at, 0)
-            .map(|d| d.to_rfc3339())
-            .unwrap_or_default();
-
-        Ok(HistoryEntry {
-            id: row.get(0)?,
-            url: row.get(1)?,
-            title: row.get(2)?,
-            thumbnail: row.get(3)?,
-            filepath,
-            filesize: row.get(5)?,
-            duration: row.get(6)?,
-            quality: row.get(7)?,
-            format: row.get(8)?,
-            source: row.get(9)?,
-            downloaded_at: dt,
-            file_exists,
-            summary: row.get(11)?,
-        })
-    }
-
     let entries: Vec<HistoryEntry> = if source_filter {
         let s = source.as_ref().unwrap();
-        stmt.query_map(params![s, limit, offset], parse_row)
+        stmt.query_map(params![s, limit, offset], parse_history_row)
             .map_err(|e| format!("Query failed: {}", e))?
             .filter_map(|r| r.ok())
             .collect()
     } else {
-        stmt.query_map(params![limit, offset], parse_row)
+        stmt.query_map(params![limit, offset], parse_history_row)
             .map_err(|e| format!("Query failed: {}", e))?
             .filter_map(|r| r.ok())
             .collect()
@@ -163,6 +327,71 @@ pub fn get_history_from_db(
     Ok(entries)
 }
 
+/// Search history by title or URL, newest first
+/// Returns the requested page and the total number of matches
+pub fn search_history_from_db(
+    limit: u32,
+    offset: u32,
+    query: Option<String>,
+) -> Result<(Vec<HistoryEntry>, i64), String> {
+    let conn = get_db()?;
+
+    let limit = limit.clamp(1, 500);
+    // Escape LIKE wildcards so user input matches literally
+    let pattern = query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| {
+            let escaped = q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            format!("%{}%", escaped)
+        });
+
+    // ?1 is bound in both cases so the count and page queries share parameters
+    let where_clause = if pattern.is_some() {
+        "WHERE title LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\'"
+    } else {
+        "WHERE ?1 IS NULL"
+    };
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM history {}", where_clause),
+            params![pattern],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count history: {}", e))?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel
+             FROM history {} ORDER BY downloaded_at DESC LIMIT ?2 OFFSET ?3",
+            where_clause
+        ))
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let entries: Vec<HistoryEntry> = stmt
+        .query_map(params![pattern, limit, offset], parse_history_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok((entries, total))
+}
+
+/// Get a single history entry by id
+pub fn get_history_entry_from_db(id: &str) -> Result<Option<HistoryEntry>, String> {
+    let conn = get_db()?;
+    conn.query_row(
+        "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel
+         FROM history WHERE id = ?1",
+        params![id],
+        parse_history_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to get history entry: {}", e))
+}
+
 /// Delete a history entry
 pub fn delete_history_from_db(id: String) -> Result<(), String> {
     let conn = get_db()?;
@@ -179,6 +408,86 @@ pub fn clear_history_from_db() -> Result<(), String> {
     Ok(())
 }
 
+/// Write the whole history to `path` as "json" (an array of HistoryEntry) or "csv".
+/// Returns the number of entries written.
+pub fn export_history_to_file(path: &str, format: &str) -> Result<usize, String> {
+    let format = format.trim().to_lowercase();
+    if format != "json" && format != "csv" {
+        return Err(format!("Unsupported export format: {} (expected json or csv)", format));
+    }
+
+    let entries = {
+        let conn = get_db()?;
+        query_all_history(&conn)?
+    };
+
+    let content = if format == "json" {
+        serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize history: {}", e))?
+    } else {
+        history_to_csv(&entries)
+    };
+
+    std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(entries.len())
+}
+
+/// Every history entry, newest first
+fn query_all_history(conn: &Connection) -> Result<Vec<HistoryEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, title, thumbnail, filepath, filesize, duration, quality, format, source, downloaded_at, summary, summary_provider, summary_model, tags, channel
+             FROM history ORDER BY downloaded_at DESC",
+        )
+        .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+    let entries = stmt
+        .query_map([], parse_history_row)
+        .map_err(|e| format!("Query failed: {}", e))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| format!("Failed to read history: {}", e))?;
+    Ok(entries)
+}
+
+/// Render history as CSV (RFC 4180) with a header row
+fn history_to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from(
+        "id,url,title,thumbnail,filepath,filesize,duration,quality,format,source,downloaded_at,summary,summary_provider,summary_model,tags\r\n",
+    );
+    for entry in entries {
+        let fields = [
+            entry.id.clone(),
+            entry.url.clone(),
+            entry.title.clone(),
+            entry.thumbnail.clone().unwrap_or_default(),
+            entry.filepath.clone(),
+            entry.filesize.map(|v| v.to_string()).unwrap_or_default(),
+            entry.duration.map(|v| v.to_string()).unwrap_or_default(),
+            entry.quality.clone().unwrap_or_default(),
+            entry.format.clone().unwrap_or_default(),
+            entry.source.clone().unwrap_or_default(),
+            entry.downloaded_at.clone(),
+            entry.summary.clone().unwrap_or_default(),
+            entry.summary_provider.clone().unwrap_or_default(),
+            entry.summary_model.clone().unwrap_or_default(),
+            entry.tags.join(","),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break, doubling inner quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Get history count
 pub fn get_history_count_from_db() -> Result<i64, String> {
     let conn = get_db()?;
@@ -187,3 +496,181 @@ pub fn get_history_count_from_db() -> Result<i64, String> {
         .map_err(|e| format!("Failed to count history: {}", e))?;
     Ok(count)
 }
+
+/// Days covered by `DownloadStats::per_day`
+pub const STATS_DAYS: i64 = 30;
+
+/// Aggregate the history table for the statistics view
+pub fn get_download_stats_from_db() -> Result<DownloadStats, String> {
+    let conn = get_db()?;
+    query_download_stats(&conn, Utc::now().timestamp())
+}
+
+/// Download statistics as of `now` (Unix timestamp). Summary-only entries (no file) are left out
+fn query_download_stats(conn: &Connection, now: i64) -> Result<DownloadStats, String> {
+    let map_err = |e: rusqlite::Error| format!("Failed to compute download stats: {}", e);
+    
+    let (total_downloads, total_bytes): (i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(filesize), 0) FROM history WHERE filepath != ''",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(map_err)?;
+    
+    let counts = |sql: &str, params: &[&dyn rusqlite::ToSql]| -> Result<Vec<StatCount>, String> {
+        let mut stmt = conn.prepare(sql).map_err(map_err)?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok(StatCount {
+                    name: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as u64,
+                })
+            })
+            .map_err(map_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(map_err)?;
+        Ok(rows)
+    };
+    
+    let by_format = counts(
+        "SELECT COALESCE(NULLIF(format, ''), 'unknown') AS name, COUNT(*) AS n FROM history
+         WHERE filepath != '' GROUP BY name ORDER BY n DESC, name",
+        &[],
+    )?;
+    let by_source = counts(
+        "SELECT COALESCE(NULLIF(source, ''), 'unknown') AS name, COUNT(*) AS n FROM history
+         WHERE filepath != '' GROUP BY name ORDER BY n DESC, name",
+        &[],
+    )?;
+    let top_channel = counts(
+        "SELECT channel, COUNT(*) AS n FROM history
+         WHERE filepath != '' AND channel IS NOT NULL AND channel != ''
+         GROUP BY channel ORDER BY n DESC, channel LIMIT 1",
+        &[],
+    )?
+    .into_iter()
+    .next();
+    
+    // Midnight (UTC) of the first day in the window
+    let today = now.div_euclid(86_400) * 86_400;
+    let since = today - (STATS_DAYS - 1) * 86_400;
+    let daily = counts(
+        "SELECT date(downloaded_at, 'unixepoch') AS day, COUNT(*) FROM history
+         WHERE filepath != '' AND downloaded_at >= ?1 AND downloaded_at < ?2 + 86400 GROUP BY day",
+        &[&since, &today],
+    )?;
+    let per_day = (0..STATS_DAYS)
+        .filter_map(|offset| chrono::DateTime::from_timestamp(since + offset * 86_400, 0))
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let count = daily.iter().find(|d| d.name == date).map_or(0, |d| d.count);
+            DailyCount { date, count }
+        })
+        .collect();
+    
+    Ok(DownloadStats {
+        total_downloads: total_downloads as u64,
+        total_bytes: total_bytes as u64,
+        by_format,
+        by_source,
+        per_day,
+        top_channel,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        super::super::migrations::run_migrations(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO history (id, url, title, filepath, filesize, duration, quality, format, source, downloaded_at, summary)
+             VALUES ('a', 'https://www.youtube.com/watch?v=a', 'Plain title', '/tmp/a.mp4', 1024, 60, '1080', 'mp4', 'youtube', 1700000000, NULL);
+             INSERT INTO history (id, url, title, filepath, downloaded_at, summary)
+             VALUES ('b', 'https://example.com/b', 'Tricky, \"quoted\"\ntitle', '/tmp/b.mp4', 1700000100, 'Line one\nLine two');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_export_history_csv_escaping() {
+        let entries = query_all_history(&fixture_db()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "b");
+
+        let csv = history_to_csv(&entries);
+        let mut lines = csv.split("\r\n");
+        assert!(lines.next().unwrap().starts_with("id,url,title,"));
+        assert!(csv.contains(",\"Tricky, \"\"quoted\"\"\ntitle\",,/tmp/b.mp4,"));
+        assert!(csv.contains(",\"Line one\nLine two\","));
+        assert!(csv.contains("a,https://www.youtube.com/watch?v=a,Plain title,,/tmp/a.mp4,1024,60,1080,mp4,youtube,"));
+        assert!(csv.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn test_tags() {
+        assert_eq!(parse_tags(Some(" work, music ,,")), vec!["work", "music"]);
+        assert!(parse_tags(None).is_empty());
+        assert_eq!(validate_tag("  project x ").unwrap(), "project x");
+        assert!(validate_tag("a,b").is_err());
+        assert!(validate_tag("   ").is_err());
+
+        let conn = fixture_db();
+        conn.execute("UPDATE history SET tags = 'work,Music' WHERE id = 'a'", []).unwrap();
+        let entries = query_all_history(&conn).unwrap();
+        assert_eq!(entries[1].tags, vec!["work", "Music"]);
+        assert!(entries[0].tags.is_empty());
+
+        let tagged = query_history_by_tag(&conn, "music").unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, "a");
+        assert!(query_history_by_tag(&conn, "mus").unwrap().is_empty());
+        assert!(query_history_by_tag(&conn, "%").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_download_stats() {
+        let conn = fixture_db();
+        conn.execute_batch(
+            "UPDATE history SET channel = 'Chan A' WHERE id = 'a';
+             UPDATE history SET channel = 'Chan B' WHERE id = 'b';
+             INSERT INTO history (id, url, title, filepath, filesize, format, source, channel, downloaded_at)
+             VALUES ('c', 'https://www.youtube.com/watch?v=c', 'Song', '/tmp/c.mp3', 2048, 'mp3', 'youtube', 'Chan A', 1699900000);
+             INSERT INTO history (id, url, title, filepath, source, channel, downloaded_at, summary)
+             VALUES ('d', 'https://www.youtube.com/watch?v=d', 'Summary only', '', 'youtube', 'Chan B', 1700000000, 'Text');",
+        )
+        .unwrap();
+
+        // 2023-11-14 23:13 UTC, the day of entries a and b
+        let stats = query_download_stats(&conn, 1_700_003_600).unwrap();
+        assert_eq!(stats.total_downloads, 3);
+        assert_eq!(stats.total_bytes, 3072);
+        let names = |counts: &[StatCount]| counts.iter().map(|c| (c.name.clone(), c.count)).collect::<Vec<_>>();
+        assert_eq!(
+            names(&stats.by_format),
+            vec![("mp3".to_string(), 1), ("mp4".to_string(), 1), ("unknown".to_string(), 1)]
+        );
+        assert_eq!(names(&stats.by_source), vec![("youtube".to_string(), 2), ("unknown".to_string(), 1)]);
+        assert_eq!(stats.top_channel, Some(StatCount { name: "Chan A".to_string(), count: 2 }));
+
+        assert_eq!(stats.per_day.len(), STATS_DAYS as usize);
+        assert_eq!(stats.per_day[29], DailyCount { date: "2023-11-14".to_string(), count: 2 });
+        assert_eq!(stats.per_day[28], DailyCount { date: "2023-11-13".to_string(), count: 1 });
+        assert_eq!(stats.per_day[0].date, "2023-10-16");
+        assert_eq!(stats.per_day.iter().map(|d| d.count).sum::<u64>(), 3);
+    }
+
+    #[test]
+    fn test_export_history_json_roundtrip() {
+        let entries = query_all_history(&fixture_db()).unwrap();
+        let json = serde_json::to_string(&entries).unwrap();
+        let parsed: Vec<HistoryEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].title, "Tricky, \"quoted\"\ntitle");
+        assert_eq!(parsed[1].filesize, Some(1024));
+    }
+}
@@ -0,0 +1,47 @@
+use rusqlite::{params, OptionalExtension};
+use chrono::Utc;
+use super::get_db;
+
+/// Get a cached video info JSON blob if it is younger than `max_age_secs`
+pub fn get_cached_info_from_db(url_key: &str, max_age_secs: i64) -> Result<Option<String>, String> {
+    let conn = get_db()?;
+    let min_created = Utc::now().timestamp() - max_age_secs;
+    
+    conn.query_row(
+        "SELECT data FROM info_cache WHERE url_key = ?1 AND created_at >= ?2",
+        params![url_key, min_created],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read info cache: {}", e))
+}
+
+/// Store a video info JSON blob, replacing any previous entry for the URL
+pub fn save_info_to_db(url_key: &str, data: &str) -> Result<(), String> {
+    let conn = get_db()?;
+    let created_at = Utc::now().timestamp();
+    
+    conn.execute(
+        "INSERT OR REPLACE INTO info_cache (url_key, data, created_at) VALUES (?1, ?2, ?3)",
+        params![url_key, data, created_at],
+    ).map_err(|e| format!("Failed to write info cache: {}", e))?;
+    
+    Ok(())
+}
+
+/// Remove entries older than `max_age_secs`
+pub fn prune_info_cache_in_db(max_age_secs: i64) -> Result<(), String> {
+    let conn = get_db()?;
+    let min_created = Utc::now().timestamp() - max_age_secs;
+    conn.execute("DELETE FROM info_cache WHERE created_at < ?1", params![min_created])
+        .map_err(|e| format!("Failed to prune info cache: {}", e))?;
+    Ok(())
+}
+
+/// Clear all cached video info
+pub fn clear_info_cache_from_db() -> Result<(), String> {
+    let conn = get_db()?;
+    conn.execute("DELETE FROM info_cache", [])
+        .map_err(|e| format!("Failed to clear info cache: {}", e))?;
+    Ok(())
+}
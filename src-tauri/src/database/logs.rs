@@ -1,4 +1,4 @@
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use chrono::Utc;
 use crate::types::LogEntry;
 use super::{get_db, MAX_LOG_ENTRIES};
@@ -22,7 +22,20 @@ pub fn add_log_internal(
         params![id, timestamp, log_type, message, details, url, created_at],
     ).map_err(|e| format!("Failed to insert log: {}", e))?;
     
-    // Prune old entries if exceeding limit
+    rotate_logs(&conn);
+    
+    Ok(LogEntry {
+        id,
+        timestamp,
+        log_type: log_type.to_string(),
+        message: message.to_string(),
+        details: details.map(|s| s.to_string()),
+        url: url.map(|s| s.to_string()),
+    })
+}
+
+/// Delete the oldest log rows beyond `MAX_LOG_ENTRIES`
+pub fn rotate_logs(conn: &Connection) {
     let count: i64 = conn.query_row(
         "SELECT COUNT(*) FROM logs",
         [],
@@ -38,18 +51,10 @@ pub fn add_log_internal(
             params![to_delete],
         ).ok();
     }
-    
-    Ok(LogEntry {
-        id,
-        timestamp,
-        log_type: log_type.to_string(),
-        message: message.to_string(),
-        details: details.map(|s| s.to_string()),
-        url: url.map(|s| s.to_string()),
-    })
 }
 
-/// Get logs from database with optional filters
+/// Get logs from database with optional filters.
+/// `filter` matches `log_type` exactly (served by `idx_logs_type`); "all" disables it.
 pub fn get_logs_from_db(
     filter: Option<String>,
     search: Option<String>,
@@ -0,0 +1,255 @@
+use rusqlite::Connection;
+
+/// One schema change; migration N (1-based) moves the database to version N
+type Migration = fn(&Connection) -> rusqlite::Result<()>;
+
+/// Ordered schema migrations. Append new ones at the end and never edit or reorder
+/// existing entries: the schema version stored in the database is an index into this list.
+const MIGRATIONS: &[Migration] = &[
+    migration_initial_schema,
+    migration_history_summary,
+    migration_info_cache,
+    migration_summary_source,
+    migration_history_transcript,
+    migration_history_tags,
+    migration_history_channel,
+    migration_download_jobs,
+];
+
+/// Schema version of a fully migrated database
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// v1: logs, history and processing tables
+fn migration_initial_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS logs (
+            id TEXT PRIMARY KEY,
+            timestamp TEXT NOT NULL,
+            log_type TEXT NOT NULL,
+            message TEXT NOT NULL,
+            details TEXT,
+            url TEXT,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_logs_type ON logs(log_type);
+        CREATE INDEX IF NOT EXISTS idx_logs_created ON logs(created_at DESC);
+
+        CREATE TABLE IF NOT EXISTS history (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            title TEXT NOT NULL,
+            thumbnail TEXT,
+            filepath TEXT NOT NULL,
+            filesize INTEGER,
+            duration INTEGER,
+            quality TEXT,
+            format TEXT,
+            source TEXT,
+            downloaded_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_history_downloaded ON history(downloaded_at DESC);
+        CREATE INDEX IF NOT EXISTS idx_history_source ON history(source);
+
+        CREATE TABLE IF NOT EXISTS processing_jobs (
+            id TEXT PRIMARY KEY,
+            input_path TEXT NOT NULL,
+            output_path TEXT,
+            task_type TEXT NOT NULL,
+            user_prompt TEXT,
+            ffmpeg_command TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            progress REAL DEFAULT 0,
+            error_message TEXT,
+            created_at TEXT NOT NULL,
+            completed_at TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_processing_jobs_created ON processing_jobs(created_at DESC);
+
+        CREATE TABLE IF NOT EXISTS processing_presets (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            task_type TEXT NOT NULL,
+            prompt_template TEXT NOT NULL,
+            icon TEXT,
+            created_at TEXT NOT NULL
+        );",
+    )
+}
+
+/// v2: AI summary per history entry
+fn migration_history_summary(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "history", "summary", "TEXT")
+}
+
+/// v3: persistent get_video_info cache
+fn migration_info_cache(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS info_cache (
+            url_key TEXT PRIMARY KEY,
+            data TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );",
+    )
+}
+
+/// v4: which AI provider and model wrote the stored summary
+fn migration_summary_source(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "history", "summary_provider", "TEXT")?;
+    add_column_if_missing(conn, "history", "summary_model", "TEXT")
+}
+
+/// v5: transcript the summary was generated from, so it can be regenerated offline
+fn migration_history_transcript(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "history", "transcript", "TEXT")
+}
+
+/// v6: comma-separated user tags per history entry
+fn migration_history_tags(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "history", "tags", "TEXT")
+}
+
+/// v7: channel (or uploader) of the downloaded video, for download statistics
+fn migration_history_channel(conn: &Connection) -> rusqlite::Result<()> {
+    add_column_if_missing(conn, "history", "channel", "TEXT")
+}
+
+/// v8: parameters of started downloads, kept for failures so they can be retried
+fn migration_download_jobs(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS download_jobs (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            title TEXT,
+            spec TEXT NOT NULL,
+            status TEXT NOT NULL,
+            error_code TEXT,
+            error_message TEXT,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_download_jobs_status ON download_jobs(status, updated_at DESC);",
+    )
+}
+
+/// Add a column unless it already exists.
+/// Databases created before versioning may already have columns added by the old
+/// ad-hoc ALTER TABLE calls; any other failure is a real error and is returned.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))?;
+    }
+    Ok(())
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Current schema version (SQLite `user_version`; 0 for a new or pre-versioning database)
+pub fn schema_version(conn: &Connection) -> Result<u32, String> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))
+}
+
+/// Apply every pending migration in order
+pub fn run_migrations(conn: &mut Connection) -> Result<(), String> {
+    migrate_to(conn, SCHEMA_VERSION)
+}
+
+/// Apply pending migrations up to `target`. Each one runs in its own transaction
+/// together with the version bump, so a failure leaves the database at the last good version.
+fn migrate_to(conn: &mut Connection, target: u32) -> Result<(), String> {
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        return Err(format!(
+            "Database schema version {} is newer than this app supports ({}). Please update Youwee.",
+            current, SCHEMA_VERSION
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().take(target as usize).skip(current as usize) {
+        let version = index as u32 + 1;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start migration {}: {}", version, e))?;
+        migration(&tx).map_err(|e| format!("Migration {} failed: {}", version, e))?;
+        tx.pragma_update(None, "user_version", version)
+            .map_err(|e| format!("Failed to record schema version {}: {}", version, e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit migration {}: {}", version, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_exists(conn: &Connection, table: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap()
+            > 0
+    }
+
+    #[test]
+    fn test_migrate_fresh_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(column_exists(&conn, "history", "summary").unwrap());
+        assert!(column_exists(&conn, "history", "summary_model").unwrap());
+        assert!(table_exists(&conn, "info_cache"));
+        assert!(table_exists(&conn, "download_jobs"));
+
+        // Running again is a no-op
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_from_v1_keeps_data() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, 1).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+        assert!(!column_exists(&conn, "history", "summary").unwrap());
+
+        conn.execute(
+            "INSERT INTO history (id, url, title, filepath, downloaded_at) VALUES ('h1', 'https://example.com', 'Old', '/tmp/old.mp4', 1)",
+            [],
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        let (title, summary): (String, Option<String>) = conn
+            .query_row("SELECT title, summary FROM history WHERE id = 'h1'", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(title, "Old");
+        assert_eq!(summary, None);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_database_with_summary_column() {
+        // Databases from before versioning report version 0 but already have the summary column
+        let mut conn = Connection::open_in_memory().unwrap();
+        migration_initial_schema(&conn).unwrap();
+        conn.execute_batch("ALTER TABLE history ADD COLUMN summary TEXT").unwrap();
+
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+}
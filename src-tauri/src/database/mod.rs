@@ -1,7 +1,12 @@
 mod connection;
 mod logs;
 mod history;
+mod info_cache;
+mod download_jobs;
+mod migrations;
 
 pub use connection::*;
 pub use logs::*;
 pub use history::*;
+pub use info_cache::*;
+pub use download_jobs::*;
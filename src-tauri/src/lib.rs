@@ -30,6 +30,9 @@ pub fn run() {
                 log::error!("Failed to initialize database: {}", e);
             }
             
+            // Apply saved socket timeout and retry settings to yt-dlp runs
+            commands::init_network_config(app.handle());
+            
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -42,21 +45,52 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // Download commands
             commands::download_video,
+            commands::download_chapter,
+            commands::download_batch,
+            commands::download_with_profile,
+            commands::download_and_summarize,
+            commands::get_failed_downloads,
+            commands::retry_failed_download,
+            commands::import_urls_from_file,
             commands::stop_download,
+            commands::stop_all_downloads,
+            commands::pause_download,
+            commands::resume_download,
+            commands::get_filename_presets,
+            commands::simulate_download,
+            commands::estimate_playlist_size,
             // Video info commands
             commands::get_video_info,
+            commands::get_grouped_formats,
+            commands::get_raw_info,
+            commands::get_video_comments,
+            commands::cancel_video_comments,
+            commands::check_format_available,
+            commands::get_available_resolutions,
+            commands::clear_info_cache,
+            commands::classify_url,
             commands::get_playlist_entries,
+            commands::stop_playlist_fetch,
+            commands::fetch_playlist_streaming,
+            commands::get_channel_videos,
             commands::get_available_subtitles,
+            commands::download_subtitles,
+            commands::download_thumbnail,
             commands::get_video_transcript,
+            commands::get_or_fetch_transcript,
             // yt-dlp commands
             commands::get_ytdlp_version,
             commands::check_ytdlp_update,
             commands::update_ytdlp,
+            commands::ytdlp_needs_update,
+            commands::get_supported_sites,
             // FFmpeg commands
             commands::check_ffmpeg,
             commands::check_ffmpeg_update,
             commands::download_ffmpeg,
             commands::get_ffmpeg_path_for_ytdlp,
+            commands::get_tool_versions,
+            commands::run_healthcheck,
             // Bun commands
             commands::check_bun,
             commands::check_bun_update,
@@ -72,10 +106,18 @@ pub fn run() {
             // History commands
             commands::add_history,
             commands::get_history,
+            commands::get_download_history,
             commands::delete_history,
             commands::clear_history,
             commands::get_history_count,
+            commands::get_download_stats,
+            commands::export_history,
+            commands::add_history_tag,
+            commands::remove_history_tag,
+            commands::get_history_by_tag,
             commands::open_file_location,
+            commands::open_download_location,
+            commands::reveal_in_folder,
             commands::check_file_exists,
             commands::update_summary,
             commands::add_summary_only_history,
@@ -85,7 +127,12 @@ pub fn run() {
             commands::get_ai_config,
             commands::test_ai_connection,
             commands::generate_video_summary,
+            commands::generate_video_summary_stream,
+            commands::translate_transcript,
             commands::generate_summary_with_options,
+            commands::regenerate_summary,
+            commands::generate_chapters,
+            commands::ask_about_video,
             commands::get_ai_models,
             commands::get_summary_languages,
             // Processing commands
@@ -94,6 +141,9 @@ pub fn run() {
             commands::generate_quick_action_command,
             commands::execute_ffmpeg_command,
             commands::cancel_ffmpeg,
+            commands::convert_media,
+            commands::concat_media,
+            commands::export_clip,
             commands::get_processing_history,
             commands::save_processing_job,
             commands::update_processing_job,
@@ -109,6 +159,15 @@ pub fn run() {
             commands::transcribe_video_with_whisper,
             commands::transcribe_url_with_whisper,
             commands::generate_subtitles_with_whisper,
+            commands::transcribe_audio,
+            // Config commands
+            commands::get_download_config,
+            commands::save_download_config,
+            commands::get_network_config,
+            commands::save_network_config,
+            commands::save_profile,
+            commands::list_profiles,
+            commands::delete_profile,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
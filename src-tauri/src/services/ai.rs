@@ -1,5 +1,9 @@
-use reqwest::Client;
+use std::collections::HashSet;
+use std::time::Duration;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use crate::types::Chapter;
+use crate::utils::parse_timestamp;
 
 /// AI Provider options
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -11,6 +15,9 @@ pub enum AIProvider {
     Qwen,
     Ollama,
     Proxy, // OpenAI-compatible API with custom domain
+    Anthropic,
+    Groq,
+    OpenRouter,
 }
 
 impl Default for AIProvider {
@@ -43,9 +50,17 @@ pub struct AIConfig {
     pub model: String,
     pub ollama_url: Option<String>,
     pub proxy_url: Option<String>, // Custom OpenAI-compatible API endpoint
+    #[serde(default)]
+    pub base_url: Option<String>, // Overrides the OpenAI provider's API base, e.g. a LiteLLM proxy ("http://localhost:4000/v1")
     pub summary_style: SummaryStyle,
     pub summary_language: String, // "auto", "en", "vi", "ja", etc.
-    pub timeout_seconds: Option<u64>, // Timeout for AI generation (default 120s)
+    #[serde(default)]
+    pub custom_prompt: Option<String>, // Replaces the built-in persona and style instructions; the transcript is still appended
+    pub timeout_seconds: Option<u64>, // Timeout for AI requests (default 120s, not applied to Ollama)
+    #[serde(default = "default_temperature")]
+    pub temperature: f32, // Sampling temperature for summaries, 0.0–2.0
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32, // Output token limit for summaries; long, detailed summaries need more
     #[serde(default)]
     pub transcript_languages: Option<Vec<String>>, // Languages to try for transcript extraction
     // Whisper settings
@@ -64,9 +79,13 @@ impl Default for AIConfig {
             model: "gemini-2.0-flash".to_string(),
             ollama_url: Some("http://localhost:11434".to_string()),
             proxy_url: Some("https://api.openai.com".to_string()),
+            base_url: None,
             summary_style: SummaryStyle::Short,
             summary_language: "auto".to_string(),
-            timeout_seconds: Some(120),
+            custom_prompt: None,
+            timeout_seconds: Some(DEFAULT_TIMEOUT_SECS),
+            temperature: DEFAULT_TEMPERATURE,
+            max_tokens: DEFAULT_MAX_TOKENS,
             transcript_languages: Some(vec!["en".to_string()]),
             whisper_enabled: false,
             whisper_api_key: None,
@@ -110,9 +129,109 @@ impl From<AIError> for String {
     }
 }
 
-/// Build prompt based on style and language
-fn build_prompt(transcript: &str, style: &SummaryStyle, language: &str, title: Option<&str>) -> String {
-    let style_instruction = match style {
+/// Default timeout for AI requests when none is configured
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Summary temperature and output token limit when none are configured
+const DEFAULT_TEMPERATURE: f32 = 0.7;
+const DEFAULT_MAX_TOKENS: u32 = 2048;
+
+fn default_temperature() -> f32 {
+    DEFAULT_TEMPERATURE
+}
+
+fn default_max_tokens() -> u32 {
+    DEFAULT_MAX_TOKENS
+}
+
+/// Check the summary sampling settings: temperature 0.0–2.0 and a non-zero token limit
+pub fn validate_sampling(temperature: f32, max_tokens: u32) -> Result<(), String> {
+    if !(0.0..=2.0).contains(&temperature) {
+        return Err(format!("Temperature must be between 0.0 and 2.0, got {}", temperature));
+    }
+    if max_tokens == 0 {
+        return Err("Max tokens must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Temperature and output token limit sent with a generation request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampling {
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl Sampling {
+    /// For FFmpeg commands, translations, chunk summaries and other output that should stay literal
+    pub const PRECISE: Sampling = Sampling { temperature: 0.3, max_tokens: 2048 };
+    
+    /// The configured summary settings
+    pub fn summary(config: &AIConfig) -> Self {
+        Sampling {
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+        }
+    }
+}
+
+/// Attempts per request when the provider answers 429 or 5xx
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Build the HTTP client for the configured provider.
+/// The timeout bounds each wait for data rather than the whole request, so long streamed
+/// responses are not cut off. Ollama only gets a connect timeout since local models can be
+/// slow to first token.
+fn build_client(config: &AIConfig) -> Client {
+    let builder = Client::builder().connect_timeout(Duration::from_secs(10));
+    
+    let builder = match config.provider {
+        AIProvider::Ollama => builder,
+        _ => builder.read_timeout(Duration::from_secs(config.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECS))),
+    };
+    
+    builder.build().unwrap_or_default()
+}
+
+trait SendWithRetry {
+    /// Send the request, retrying with exponential backoff (1s, 2s, ...) on 429 and 5xx responses
+    async fn send_with_retry(self) -> reqwest::Result<Response>;
+}
+
+impl SendWithRetry for RequestBuilder {
+    async fn send_with_retry(self) -> reqwest::Result<Response> {
+        let mut attempt = 1;
+        
+        loop {
+            // The last attempt (or a request whose body can't be cloned) returns whatever comes back
+            let Some(request) = self.try_clone().filter(|_| attempt < MAX_ATTEMPTS) else {
+                return self.send().await;
+            };
+            
+            let response = request.send().await?;
+            let status = response.status();
+            if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                return Ok(response);
+            }
+            
+            let delay = Duration::from_secs(1 << (attempt - 1));
+            #[cfg(debug_assertions)]
+            println!("[AI] Status {}, retrying in {:?} (retry {}/{})", status, delay, attempt, MAX_ATTEMPTS - 1);
+            
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Transcripts longer than this are summarized chunk by chunk (map-reduce)
+const TRANSCRIPT_CHUNK_CHARS: usize = 6000;
+
+/// Upper bound on chunk summaries per transcript, to bound API cost on very long videos
+const MAX_CHUNKS: usize = 8;
+
+fn style_instruction(style: &SummaryStyle) -> &'static str {
+    match style {
         SummaryStyle::Short => "Provide a concise summary in 2-3 sentences capturing the main idea.",
         SummaryStyle::Concise => r#"Summarize this video in a clear, structured format:
 1. Start with a one-sentence overview of what the video is about
@@ -125,63 +244,423 @@ Be informative but concise. Focus on the most valuable insights."#,
 3. Include specific details, examples, statistics, or quotes mentioned
 4. End with key conclusions or action items if applicable
 Be thorough and capture all important information."#,
+    }
+}
+
+/// Display name for a language code, falling back to the code itself
+fn language_name(language: &str) -> &str {
+    match language {
+        "en" => "English",
+        "vi" => "Vietnamese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "zh" => "Chinese",
+        "es" => "Spanish",
+        "fr" => "French",
+        "de" => "German",
+        "pt" => "Portuguese",
+        "ru" => "Russian",
+        _ => language,
+    }
+}
+
+fn language_instruction(language: &str) -> String {
+    if language == "auto" {
+        return "Respond in the same language as the transcript.".to_string();
+    }
+    
+    format!("Respond in {}.", language_name(language))
+}
+
+fn title_section(title: Option<&str>) -> String {
+    match title {
+        Some(t) if !t.is_empty() => format!("Video Title: \"{}\"\n\n", t),
+        _ => String::new(),
+    }
+}
+
+/// The user's custom summary prompt, if one is set
+fn custom_prompt(config: &AIConfig) -> Option<&str> {
+    config.custom_prompt.as_deref().map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// Persona and style instructions that open a summary prompt, or the custom prompt that replaces them
+fn summary_instructions(style: &SummaryStyle, custom_prompt: Option<&str>) -> String {
+    match custom_prompt {
+        Some(prompt) => prompt.to_string(),
+        None => format!("You are a helpful assistant that summarizes video content.\n\n{}", style_instruction(style)),
+    }
+}
+
+/// Build prompt based on style and language
+fn build_prompt(transcript: &str, style: &SummaryStyle, language: &str, title: Option<&str>) -> String {
+    build_prompt_from_instructions(&summary_instructions(style, None), transcript, language, title)
+}
+
+/// Build a summary prompt from the given instructions, appending the transcript
+fn build_prompt_from_instructions(instructions: &str, transcript: &str, language: &str, title: Option<&str>) -> String {
+    format!(
+        "{}\n\
+        {}\n\n\
+        {}Here is the video transcript:\n\n\
+        {}\n\n\
+        Summary:",
+        instructions, language_instruction(language), title_section(title), transcript
+    )
+}
+
+/// Build the map-step prompt that condenses one chunk of a long transcript
+fn build_chunk_prompt(chunk: &str, index: usize, total: usize, language: &str, title: Option<&str>) -> String {
+    format!(
+        "You are a helpful assistant that summarizes video content.\n\n\
+        The transcript below is part {} of {} of a longer video. \
+        List the key points, facts, and details from this part as concise bullet points. \
+        Do not add an introduction or conclusion.\n\
+        {}\n\n\
+        {}Transcript part:\n\n\
+        {}\n\n\
+        Key points:",
+        index + 1, total, language_instruction(language), title_section(title), chunk
+    )
+}
+
+/// Build the reduce-step prompt that merges chunk summaries into the final summary
+fn build_reduce_prompt(chunk_summaries: &[String], truncated: bool, instructions: &str, language: &str, title: Option<&str>) -> String {
+    let parts = chunk_summaries
+        .iter()
+        .enumerate()
+        .map(|(i, summary)| format!("Part {}:\n{}", i + 1, summary.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let truncated_note = if truncated {
+        "\n\n(The remainder of the video was not included.)"
+    } else {
+        ""
     };
     
-    let language_instruction = if language == "auto" {
-        "Respond in the same language as the transcript."
+    format!(
+        "{}\n\
+        {}\n\n\
+        {}The video transcript was too long to process at once, so it was split into consecutive parts. \
+        Here are the key points of each part, in order:\n\n\
+        {}{}\n\n\
+        Write a single summary of the whole video.\n\n\
+        Summary:",
+        instructions, language_instruction(language), title_section(title), parts, truncated_note
+    )
+}
+
+/// Transcript cues are merged into windows of this many seconds for the chapter prompt
+const CHAPTER_WINDOW_SECS: f64 = 30.0;
+
+/// Format seconds as "M:SS", or "H:MM:SS" past the first hour
+fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
     } else {
-        &format!("Respond in {}.", match language {
-            "en" => "English",
-            "vi" => "Vietnamese",
-            "ja" => "Japanese",
-            "ko" => "Korean",
-            "zh" => "Chinese",
-            "es" => "Spanish",
-            "fr" => "French",
-            "de" => "German",
-            "pt" => "Portuguese",
-            "ru" => "Russian",
-            _ => language,
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+/// Build the prompt asking for chapter markers from a timestamped transcript.
+/// Cues are merged into `CHAPTER_WINDOW_SECS` windows, and each window's text is shortened
+/// evenly when the whole transcript would not fit in the chunked summary budget.
+fn build_chapters_prompt(cues: &[(f64, String)], language: &str) -> String {
+    let mut windows: Vec<(f64, String)> = Vec::new();
+    for (start, text) in cues {
+        match windows.last_mut() {
+            Some((window_start, window_text)) if start - *window_start < CHAPTER_WINDOW_SECS => {
+                window_text.push(' ');
+                window_text.push_str(text);
+            }
+            _ => windows.push((*start, text.clone())),
+        }
+    }
+    
+    let budget = TRANSCRIPT_CHUNK_CHARS * MAX_CHUNKS;
+    let total: usize = windows.iter().map(|(_, text)| text.chars().count()).sum();
+    let max_window_chars = if total > budget { (budget / windows.len().max(1)).max(1) } else { usize::MAX };
+    
+    let lines = windows
+        .iter()
+        .map(|(start, text)| {
+            let text: String = text.chars().take(max_window_chars).collect();
+            format!("[{}] {}", format_clock(*start), text)
         })
-    };
+        .collect::<Vec<_>>()
+        .join("\n");
+    
+    format!(
+        "You are a helpful assistant that splits videos into chapters.\n\n\
+        Below is a timestamped transcript. Identify the main topics and list them as chapters in chronological order, \
+        one per line, in the format \"M:SS Chapter title\" (use H:MM:SS past the first hour). \
+        The first chapter must start at 0:00. Use short, descriptive titles and aim for one chapter every few minutes. \
+        Output only the chapter list.\n\
+        {}\n\n\
+        Transcript:\n\n\
+        {}\n\n\
+        Chapters:",
+        language_instruction(language), lines
+    )
+}
+
+/// Parse "M:SS Title" lines from the model's answer into chapters sorted by start time.
+/// Tolerates list markers, brackets and separators around the timestamp; other lines are ignored.
+fn parse_chapters_response(text: &str) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    
+    for line in text.lines() {
+        let mut line = line.trim().trim_start_matches(['-', '*', '•']).trim_start();
+        
+        // Drop list numbering like "1." or "2)"
+        if let Some((first, rest)) = line.split_once(' ') {
+            let number = first.trim_end_matches(['.', ')']);
+            if first != number && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                line = rest.trim_start();
+            }
+        }
+        
+        let Some((stamp, title)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let stamp = stamp.trim_matches(['[', ']', '(', ')', '*']).trim_end_matches(['-', ':', ',']);
+        if !stamp.contains(':') {
+            continue;
+        }
+        let Some(start_time) = parse_timestamp(stamp) else {
+            continue;
+        };
+        let title = title.trim().trim_start_matches(['-', '–', '—', ':', '|']).trim().trim_matches('*').trim();
+        if title.is_empty() {
+            continue;
+        }
+        
+        chapters.push(Chapter {
+            title: title.to_string(),
+            start_time,
+            end_time: None,
+        });
+    }
+    
+    chapters.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    chapters.dedup_by(|later, earlier| later.start_time == earlier.start_time);
+    
+    // Each chapter ends where the next one starts; the last one runs to the end of the video
+    let starts: Vec<f64> = chapters.iter().skip(1).map(|c| c.start_time).collect();
+    for (chapter, next_start) in chapters.iter_mut().zip(starts) {
+        chapter.end_time = Some(next_start);
+    }
+    
+    chapters
+}
+
+/// Generate chapter markers from timestamped transcript cues (`(start_seconds, text)`)
+pub async fn generate_chapter_markers(config: &AIConfig, cues: &[(f64, String)]) -> Result<Vec<Chapter>, AIError> {
+    if cues.is_empty() {
+        return Err(AIError::NoTranscript);
+    }
     
-    // Truncate transcript if too long (keep ~8000 chars for context window)
-    // Use char indices to avoid cutting in the middle of multi-byte UTF-8 characters
-    let max_chars = 8000;
-    let truncated = if transcript.chars().count() > max_chars {
-        let truncated_str: String = transcript.chars().take(max_chars).collect();
-        format!("{}... [truncated]", truncated_str)
+    let prompt = build_chapters_prompt(cues, &config.summary_language);
+    let result = generate_raw(config, &prompt).await?;
+    let chapters = parse_chapters_response(&result.summary);
+    if chapters.is_empty() {
+        return Err(AIError::ParseError(format!(
+            "No chapters in response: {}",
+            result.summary.chars().take(200).collect::<String>()
+        )));
+    }
+    
+    Ok(chapters)
+}
+
+/// Split a transcript into windows of at most `max_chars` characters.
+/// Splits always fall on char boundaries and prefer whitespace near the end of a window.
+fn split_transcript(transcript: &str, max_chars: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = transcript.trim();
+    
+    while !rest.is_empty() {
+        let end = match rest.char_indices().nth(max_chars) {
+            None => rest.len(),
+            Some((limit, _)) => rest[..limit]
+                .rfind(char::is_whitespace)
+                .filter(|&pos| pos > limit / 2)
+                .unwrap_or(limit),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk.trim_end());
+        rest = tail.trim_start();
+    }
+    
+    chunks
+}
+
+/// Build the prompt that translates one chunk of a transcript
+fn build_translation_prompt(chunk: &str, index: usize, total: usize, target_language: &str) -> String {
+    let part_note = if total > 1 {
+        format!("This is part {} of {} of a longer transcript. ", index + 1, total)
     } else {
-        transcript.to_string()
+        String::new()
     };
     
-    // Include title if provided for better context
-    let title_section = match title {
-        Some(t) if !t.is_empty() => format!("Video Title: \"{}\"\n\n", t),
-        _ => String::new(),
+    format!(
+        "You are a professional translator.\n\n\
+        Translate the following video transcript into {}. {}\
+        Preserve the meaning and tone, and keep names and technical terms accurate. \
+        Output only the translation, without notes or commentary.\n\n\
+        Transcript:\n\n\
+        {}\n\n\
+        Translation:",
+        language_name(target_language), part_note, chunk
+    )
+}
+
+/// Summarize each chunk of a long transcript, then build the prompt that merges them.
+/// Returns `None` when the transcript fits in a single prompt.
+async fn map_transcript_chunks(
+    config: &AIConfig,
+    transcript: &str,
+    style: &SummaryStyle,
+    language: &str,
+    title: Option<&str>,
+) -> Result<Option<String>, AIError> {
+    let mut chunks = split_transcript(transcript, TRANSCRIPT_CHUNK_CHARS);
+    if chunks.len() <= 1 {
+        return Ok(None);
+    }
+    
+    let truncated = chunks.len() > MAX_CHUNKS;
+    chunks.truncate(MAX_CHUNKS);
+    
+    let mut chunk_summaries = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let prompt = build_chunk_prompt(chunk, i, chunks.len(), language, title);
+        let result = generate_raw(config, &prompt).await?;
+        chunk_summaries.push(result.summary);
+    }
+    
+    let instructions = summary_instructions(style, custom_prompt(config));
+    Ok(Some(build_reduce_prompt(&chunk_summaries, truncated, &instructions, language, title)))
+}
+
+/// Transcript chunks passed as context when answering a question about a long video
+const QA_CONTEXT_CHUNKS: usize = 3;
+
+/// Lowercased words of at least three characters, minus common filler words, used to match questions to transcript chunks
+fn keywords(text: &str) -> HashSet<String> {
+    const STOP_WORDS: &[&str] = &[
+        "the", "and", "for", "are", "was", "were", "what", "which", "who", "when", "where", "why", "how",
+        "did", "does", "this", "that", "with", "from", "they", "them", "their", "about", "have", "has",
+        "you", "your", "video", "there", "then", "than", "into", "can", "could", "would", "should",
+    ];
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Pick the transcript chunks that share the most keywords with the question, in transcript order.
+/// Returns the chunks and whether any were left out.
+fn select_relevant_chunks<'a>(transcript: &'a str, question: &str, max_chunks: usize) -> (Vec<&'a str>, bool) {
+    let chunks = split_transcript(transcript, TRANSCRIPT_CHUNK_CHARS);
+    if chunks.len() <= max_chunks {
+        return (chunks, false);
+    }
+    
+    let question_words = keywords(question);
+    let mut scored: Vec<(usize, usize)> = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let score = chunk
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| question_words.contains(&w.to_lowercase()))
+                .count();
+            (i, score)
+        })
+        .collect();
+    // Highest score first; ties keep the earlier chunk
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    
+    let mut picked: Vec<usize> = scored.iter().take(max_chunks).map(|(i, _)| *i).collect();
+    picked.sort_unstable();
+    (picked.into_iter().map(|i| chunks[i]).collect(), true)
+}
+
+/// Build the prompt answering a free-form question from transcript excerpts
+fn build_qa_prompt(context: &[&str], partial: bool, question: &str, language: &str, title: Option<&str>) -> String {
+    let scope = if partial {
+        "Below are the parts of the video transcript most relevant to the question (other parts were left out)."
+    } else {
+        "Below is the video transcript."
     };
     
     format!(
-        "You are a helpful assistant that summarizes video content.\n\n\
-        {}\n\
+        "You are a helpful assistant that answers questions about a video using its transcript.\n\n\
+        {} Answer the question using only information from the transcript. \
+        If the transcript does not contain the answer, say so instead of guessing.\n\
         {}\n\n\
-        {}Here is the video transcript:\n\n\
+        {}Transcript:\n\n\
         {}\n\n\
-        Summary:",
-        style_instruction, language_instruction, title_section, truncated
+        Question: {}\n\n\
+        Answer:",
+        scope, language_instruction(language), title_section(title), context.join("\n\n[...]\n\n"), question.trim()
     )
 }
 
+/// Answer a question about a video from its transcript. Long transcripts are narrowed down to
+/// the chunks most relevant to the question (keyword overlap) instead of being truncated.
+pub async fn answer_question(
+    config: &AIConfig,
+    transcript: &str,
+    question: &str,
+    title: Option<&str>,
+) -> Result<SummaryResult, AIError> {
+    if transcript.trim().is_empty() {
+        return Err(AIError::NoTranscript);
+    }
+    
+    let (context, partial) = select_relevant_chunks(transcript, question, QA_CONTEXT_CHUNKS);
+    let prompt = build_qa_prompt(&context, partial, question, &config.summary_language, title);
+    let result = generate_raw(config, &prompt).await?;
+    
+    Ok(SummaryResult {
+        summary: result.summary.trim().to_string(),
+        ..result
+    })
+}
+
+/// Thinking models (gemini-2.5, gemini-3) spend output tokens on thinking
+fn is_gemini_thinking_model(model: &str) -> bool {
+    model.contains("flash-preview") || model.contains("2.5") || model.contains("3-")
+}
+
+/// Gemini `generationConfig`; output tokens aren't capped for thinking models
+fn gemini_generation_config(model: &str, sampling: Sampling) -> serde_json::Value {
+    if is_gemini_thinking_model(model) {
+        serde_json::json!({ "temperature": sampling.temperature })
+    } else {
+        serde_json::json!({
+            "temperature": sampling.temperature,
+            "maxOutputTokens": sampling.max_tokens
+        })
+    }
+}
+
 /// Generate summary using Gemini API
 pub async fn generate_with_gemini(
+    client: &Client,
     api_key: &str,
     model: &str,
     transcript: &str,
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
     
     // Gemini API endpoint - use v1beta for latest models
@@ -190,30 +669,16 @@ pub async fn generate_with_gemini(
         model
     );
     
-    // Build request body - for thinking models (gemini-2.5, gemini-3), don't restrict output tokens
-    let is_thinking_model = model.contains("flash-preview") || model.contains("2.5") || model.contains("3-");
+    let is_thinking_model = is_gemini_thinking_model(model);
     
-    let body = if is_thinking_model {
-        serde_json::json!({
-            "contents": [{
-                "parts": [{
-                    "text": prompt
-                }]
+    let body = serde_json::json!({
+        "contents": [{
+            "parts": [{
+                "text": prompt
             }]
-        })
-    } else {
-        serde_json::json!({
-            "contents": [{
-                "parts": [{
-                    "text": prompt
-                }]
-            }],
-            "generationConfig": {
-                "temperature": 0.7,
-                "maxOutputTokens": 2048
-            }
-        })
-    };
+        }],
+        "generationConfig": gemini_generation_config(model, sampling)
+    });
     
     #[cfg(debug_assertions)]
     {
@@ -227,7 +692,7 @@ pub async fn generate_with_gemini(
         .header("Content-Type", "application/json")
         .header("x-goog-api-key", api_key)
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -290,16 +755,55 @@ pub async fn generate_with_gemini(
     })
 }
 
-/// Generate summary using OpenAI API
+/// Base URLs of providers that speak the OpenAI chat completions API
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Check that a custom API base is a well-formed http(s) URL and return it without a trailing slash
+pub fn validate_base_url(base_url: &str) -> Result<String, String> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(trimmed)
+        .map_err(|e| format!("Invalid base URL '{}': {}", trimmed, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!("Base URL must be an http(s) URL: {}", trimmed));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// API base for the OpenAI provider: the configured `base_url`, or the official endpoint when absent
+fn openai_base_url(config: &AIConfig) -> Result<String, AIError> {
+    match config.base_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        Some(base_url) => validate_base_url(base_url).map_err(AIError::ApiError),
+        None => Ok(OPENAI_BASE_URL.to_string()),
+    }
+}
+
+/// Add provider-specific headers to an OpenAI-compatible request.
+/// OpenRouter expects the calling app to identify itself via `HTTP-Referer` (and optionally `X-Title`).
+fn with_provider_headers(request: RequestBuilder, url: &str) -> RequestBuilder {
+    if url.contains("openrouter.ai") {
+        request
+            .header("HTTP-Referer", "https://github.com/vanloctech/youwee")
+            .header("X-Title", "Youwee")
+    } else {
+        request
+    }
+}
+
+/// Generate summary using an OpenAI-compatible chat completions API (OpenAI, Groq, OpenRouter)
 pub async fn generate_with_openai(
+    client: &Client,
+    base_url: &str,
+    provider_name: &str,
     api_key: &str,
     model: &str,
     transcript: &str,
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
     
     let body = serde_json::json!({
@@ -308,23 +812,23 @@ pub async fn generate_with_openai(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.7,
-        "max_tokens": 1024,
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens,
     });
     
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
+    let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+    let response = with_provider_headers(client.post(&url), &url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
     if !response.status().is_success() {
         let status = response.status();
         let text = response.text().await.unwrap_or_default();
-        return Err(AIError::ApiError(format!("Status {}: {}", status, text)));
+        return Err(AIError::ApiError(format!("{} status {}: {}", provider_name, status, text)));
     }
     
     let json: serde_json::Value = response
@@ -342,21 +846,104 @@ pub async fn generate_with_openai(
     
     Ok(SummaryResult {
         summary: summary.trim().to_string(),
-        provider: "OpenAI".to_string(),
+        provider: provider_name.to_string(),
         model: model.to_string(),
     })
 }
 
+/// Anthropic Messages API endpoint and version header
+const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Generate summary using Anthropic Claude API
+pub async fn generate_with_anthropic(
+    client: &Client,
+    api_key: &str,
+    model: &str,
+    transcript: &str,
+    style: &SummaryStyle,
+    language: &str,
+    title: Option<&str>,
+    sampling: Sampling,
+) -> Result<SummaryResult, AIError> {
+    let prompt = build_prompt(transcript, style, language, title);
+    let summary = anthropic_messages(client, api_key, model, &prompt, sampling).await?;
+    
+    Ok(SummaryResult {
+        summary: summary.trim().to_string(),
+        provider: "Anthropic".to_string(),
+        model: model.to_string(),
+    })
+}
+
+/// Highest temperature the Anthropic Messages API accepts
+const ANTHROPIC_MAX_TEMPERATURE: f32 = 1.0;
+
+/// Anthropic Messages API request body for a single user message
+fn anthropic_request_body(model: &str, prompt: &str, sampling: Sampling, stream: bool) -> serde_json::Value {
+    serde_json::json!({
+        "model": model,
+        "max_tokens": sampling.max_tokens,
+        // Anthropic rejects temperatures above 1.0, which other providers accept
+        "temperature": sampling.temperature.min(ANTHROPIC_MAX_TEMPERATURE),
+        "stream": stream,
+        "messages": [{
+            "role": "user",
+            "content": prompt
+        }],
+    })
+}
+
+/// Send a single user message to the Anthropic Messages API and return the text reply
+async fn anthropic_messages(
+    client: &Client,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    sampling: Sampling,
+) -> Result<String, AIError> {
+    let body = anthropic_request_body(model, prompt, sampling, false);
+    
+    let response = client
+        .post(ANTHROPIC_API_URL)
+        .header("Content-Type", "application/json")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .json(&body)
+        .send_with_retry()
+        .await
+        .map_err(|e| AIError::NetworkError(e.to_string()))?;
+    
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(AIError::ApiError(format!("Anthropic API error ({}): {}", status, text)));
+    }
+    
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| AIError::ParseError(e.to_string()))?;
+    
+    json.get("content")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string())
+        .ok_or_else(|| AIError::ParseError("No content in response".to_string()))
+}
+
 /// Generate summary using Ollama (local)
 pub async fn generate_with_ollama(
+    client: &Client,
     ollama_url: &str,
     model: &str,
     transcript: &str,
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
     
     let url = format!("{}/api/generate", ollama_url.trim_end_matches('/'));
@@ -366,7 +953,8 @@ pub async fn generate_with_ollama(
         "prompt": prompt,
         "stream": false,
         "options": {
-            "temperature": 0.7,
+            "temperature": sampling.temperature,
+            "num_predict": sampling.max_tokens,
         }
     });
     
@@ -374,7 +962,7 @@ pub async fn generate_with_ollama(
         .post(&url)
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(format!("Failed to connect to Ollama at {}: {}", ollama_url, e)))?;
     
@@ -403,14 +991,15 @@ pub async fn generate_with_ollama(
 
 /// Generate summary using DeepSeek API (OpenAI-compatible)
 pub async fn generate_with_deepseek(
+    client: &Client,
     api_key: &str,
     model: &str,
     transcript: &str,
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
     
     let body = serde_json::json!({
@@ -419,8 +1008,8 @@ pub async fn generate_with_deepseek(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.7,
-        "max_tokens": 2048,
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens,
     });
     
     let response = client
@@ -428,7 +1017,7 @@ pub async fn generate_with_deepseek(
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -460,14 +1049,15 @@ pub async fn generate_with_deepseek(
 
 /// Generate summary using Qwen API (OpenAI-compatible via DashScope)
 pub async fn generate_with_qwen(
+    client: &Client,
     api_key: &str,
     model: &str,
     transcript: &str,
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
     
     let body = serde_json::json!({
@@ -476,8 +1066,8 @@ pub async fn generate_with_qwen(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.7,
-        "max_tokens": 2048,
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens,
     });
     
     let response = client
@@ -485,7 +1075,7 @@ pub async fn generate_with_qwen(
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -515,8 +1105,22 @@ pub async fn generate_with_qwen(
     })
 }
 
+/// Chat completions endpoint for a proxy URL given with or without the /v1 or
+/// /v1/chat/completions suffix
+fn proxy_chat_completions_url(proxy_url: &str) -> String {
+    let base_url = proxy_url.trim_end_matches('/');
+    if base_url.ends_with("/chat/completions") {
+        base_url.to_string()
+    } else if base_url.ends_with("/v1") {
+        format!("{}/chat/completions", base_url)
+    } else {
+        format!("{}/v1/chat/completions", base_url)
+    }
+}
+
 /// Generate summary using Proxy (OpenAI-compatible API with custom domain)
 pub async fn generate_with_proxy(
+    client: &Client,
     proxy_url: &str,
     api_key: &str,
     model: &str,
@@ -524,19 +1128,10 @@ pub async fn generate_with_proxy(
     style: &SummaryStyle,
     language: &str,
     title: Option<&str>,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let prompt = build_prompt(transcript, style, language, title);
-    
-    // Build endpoint URL - support both with and without /v1/chat/completions suffix
-    let base_url = proxy_url.trim_end_matches('/');
-    let url = if base_url.ends_with("/chat/completions") || base_url.ends_with("/v1/chat/completions") {
-        base_url.to_string()
-    } else if base_url.ends_with("/v1") {
-        format!("{}/chat/completions", base_url)
-    } else {
-        format!("{}/v1/chat/completions", base_url)
-    };
+    let url = proxy_chat_completions_url(proxy_url);
     
     let body = serde_json::json!({
         "model": model,
@@ -544,8 +1139,8 @@ pub async fn generate_with_proxy(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.7,
-        "max_tokens": 1024,
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens,
     });
     
     let response = client
@@ -553,7 +1148,7 @@ pub async fn generate_with_proxy(
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(format!("Failed to connect to proxy at {}: {}", proxy_url, e)))?;
     
@@ -600,37 +1195,49 @@ pub async fn generate_summary(
     transcript: &str,
     title: Option<&str>,
 ) -> Result<SummaryResult, AIError> {
+    generate_summary_custom(config, transcript, &config.summary_style, &config.summary_language, title).await
+}
+
+/// AI translation result
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename = "translation")]
+pub struct TranslationResult {
+    pub translation: String,
+    pub target_language: String,
+    pub provider: String,
+    pub model: String,
+}
+
+/// Translate a full transcript into `target_language`.
+/// Long transcripts are translated chunk by chunk (without the summary chunk limit) so nothing gets cut.
+pub async fn generate_translation(
+    config: &AIConfig,
+    transcript: &str,
+    target_language: &str,
+) -> Result<TranslationResult, AIError> {
     if transcript.trim().is_empty() {
         return Err(AIError::NoTranscript);
     }
     
-    match config.provider {
-        AIProvider::Gemini => {
-            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_gemini(api_key, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
-        AIProvider::OpenAI => {
-            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_openai(api_key, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
-        AIProvider::DeepSeek => {
-            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_deepseek(api_key, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
-        AIProvider::Qwen => {
-            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_qwen(api_key, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
-        AIProvider::Ollama => {
-            let ollama_url = config.ollama_url.as_ref().map(|s| s.as_str()).unwrap_or("http://localhost:11434");
-            generate_with_ollama(ollama_url, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
-        AIProvider::Proxy => {
-            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            let proxy_url = config.proxy_url.as_ref().map(|s| s.as_str()).unwrap_or("https://api.openai.com");
-            generate_with_proxy(proxy_url, api_key, &config.model, transcript, &config.summary_style, &config.summary_language, title).await
-        }
+    let chunks = split_transcript(transcript, TRANSCRIPT_CHUNK_CHARS);
+    let mut parts = Vec::with_capacity(chunks.len());
+    let mut last_result = None;
+    
+    for (i, chunk) in chunks.iter().enumerate() {
+        let prompt = build_translation_prompt(chunk, i, chunks.len(), target_language);
+        let result = generate_raw(config, &prompt).await?;
+        parts.push(result.summary.trim().to_string());
+        last_result = Some(result);
     }
+    
+    let result = last_result.ok_or(AIError::NoTranscript)?;
+    
+    Ok(TranslationResult {
+        translation: parts.join("\n\n"),
+        target_language: target_language.to_string(),
+        provider: result.provider,
+        model: result.model,
+    })
 }
 
 /// Generate summary with custom style and language (overriding config)
@@ -645,31 +1252,65 @@ pub async fn generate_summary_custom(
         return Err(AIError::NoTranscript);
     }
     
+    // Long transcripts: summarize each chunk, then summarize the chunk summaries
+    let sampling = Sampling::summary(config);
+    if let Some(reduce_prompt) = map_transcript_chunks(config, transcript, style, language, title).await? {
+        let result = generate_raw_with_sampling(config, &reduce_prompt, sampling).await?;
+        return Ok(SummaryResult {
+            summary: result.summary.trim().to_string(),
+            ..result
+        });
+    }
+    
+    // A custom prompt replaces the style instructions, so send the finished prompt as-is
+    if let Some(instructions) = custom_prompt(config) {
+        let prompt = build_prompt_from_instructions(instructions, transcript, language, title);
+        let result = generate_raw_with_sampling(config, &prompt, sampling).await?;
+        return Ok(SummaryResult {
+            summary: result.summary.trim().to_string(),
+            ..result
+        });
+    }
+    
+    let client = build_client(config);
     match config.provider {
         AIProvider::Gemini => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_gemini(api_key, &config.model, transcript, style, language, title).await
+            generate_with_gemini(&client, api_key, &config.model, transcript, style, language, title, sampling).await
         }
         AIProvider::OpenAI => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_openai(api_key, &config.model, transcript, style, language, title).await
+            let base_url = openai_base_url(config)?;
+            generate_with_openai(&client, &base_url, "OpenAI", api_key, &config.model, transcript, style, language, title, sampling).await
+        }
+        AIProvider::Groq => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_with_openai(&client, GROQ_BASE_URL, "Groq", api_key, &config.model, transcript, style, language, title, sampling).await
+        }
+        AIProvider::OpenRouter => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_with_openai(&client, OPENROUTER_BASE_URL, "OpenRouter", api_key, &config.model, transcript, style, language, title, sampling).await
         }
         AIProvider::DeepSeek => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_deepseek(api_key, &config.model, transcript, style, language, title).await
+            generate_with_deepseek(&client, api_key, &config.model, transcript, style, language, title, sampling).await
         }
         AIProvider::Qwen => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_with_qwen(api_key, &config.model, transcript, style, language, title).await
+            generate_with_qwen(&client, api_key, &config.model, transcript, style, language, title, sampling).await
         }
         AIProvider::Ollama => {
             let ollama_url = config.ollama_url.as_ref().map(|s| s.as_str()).unwrap_or("http://localhost:11434");
-            generate_with_ollama(ollama_url, &config.model, transcript, style, language, title).await
+            generate_with_ollama(&client, ollama_url, &config.model, transcript, style, language, title, sampling).await
         }
         AIProvider::Proxy => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
             let proxy_url = config.proxy_url.as_ref().map(|s| s.as_str()).unwrap_or("https://api.openai.com");
-            generate_with_proxy(proxy_url, api_key, &config.model, transcript, style, language, title).await
+            generate_with_proxy(&client, proxy_url, api_key, &config.model, transcript, style, language, title, sampling).await
+        }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_with_anthropic(&client, api_key, &config.model, transcript, style, language, title, sampling).await
         }
     }
 }
@@ -677,46 +1318,71 @@ pub async fn generate_summary_custom(
 /// Generate raw AI response without summarization prompt wrapping
 /// Used for FFmpeg command generation and other custom tasks
 pub async fn generate_raw(config: &AIConfig, prompt: &str) -> Result<SummaryResult, AIError> {
+    generate_raw_with_sampling(config, prompt, Sampling::PRECISE).await
+}
+
+/// `generate_raw` with explicit sampling settings, e.g. the configured summary settings
+async fn generate_raw_with_sampling(config: &AIConfig, prompt: &str, sampling: Sampling) -> Result<SummaryResult, AIError> {
     if prompt.trim().is_empty() {
         return Err(AIError::NoTranscript);
     }
     
+    let client = build_client(config);
     match config.provider {
         AIProvider::Gemini => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_raw_with_gemini(api_key, &config.model, prompt).await
+            generate_raw_with_gemini(&client, api_key, &config.model, prompt, sampling).await
         }
         AIProvider::OpenAI => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_raw_with_openai(api_key, &config.model, prompt).await
+            let base_url = openai_base_url(config)?;
+            generate_raw_with_openai(&client, &base_url, "OpenAI", api_key, &config.model, prompt, sampling).await
+        }
+        AIProvider::Groq => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_raw_with_openai(&client, GROQ_BASE_URL, "Groq", api_key, &config.model, prompt, sampling).await
+        }
+        AIProvider::OpenRouter => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            generate_raw_with_openai(&client, OPENROUTER_BASE_URL, "OpenRouter", api_key, &config.model, prompt, sampling).await
         }
         AIProvider::DeepSeek => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_raw_with_deepseek(api_key, &config.model, prompt).await
+            generate_raw_with_deepseek(&client, api_key, &config.model, prompt, sampling).await
         }
         AIProvider::Qwen => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
-            generate_raw_with_qwen(api_key, &config.model, prompt).await
+            generate_raw_with_qwen(&client, api_key, &config.model, prompt, sampling).await
         }
         AIProvider::Ollama => {
             let ollama_url = config.ollama_url.as_ref().map(|s| s.as_str()).unwrap_or("http://localhost:11434");
-            generate_raw_with_ollama(ollama_url, &config.model, prompt).await
+            generate_raw_with_ollama(&client, ollama_url, &config.model, prompt, sampling).await
         }
         AIProvider::Proxy => {
             let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
             let proxy_url = config.proxy_url.as_ref().map(|s| s.as_str()).unwrap_or("https://api.openai.com");
-            generate_raw_with_proxy(proxy_url, api_key, &config.model, prompt).await
+            generate_raw_with_proxy(&client, proxy_url, api_key, &config.model, prompt, sampling).await
+        }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let text = anthropic_messages(&client, api_key, &config.model, prompt, sampling).await?;
+            Ok(SummaryResult {
+                summary: text,
+                model: config.model.clone(),
+                provider: "Anthropic".to_string(),
+            })
         }
     }
 }
 
 /// Raw generation with Gemini (no summarization wrapping)
 async fn generate_raw_with_gemini(
+    client: &Client,
     api_key: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     
     let url = format!(
         "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent",
@@ -729,10 +1395,7 @@ async fn generate_raw_with_gemini(
                 "text": prompt
             }]
         }],
-        "generationConfig": {
-            "temperature": 0.3,
-            "maxOutputTokens": 2048
-        }
+        "generationConfig": gemini_generation_config(model, sampling)
     });
     
     #[cfg(debug_assertions)]
@@ -746,7 +1409,7 @@ async fn generate_raw_with_gemini(
         .header("Content-Type", "application/json")
         .header("x-goog-api-key", api_key)
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -791,13 +1454,16 @@ async fn generate_raw_with_gemini(
     })
 }
 
-/// Raw generation with OpenAI (no summarization wrapping)
+/// Raw generation with an OpenAI-compatible API (no summarization wrapping)
 async fn generate_raw_with_openai(
+    client: &Client,
+    base_url: &str,
+    provider_name: &str,
     api_key: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     
     let body = serde_json::json!({
         "model": model,
@@ -805,16 +1471,16 @@ async fn generate_raw_with_openai(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.3,
-        "max_tokens": 2048
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens
     });
     
-    let response = client
-        .post("https://api.openai.com/v1/chat/completions")
+    let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+    let response = with_provider_headers(client.post(&url), &url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -822,7 +1488,7 @@ async fn generate_raw_with_openai(
     let response_text = response.text().await.unwrap_or_default();
     
     if !status.is_success() {
-        return Err(AIError::ApiError(format!("OpenAI API error: {}", response_text)));
+        return Err(AIError::ApiError(format!("{} API error: {}", provider_name, response_text)));
     }
     
     let json: serde_json::Value = serde_json::from_str(&response_text)
@@ -839,17 +1505,18 @@ async fn generate_raw_with_openai(
     Ok(SummaryResult {
         summary: text.to_string(),
         model: model.to_string(),
-        provider: "OpenAI".to_string(),
+        provider: provider_name.to_string(),
     })
 }
 
 /// Raw generation with Ollama (no summarization wrapping)
 async fn generate_raw_with_ollama(
+    client: &Client,
     base_url: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
     
     let body = serde_json::json!({
@@ -857,7 +1524,8 @@ async fn generate_raw_with_ollama(
         "prompt": prompt,
         "stream": false,
         "options": {
-            "temperature": 0.3
+            "temperature": sampling.temperature,
+            "num_predict": sampling.max_tokens
         }
     });
     
@@ -865,7 +1533,7 @@ async fn generate_raw_with_ollama(
         .post(&url)
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -893,11 +1561,12 @@ async fn generate_raw_with_ollama(
 
 /// Raw generation with DeepSeek (no summarization wrapping)
 async fn generate_raw_with_deepseek(
+    client: &Client,
     api_key: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     
     let body = serde_json::json!({
         "model": model,
@@ -905,8 +1574,8 @@ async fn generate_raw_with_deepseek(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.3,
-        "max_tokens": 2048
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens
     });
     
     let response = client
@@ -914,7 +1583,7 @@ async fn generate_raw_with_deepseek(
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -945,11 +1614,12 @@ async fn generate_raw_with_deepseek(
 
 /// Raw generation with Qwen (no summarization wrapping)
 async fn generate_raw_with_qwen(
+    client: &Client,
     api_key: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
     
     let body = serde_json::json!({
         "model": model,
@@ -957,8 +1627,8 @@ async fn generate_raw_with_qwen(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.3,
-        "max_tokens": 2048
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens
     });
     
     let response = client
@@ -966,7 +1636,7 @@ async fn generate_raw_with_qwen(
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -997,13 +1667,14 @@ async fn generate_raw_with_qwen(
 
 /// Raw generation with Proxy (no summarization wrapping)
 async fn generate_raw_with_proxy(
+    client: &Client,
     proxy_url: &str,
     api_key: &str,
     model: &str,
     prompt: &str,
+    sampling: Sampling,
 ) -> Result<SummaryResult, AIError> {
-    let client = Client::new();
-    let url = format!("{}/v1/chat/completions", proxy_url.trim_end_matches('/'));
+    let url = proxy_chat_completions_url(proxy_url);
     
     let body = serde_json::json!({
         "model": model,
@@ -1011,8 +1682,8 @@ async fn generate_raw_with_proxy(
             "role": "user",
             "content": prompt
         }],
-        "temperature": 0.3,
-        "max_tokens": 2048
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens
     });
     
     let response = client
@@ -1020,7 +1691,7 @@ async fn generate_raw_with_proxy(
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
         .json(&body)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|e| AIError::NetworkError(e.to_string()))?;
     
@@ -1049,9 +1720,416 @@ async fn generate_raw_with_proxy(
     })
 }
 
+/// Wire format of a streaming response body
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StreamFraming {
+    /// Server-sent events, one `data:`-prefixed JSON payload per line (OpenAI, Gemini, Anthropic)
+    Sse,
+    /// Newline-delimited JSON objects (Ollama)
+    NdJson,
+}
+
+/// Extract the JSON payload from one line of a streaming response.
+/// Returns `None` for blank lines, SSE comments/event names, and the `[DONE]` terminator.
+fn parse_stream_line(line: &str, framing: StreamFraming) -> Option<serde_json::Value> {
+    let line = line.trim();
+    let payload = match framing {
+        StreamFraming::Sse => line.strip_prefix("data:")?.trim(),
+        StreamFraming::NdJson => line,
+    };
+    
+    if payload.is_empty() || payload == "[DONE]" {
+        return None;
+    }
+    
+    serde_json::from_str(payload).ok()
+}
+
+/// Read a streaming response body, calling `on_chunk` with each piece of text as it arrives.
+/// Returns the full concatenated text.
+async fn read_stream<F>(
+    mut response: reqwest::Response,
+    framing: StreamFraming,
+    extract: fn(&serde_json::Value) -> Option<&str>,
+    on_chunk: &mut F,
+) -> Result<String, AIError>
+where
+    F: FnMut(&str),
+{
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(AIError::ApiError(format!("Status {}: {}", status, text)));
+    }
+    
+    let mut full_text = String::new();
+    let mut buffer: Vec<u8> = Vec::new();
+    
+    let mut handle_line = |line: &[u8], full_text: &mut String| -> Result<(), AIError> {
+        let line = String::from_utf8_lossy(line);
+        let Some(json) = parse_stream_line(&line, framing) else {
+            return Ok(());
+        };
+        
+        if let Some(error) = json.get("error") {
+            let msg = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| error.to_string());
+            return Err(AIError::ApiError(msg));
+        }
+        
+        if let Some(text) = extract(&json) {
+            if !text.is_empty() {
+                full_text.push_str(text);
+                on_chunk(text);
+            }
+        }
+        Ok(())
+    };
+    
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| AIError::NetworkError(e.to_string()))?
+    {
+        buffer.extend_from_slice(&bytes);
+        
+        // Only complete lines are parsed; a partial line stays buffered until the rest arrives
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            handle_line(&line, &mut full_text)?;
+        }
+    }
+    
+    if !buffer.is_empty() {
+        handle_line(&buffer, &mut full_text)?;
+    }
+    
+    if full_text.trim().is_empty() {
+        return Err(AIError::ParseError("No content in streamed response".to_string()));
+    }
+    
+    Ok(full_text)
+}
+
+fn extract_gemini_chunk(json: &serde_json::Value) -> Option<&str> {
+    json.get("candidates")?
+        .get(0)?
+        .get("content")?
+        .get("parts")?
+        .get(0)?
+        .get("text")?
+        .as_str()
+}
+
+fn extract_openai_chunk(json: &serde_json::Value) -> Option<&str> {
+    json.get("choices")?
+        .get(0)?
+        .get("delta")?
+        .get("content")?
+        .as_str()
+}
+
+fn extract_ollama_chunk(json: &serde_json::Value) -> Option<&str> {
+    json.get("response")?.as_str()
+}
+
+fn extract_anthropic_chunk(json: &serde_json::Value) -> Option<&str> {
+    if json.get("type")?.as_str()? != "content_block_delta" {
+        return None;
+    }
+    json.get("delta")?.get("text")?.as_str()
+}
+
+/// Stream an OpenAI-compatible chat completion
+async fn stream_openai_compatible<F>(
+    client: &Client,
+    url: &str,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    sampling: Sampling,
+    on_chunk: &mut F,
+) -> Result<String, AIError>
+where
+    F: FnMut(&str),
+{
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{
+            "role": "user",
+            "content": prompt
+        }],
+        "temperature": sampling.temperature,
+        "max_tokens": sampling.max_tokens,
+        "stream": true,
+    });
+    
+    let response = with_provider_headers(client.post(url), url)
+        .header("Content-Type", "application/json")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(&body)
+        .send_with_retry()
+        .await
+        .map_err(|e| AIError::NetworkError(e.to_string()))?;
+    
+    read_stream(response, StreamFraming::Sse, extract_openai_chunk, on_chunk).await
+}
+
+/// Generate summary based on config, streaming partial text to `on_chunk` as it arrives
+pub async fn generate_summary_stream<F>(
+    config: &AIConfig,
+    transcript: &str,
+    title: Option<&str>,
+    mut on_chunk: F,
+) -> Result<SummaryResult, AIError>
+where
+    F: FnMut(&str),
+{
+    if transcript.trim().is_empty() {
+        return Err(AIError::NoTranscript);
+    }
+    
+    // Long transcripts: the chunk summaries are generated up front, only the final merge is streamed
+    let prompt = match map_transcript_chunks(config, transcript, &config.summary_style, &config.summary_language, title).await? {
+        Some(reduce_prompt) => reduce_prompt,
+        None => {
+            let instructions = summary_instructions(&config.summary_style, custom_prompt(config));
+            build_prompt_from_instructions(&instructions, transcript, &config.summary_language, title)
+        }
+    };
+    let model = config.model.as_str();
+    let sampling = Sampling::summary(config);
+    let client = build_client(config);
+    
+    let (summary, provider) = match config.provider {
+        AIProvider::Gemini => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let url = format!(
+                "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse",
+                model
+            );
+            let body = serde_json::json!({
+                "contents": [{
+                    "parts": [{
+                        "text": prompt
+                    }]
+                }],
+                "generationConfig": gemini_generation_config(model, sampling)
+            });
+            let response = client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .header("x-goog-api-key", api_key)
+                .json(&body)
+                .send_with_retry()
+                .await
+                .map_err(|e| AIError::NetworkError(e.to_string()))?;
+            let text = read_stream(response, StreamFraming::Sse, extract_gemini_chunk, &mut on_chunk).await?;
+            (text, "Gemini")
+        }
+        AIProvider::OpenAI => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let url = format!("{}/chat/completions", openai_base_url(config)?);
+            let text = stream_openai_compatible(&client, &url, api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "OpenAI")
+        }
+        AIProvider::Groq => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let url = format!("{}/chat/completions", GROQ_BASE_URL);
+            let text = stream_openai_compatible(&client, &url, api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "Groq")
+        }
+        AIProvider::OpenRouter => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let url = format!("{}/chat/completions", OPENROUTER_BASE_URL);
+            let text = stream_openai_compatible(&client, &url, api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "OpenRouter")
+        }
+        AIProvider::DeepSeek => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let text = stream_openai_compatible(&client, "https://api.deepseek.com/chat/completions", api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "DeepSeek")
+        }
+        AIProvider::Qwen => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let text = stream_openai_compatible(&client, "https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions", api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "Qwen")
+        }
+        AIProvider::Proxy => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let url = proxy_chat_completions_url(config.proxy_url.as_deref().unwrap_or("https://api.openai.com"));
+            let text = stream_openai_compatible(&client, &url, api_key, model, &prompt, sampling, &mut on_chunk).await?;
+            (text, "Proxy")
+        }
+        AIProvider::Ollama => {
+            let ollama_url = config.ollama_url.as_deref().unwrap_or("http://localhost:11434");
+            let url = format!("{}/api/generate", ollama_url.trim_end_matches('/'));
+            let body = serde_json::json!({
+                "model": model,
+                "prompt": prompt,
+                "stream": true,
+                "options": {
+                    "temperature": sampling.temperature,
+                    "num_predict": sampling.max_tokens,
+                }
+            });
+            let response = client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&body)
+                .send_with_retry()
+                .await
+                .map_err(|e| AIError::NetworkError(format!("Failed to connect to Ollama at {}: {}", ollama_url, e)))?;
+            let text = read_stream(response, StreamFraming::NdJson, extract_ollama_chunk, &mut on_chunk).await?;
+            (text, "Ollama")
+        }
+        AIProvider::Anthropic => {
+            let api_key = config.api_key.as_ref().ok_or(AIError::NoApiKey)?;
+            let body = anthropic_request_body(model, &prompt, sampling, true);
+            let response = client
+                .post(ANTHROPIC_API_URL)
+                .header("Content-Type", "application/json")
+                .header("x-api-key", api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .json(&body)
+                .send_with_retry()
+                .await
+                .map_err(|e| AIError::NetworkError(e.to_string()))?;
+            let text = read_stream(response, StreamFraming::Sse, extract_anthropic_chunk, &mut on_chunk).await?;
+            (text, "Anthropic")
+        }
+    };
+    
+    Ok(SummaryResult {
+        summary: summary.trim().to_string(),
+        provider: provider.to_string(),
+        model: model.to_string(),
+    })
+}
+
 /// Test AI connection with a simple prompt
 pub async fn test_connection(config: &AIConfig) -> Result<String, AIError> {
     let test_transcript = "This is a test video about programming tutorials.";
     let result = generate_summary(config, test_transcript, None).await?;
     Ok(format!("Connection successful! Using {} with model {}", result.provider, result.model))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_base_url() {
+        assert_eq!(validate_base_url(" http://localhost:4000/v1/ ").unwrap(), "http://localhost:4000/v1");
+        assert_eq!(validate_base_url("https://gateway.example.com/openai/v1").unwrap(), "https://gateway.example.com/openai/v1");
+        assert!(validate_base_url("ftp://example.com/v1").is_err());
+        assert!(validate_base_url("localhost:4000").is_err());
+        assert!(validate_base_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_proxy_chat_completions_url() {
+        for proxy_url in [
+            "https://proxy.example.com",
+            "https://proxy.example.com/",
+            "https://proxy.example.com/v1",
+            "https://proxy.example.com/v1/chat/completions",
+        ] {
+            assert_eq!(proxy_chat_completions_url(proxy_url), "https://proxy.example.com/v1/chat/completions");
+        }
+    }
+
+    #[test]
+    fn test_sampling_settings() {
+        // Configs saved before these settings existed get the defaults
+        let config: AIConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "provider": "openai",
+            "model": "gpt-4o-mini",
+            "summary_style": "detailed",
+            "summary_language": "en",
+        })).unwrap();
+        assert_eq!(Sampling::summary(&config), Sampling { temperature: DEFAULT_TEMPERATURE, max_tokens: DEFAULT_MAX_TOKENS });
+        
+        assert!(validate_sampling(0.0, 4096).is_ok());
+        assert!(validate_sampling(2.0, 1).is_ok());
+        assert!(validate_sampling(2.1, 1024).is_err());
+        assert!(validate_sampling(-0.1, 1024).is_err());
+        assert!(validate_sampling(f32::NAN, 1024).is_err());
+        assert!(validate_sampling(0.7, 0).is_err());
+        
+        let hot = Sampling { temperature: 1.8, max_tokens: 1024 };
+        for stream in [false, true] {
+            let body = anthropic_request_body("claude-3-5-haiku-latest", "Hi", hot, stream);
+            assert_eq!(body["temperature"], serde_json::json!(ANTHROPIC_MAX_TEMPERATURE));
+            assert_eq!(body["stream"], stream);
+        }
+    }
+
+    #[test]
+    fn test_custom_prompt_replaces_style_instructions() {
+        let default_prompt = build_prompt("the transcript", &SummaryStyle::Short, "en", None);
+        assert!(default_prompt.starts_with("You are a helpful assistant"));
+
+        let mut config = AIConfig { custom_prompt: Some("  ".to_string()), ..AIConfig::default() };
+        assert_eq!(custom_prompt(&config), None);
+
+        config.custom_prompt = Some("Extract action items.".to_string());
+        let instructions = summary_instructions(&config.summary_style, custom_prompt(&config));
+        let prompt = build_prompt_from_instructions(&instructions, "the transcript", "en", Some("Title"));
+        assert!(prompt.starts_with("Extract action items.\nRespond in English."));
+        assert!(!prompt.contains("You are a helpful assistant"));
+        assert!(prompt.contains("Video Title: \"Title\""));
+        assert!(prompt.contains("the transcript"));
+    }
+
+    #[test]
+    fn test_parse_chapters_response() {
+        let response = "Here are the chapters:\n0:00 Intro\n- 3:42 - Setup\n2. [1:02:05] Q&A: audience questions\n**10:15** Deep dive\nno timestamp here\n3:42 Duplicate";
+        let chapters = parse_chapters_response(response);
+        
+        let summary: Vec<(f64, &str, Option<f64>)> = chapters
+            .iter()
+            .map(|c| (c.start_time, c.title.as_str(), c.end_time))
+            .collect();
+        assert_eq!(summary, vec![
+            (0.0, "Intro", Some(222.0)),
+            (222.0, "Setup", Some(615.0)),
+            (615.0, "Deep dive", Some(3725.0)),
+            (3725.0, "Q&A: audience questions", None),
+        ]);
+    }
+
+    #[test]
+    fn test_select_relevant_chunks() {
+        let filler = "lorem ipsum dolor sit amet ".repeat(300);
+        let transcript = format!(
+            "{} {} they recommend the Rust compiler {} {} {}",
+            filler, filler, filler, filler, filler
+        );
+        
+        let (chunks, partial) = select_relevant_chunks(&transcript, "What compiler did they recommend?", 1);
+        assert!(partial);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].contains("Rust compiler"));
+        
+        let (chunks, partial) = select_relevant_chunks("short transcript", "anything?", QA_CONTEXT_CHUNKS);
+        assert!(!partial);
+        assert_eq!(chunks, vec!["short transcript"]);
+    }
+
+    #[test]
+    fn test_build_chapters_prompt_merges_windows() {
+        let cues = vec![
+            (0.0, "hello".to_string()),
+            (12.0, "world".to_string()),
+            (45.0, "next topic".to_string()),
+            (3700.0, "late".to_string()),
+        ];
+        let prompt = build_chapters_prompt(&cues, "en");
+        assert!(prompt.contains("[0:00] hello world\n[0:45] next topic\n[1:01:40] late"));
+    }
+}
@@ -55,6 +55,11 @@ pub async fn get_ffmpeg_path(app: &AppHandle) -> Option<PathBuf> {
     None
 }
 
+/// Whether an FFmpeg binary can be found (app data or system)
+pub async fn is_ffmpeg_available(app: &AppHandle) -> bool {
+    get_ffmpeg_path(app).await.is_some()
+}
+
 /// Check FFmpeg status
 pub async fn check_ffmpeg_internal(app: &AppHandle) -> Result<FfmpegStatus, String> {
     // First check app data directory
@@ -70,6 +75,7 @@ pub async fn check_ffmpeg_internal(app: &AppHandle) -> Result<FfmpegStatus, Stri
                 .args(["-version"])
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
+                .kill_on_drop(true)
                 .output()
                 .await;
             
@@ -91,6 +97,7 @@ pub async fn check_ffmpeg_internal(app: &AppHandle) -> Result<FfmpegStatus, Stri
         .args(["-version"])
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
+        .kill_on_drop(true)
         .output()
         .await;
     
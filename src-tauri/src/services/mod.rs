@@ -3,9 +3,13 @@ mod ffmpeg;
 mod bun;
 mod ai;
 mod whisper;
+mod info_cache;
+mod supported_sites;
 
 pub use ytdlp::*;
 pub use ffmpeg::*;
 pub use bun::*;
 pub use ai::*;
 pub use whisper::*;
+pub use info_cache::*;
+pub use supported_sites::*;
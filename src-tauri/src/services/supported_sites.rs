@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use super::run_ytdlp_with_stderr;
+
+/// How long the extractor list stays fresh; it only changes when yt-dlp is updated
+pub const SUPPORTED_SITES_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+/// Suffix yt-dlp adds to extractors that are known not to work
+const BROKEN_EXTRACTOR_SUFFIX: &str = "(CURRENTLY BROKEN)";
+
+/// Extractor list as stored in supported_sites.json under app data
+#[derive(Clone, Serialize, Deserialize)]
+struct SupportedSitesCache {
+    /// Unix timestamp of the `--list-extractors` run
+    fetched_at: i64,
+    sites: Vec<String>,
+}
+
+static SUPPORTED_SITES: LazyLock<Mutex<Option<SupportedSitesCache>>> = LazyLock::new(|| Mutex::new(None));
+
+fn get_cache_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join("supported_sites.json"))
+}
+
+fn is_fresh(cache: &SupportedSitesCache) -> bool {
+    chrono::Utc::now().timestamp() - cache.fetched_at < SUPPORTED_SITES_TTL_SECS
+}
+
+/// Parse `yt-dlp --list-extractors` output: one extractor name per line.
+/// Extractors marked as broken are left out since downloads from them fail
+pub fn parse_extractor_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.ends_with(BROKEN_EXTRACTOR_SUFFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Supported extractor names, from memory, then supported_sites.json, then yt-dlp.
+/// `refresh` skips both caches and runs yt-dlp again
+pub async fn get_supported_sites_internal(app: &AppHandle, refresh: bool) -> Result<Vec<String>, String> {
+    if !refresh {
+        if let Ok(cached) = SUPPORTED_SITES.lock() {
+            if let Some(cache) = cached.as_ref().filter(|cache| is_fresh(cache)) {
+                return Ok(cache.sites.clone());
+            }
+        }
+
+        let stored = get_cache_path(app)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<SupportedSitesCache>(&content).ok())
+            .filter(is_fresh);
+        if let Some(cache) = stored {
+            let sites = cache.sites.clone();
+            if let Ok(mut cached) = SUPPORTED_SITES.lock() {
+                *cached = Some(cache);
+            }
+            return Ok(sites);
+        }
+    }
+
+    let output = run_ytdlp_with_stderr(app, &["--list-extractors"]).await?;
+    if !output.success {
+        return Err(format!("Failed to list supported sites: {}", output.stderr.trim()));
+    }
+    let sites = parse_extractor_list(&output.stdout);
+    if sites.is_empty() {
+        return Err("yt-dlp returned no supported sites".to_string());
+    }
+
+    let cache = SupportedSitesCache {
+        fetched_at: chrono::Utc::now().timestamp(),
+        sites: sites.clone(),
+    };
+    // A failed write only costs a re-run next time
+    if let (Ok(path), Ok(content)) = (get_cache_path(app), serde_json::to_string(&cache)) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(path, content).ok();
+    }
+    if let Ok(mut cached) = SUPPORTED_SITES.lock() {
+        *cached = Some(cache);
+    }
+    Ok(sites)
+}
+
+/// Forget the cached extractor list, e.g. after yt-dlp was updated
+pub fn clear_supported_sites_cache(app: &AppHandle) {
+    if let Ok(mut cached) = SUPPORTED_SITES.lock() {
+        *cached = None;
+    }
+    if let Ok(path) = get_cache_path(app) {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_extractor_list() {
+        let output = "youtube\nyoutube:playlist\n\n  vimeo  \nSomeSite (CURRENTLY BROKEN)\n";
+        assert_eq!(parse_extractor_list(output), vec!["youtube", "youtube:playlist", "vimeo"]);
+    }
+}
@@ -84,6 +84,9 @@ impl From<WhisperError> for String {
 /// Maximum file size for Whisper API (25 MB)
 const MAX_FILE_SIZE: u64 = 25 * 1024 * 1024;
 
+/// Default Whisper model
+pub const DEFAULT_WHISPER_MODEL: &str = "whisper-1";
+
 /// Supported audio formats for Whisper
 const SUPPORTED_FORMATS: &[&str] = &["mp3", "mp4", "mpeg", "mpga", "m4a", "wav", "webm", "ogg"];
 
@@ -102,6 +105,17 @@ pub async fn transcribe_audio(
     audio_path: &str,
     response_format: WhisperResponseFormat,
     language: Option<&str>,
+) -> Result<WhisperResult, WhisperError> {
+    transcribe_audio_with_model(api_key, audio_path, DEFAULT_WHISPER_MODEL, response_format, language).await
+}
+
+/// Transcribe audio file using a specific Whisper model
+pub async fn transcribe_audio_with_model(
+    api_key: &str,
+    audio_path: &str,
+    model: &str,
+    response_format: WhisperResponseFormat,
+    language: Option<&str>,
 ) -> Result<WhisperResult, WhisperError> {
     let path = Path::new(audio_path);
     
@@ -161,7 +175,7 @@ pub async fn transcribe_audio(
     
     let mut form = Form::new()
         .part("file", file_part)
-        .text("model", "whisper-1")
+        .text("model", model.to_string())
         .text("response_format", response_format.to_string());
     
     // Add language hint if provided
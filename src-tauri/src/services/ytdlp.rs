@@ -1,10 +1,16 @@
+use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::process::Stdio;
-use tauri::{AppHandle, Manager};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::ShellExt;
-use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tokio::process::Command;
-use crate::types::YtdlpVersionInfo;
+use crate::database::add_log_internal;
+use crate::types::{DownloadError, NetworkConfig, YtdlpVersionInfo, YtdlpWarning};
 
 /// Get the path to yt-dlp binary, prioritizing user-updated version in app_data_dir
 /// Returns: (path, is_bundled)
@@ -49,6 +55,56 @@ pub struct YtdlpOutput {
     pub success: bool,
 }
 
+/// Set once the bundled sidecar turned out not to run on this machine
+static SIDECAR_UNUSABLE: AtomicBool = AtomicBool::new(false);
+
+/// A sidecar that fails this quickly without printing anything never really started
+const SIDECAR_STARTUP_GRACE: Duration = Duration::from_secs(2);
+
+/// Whether a spawn error means the binary was built for another CPU architecture
+/// (ENOEXEC on Linux, "Bad CPU type" on macOS, ERROR_BAD_EXE_FORMAT on Windows)
+pub fn is_exec_format_error(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    ["exec format error", "bad cpu type", "os error 86", "os error 193", "not a valid win32 application"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+}
+
+/// Stop using the bundled sidecar for the rest of the session and log why, once
+fn mark_sidecar_unusable(reason: &str) {
+    if !SIDECAR_UNUSABLE.swap(true, Ordering::SeqCst) {
+        add_log_internal(
+            "error",
+            "Bundled yt-dlp cannot run on this machine (wrong CPU architecture?); using the system yt-dlp instead",
+            Some(reason),
+            None,
+        ).ok();
+    }
+}
+
+/// Spawn the bundled yt-dlp sidecar. Returns `Ok(None)` when there is no usable sidecar,
+/// including one built for another CPU architecture; the caller then runs the system yt-dlp
+pub fn spawn_ytdlp_sidecar<I, S>(app: &AppHandle, args: I) -> Result<Option<(Receiver<CommandEvent>, CommandChild)>, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    if SIDECAR_UNUSABLE.load(Ordering::SeqCst) {
+        return Ok(None);
+    }
+    let Ok(sidecar) = app.shell().sidecar("yt-dlp") else {
+        return Ok(None);
+    };
+    match sidecar.args(args).spawn() {
+        Ok(spawned) => Ok(Some(spawned)),
+        Err(e) if is_exec_format_error(&e.to_string()) => {
+            mark_sidecar_unusable(&e.to_string());
+            Ok(None)
+        }
+        Err(e) => Err(format!("Failed to start bundled yt-dlp: {}", e)),
+    }
+}
+
 /// Helper to run yt-dlp command and get output with stderr
 pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<YtdlpOutput, String> {
     // Try to get yt-dlp path (prioritizes user-updated version)
@@ -57,6 +113,7 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
             .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
             .output()
             .await
             .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
@@ -69,14 +126,9 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
     }
     
     // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
-    
-    match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, _child) = sidecar
-                .args(args)
-                .spawn()
-                .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    match spawn_ytdlp_sidecar(app, args)? {
+        Some((mut rx, child)) => {
+            let mut child = SidecarGuard(Some(child));
             
             let mut stdout = String::new();
             let mut stderr = String::new();
@@ -94,6 +146,7 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
                         return Err(format!("Process error: {}", err));
                     }
                     CommandEvent::Terminated(status) => {
+                        child.0 = None;
                         success = status.code == Some(0);
                     }
                     _ => {}
@@ -102,12 +155,13 @@ pub async fn run_ytdlp_with_stderr(app: &AppHandle, args: &[&str]) -> Result<Ytd
             
             Ok(YtdlpOutput { stdout, stderr, success })
         }
-        Err(_) => {
+        None => {
             // Fallback to system yt-dlp
             let output = Command::new("yt-dlp")
                 .args(args)
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
+                .kill_on_drop(true)
                 .output()
                 .await
                 .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
@@ -146,7 +200,7 @@ pub fn parse_ytdlp_error(stderr: &str) -> Option<String> {
     }
     
     // Members-only / subscription required
-    if stderr_lower.contains("members-only") || stderr_lower.contains("member-only") || stderr_lower.contains("join this channel") {
+    if stderr_lower.contains("members-only") || stderr_lower.contains("member-only") || stderr_lower.contains("join this channel") || stderr_lower.contains("available to this channel's members") {
         return Some("This video is for channel members only. Please enable authentication in Settings → Video Authentication with a subscribed account.".to_string());
     }
     
@@ -175,38 +229,225 @@ pub fn parse_ytdlp_error(stderr: &str) -> Option<String> {
     None
 }
 
-/// Helper to run yt-dlp command and get JSON output
+/// Classify yt-dlp stderr into a DownloadError by matching known messages
+pub fn classify_download_error(stderr: &str) -> DownloadError {
+    let stderr_lower = stderr.to_lowercase();
+    let contains_any = |needles: &[&str]| needles.iter().any(|n| stderr_lower.contains(n));
+    
+    if contains_any(&["ffmpeg not found", "ffmpeg is not installed"]) {
+        return DownloadError::FfmpegMissing("process this download".to_string());
+    }
+    // Checked early: membership errors can also mention sign-in or unavailability
+    if contains_any(&["available to this channel's members", "join this channel to get access", "members-only content", "only available for premium users"]) {
+        return DownloadError::MembersOnly;
+    }
+    if contains_any(&["not available in your country", "geo restrict", "geo-restrict", "blocked it in your country"]) {
+        return DownloadError::GeoBlocked;
+    }
+    if contains_any(&["age-restricted", "age restricted", "sign in to confirm your age", "inappropriate for some users"]) {
+        return DownloadError::AgeRestricted;
+    }
+    if contains_any(&["requested format is not available", "format is not available", "no video formats found"]) {
+        return DownloadError::FormatUnavailable;
+    }
+    if contains_any(&["video unavailable", "this video is unavailable", "has been removed", "private video", "http error 404"]) {
+        return DownloadError::VideoUnavailable;
+    }
+    if contains_any(&["timed out", "timeout", "connection reset", "temporary failure in name resolution", "network is unreachable", "unable to connect"]) {
+        return DownloadError::NetworkTimeout;
+    }
+    
+    // Keep yt-dlp's own last error line so unknown failures are still actionable
+    let detail = stderr
+        .lines()
+        .rev()
+        .find(|l| l.trim_start().starts_with("ERROR:"))
+        .or_else(|| stderr.lines().rev().find(|l| !l.trim().is_empty()))
+        .map(|l| l.trim().to_string())
+        .unwrap_or_else(|| "Download failed".to_string());
+    DownloadError::Unknown(detail)
+}
+
+/// Classify a failed download like `classify_download_error`; unknown failures also carry
+/// yt-dlp's recent warnings and an update hint
+pub fn classify_download_failure(stderr: &str) -> DownloadError {
+    note_ytdlp_outdated(stderr);
+    match classify_download_error(stderr) {
+        DownloadError::Unknown(message) => DownloadError::Unknown(with_stderr_warnings(message, stderr)),
+        error => error,
+    }
+}
+
+/// Error message for a failed yt-dlp run: the friendly message if known, otherwise the classified error
+fn ytdlp_failure_message(stderr: &str) -> String {
+    note_ytdlp_outdated(stderr);
+    let message = parse_ytdlp_error(stderr).unwrap_or_else(|| classify_download_error(stderr).to_string());
+    with_stderr_warnings(message, stderr)
+}
+
+/// Number of recent stderr warning lines surfaced in `ytdlp-warning` events and error messages
+pub const YTDLP_WARNING_LINES: usize = 5;
+
+/// Set when yt-dlp reports that it is out of date; cleared after a successful update
+static YTDLP_OUTDATED: AtomicBool = AtomicBool::new(false);
+
+/// The last `YTDLP_WARNING_LINES` "WARNING:" lines yt-dlp printed
+pub fn stderr_warnings(stderr: &str) -> Vec<String> {
+    let warnings: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("WARNING:"))
+        .collect();
+    let skip = warnings.len().saturating_sub(YTDLP_WARNING_LINES);
+    warnings[skip..].iter().map(|line| line.to_string()).collect()
+}
+
+/// Whether a stderr line is yt-dlp suggesting that it should be updated
+pub fn is_outdated_warning(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    [
+        "out of date",
+        "outdated version",
+        "older than 90 days",
+        "update to the latest version",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+}
+
+/// Remember whether stderr says yt-dlp is out of date; returns true if it does
+fn note_ytdlp_outdated(stderr: &str) -> bool {
+    let outdated = stderr.lines().any(is_outdated_warning);
+    if outdated {
+        YTDLP_OUTDATED.store(true, Ordering::SeqCst);
+    }
+    outdated
+}
+
+/// Whether yt-dlp has reported being out of date since the last update
+pub fn is_ytdlp_outdated() -> bool {
+    YTDLP_OUTDATED.load(Ordering::SeqCst)
+}
+
+/// Forget an earlier out-of-date report, e.g. after `update_ytdlp`
+pub fn clear_ytdlp_outdated() {
+    YTDLP_OUTDATED.store(false, Ordering::SeqCst);
+}
+
+/// Append yt-dlp's recent warnings, and an update hint if it reported being out of date, to an error message
+fn with_stderr_warnings(mut message: String, stderr: &str) -> String {
+    let warnings = stderr_warnings(stderr);
+    if !warnings.is_empty() {
+        message.push_str("\n\nyt-dlp warnings:\n");
+        message.push_str(&warnings.join("\n"));
+    }
+    if stderr.lines().any(is_outdated_warning) {
+        message.push_str("\n\nyt-dlp appears to be out of date. Update it in Settings.");
+    }
+    message
+}
+
+/// Emit the warnings of a successful yt-dlp run as a `ytdlp-warning` event.
+/// `id` is the download id when the run was a download.
+pub fn report_ytdlp_warnings(app: &AppHandle, id: Option<&str>, stderr: &str) {
+    let outdated = note_ytdlp_outdated(stderr);
+    let lines = stderr_warnings(stderr);
+    if lines.is_empty() && !outdated {
+        return;
+    }
+    app.emit("ytdlp-warning", YtdlpWarning {
+        id: id.map(|s| s.to_string()),
+        lines,
+        outdated,
+    }).ok();
+}
+
+/// Default number of attempts for yt-dlp JSON commands (first run plus retries)
+pub const DEFAULT_YTDLP_ATTEMPTS: u32 = 3;
+
+/// Whether a failed yt-dlp run looks transient (throttling, dropped connection)
+/// and is worth retrying. Permanent failures such as removed or private videos never are.
+pub fn is_transient_ytdlp_error(stderr: &str) -> bool {
+    let stderr_lower = stderr.to_lowercase();
+    let contains_any = |needles: &[&str]| needles.iter().any(|n| stderr_lower.contains(n));
+    
+    if contains_any(&["video unavailable", "private video", "has been removed", "sign in to confirm your age", "members-only", "http error 404"]) {
+        return false;
+    }
+    contains_any(&["http error 403", "connection reset", "unable to download webpage", "timed out", "temporary failure in name resolution"])
+}
+
+/// Why a single yt-dlp run failed
+enum YtdlpRunError {
+    /// The process could not be started or crashed
+    Spawn(String),
+    /// yt-dlp exited non-zero; holds its stderr
+    Failed(String),
+}
+
+/// Helper to run yt-dlp command and get JSON output, retrying transient failures
 pub async fn run_ytdlp_json(app: &AppHandle, args: &[&str]) -> Result<String, String> {
+    run_ytdlp_json_with_retry(app, args, DEFAULT_YTDLP_ATTEMPTS).await
+}
+
+/// Run a yt-dlp JSON command up to `max_attempts` times, backing off 1s, 2s, 4s...
+/// between attempts while stderr indicates a transient error
+pub async fn run_ytdlp_json_with_retry(app: &AppHandle, args: &[&str], max_attempts: u32) -> Result<String, String> {
+    let mut attempt = 1;
+    loop {
+        match run_ytdlp_json_once(app, args).await {
+            Ok(output) => return Ok(output),
+            Err(YtdlpRunError::Failed(stderr)) if attempt < max_attempts && is_transient_ytdlp_error(&stderr) => {
+                let delay = std::time::Duration::from_secs(1 << (attempt - 1));
+                #[cfg(debug_assertions)]
+                println!("[YTDLP] Transient failure (attempt {}/{}), retrying in {:?}", attempt, max_attempts, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(YtdlpRunError::Failed(stderr)) => return Err(ytdlp_failure_message(&stderr)),
+            Err(YtdlpRunError::Spawn(message)) => return Err(message),
+        }
+    }
+}
+
+/// Kills a sidecar yt-dlp that is still running when the future driving it is dropped,
+/// e.g. because the caller cancelled; the system binary gets the same via `kill_on_drop`
+struct SidecarGuard(Option<CommandChild>);
+
+impl Drop for SidecarGuard {
+    fn drop(&mut self) {
+        if let Some(child) = self.0.take() {
+            child.kill().ok();
+        }
+    }
+}
+
+/// Run yt-dlp once, keeping stderr so the caller can decide whether to retry
+async fn run_ytdlp_json_once(app: &AppHandle, args: &[&str]) -> Result<String, YtdlpRunError> {
     // Try to get yt-dlp path (prioritizes user-updated version)
     if let Some((binary_path, _)) = get_ytdlp_path(app).await {
         let output = Command::new(&binary_path)
             .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
             .output()
             .await
-            .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
+            .map_err(|e| YtdlpRunError::Spawn(format!("Failed to run yt-dlp: {}", e)))?;
         
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if let Some(parsed_error) = parse_ytdlp_error(&stderr) {
-                return Err(parsed_error);
-            }
-            return Err("yt-dlp command failed".to_string());
+            return Err(YtdlpRunError::Failed(String::from_utf8_lossy(&output.stderr).to_string()));
         }
         
+        report_ytdlp_warnings(app, None, &String::from_utf8_lossy(&output.stderr));
         return Ok(String::from_utf8_lossy(&output.stdout).to_string());
     }
     
     // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
-    
-    match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, _child) = sidecar
-                .args(args)
-                .spawn()
-                .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    let started = Instant::now();
+    match spawn_ytdlp_sidecar(app, args).map_err(YtdlpRunError::Spawn)? {
+        Some((mut rx, child)) => {
+            let mut child = SidecarGuard(Some(child));
             
             let mut output = String::new();
             let mut stderr_output = String::new();
@@ -220,45 +461,50 @@ pub async fn run_ytdlp_json(app: &AppHandle, args: &[&str]) -> Result<String, St
                         stderr_output.push_str(&String::from_utf8_lossy(&bytes));
                     }
                     CommandEvent::Error(err) => {
-                        return Err(format!("Process error: {}", err));
+                        return Err(YtdlpRunError::Spawn(format!("Process error: {}", err)));
                     }
                     CommandEvent::Terminated(status) => {
+                        child.0 = None;
                         if status.code != Some(0) {
-                            // Parse stderr for user-friendly error
-                            if let Some(parsed_error) = parse_ytdlp_error(&stderr_output) {
-                                return Err(parsed_error);
+                            // A binary for another architecture can start and die at once without a word
+                            if output.is_empty() && stderr_output.trim().is_empty() && started.elapsed() < SIDECAR_STARTUP_GRACE {
+                                mark_sidecar_unusable(&format!(
+                                    "Sidecar exited with code {:?} (signal {:?}) after {:?} without output",
+                                    status.code, status.signal, started.elapsed()
+                                ));
+                                return run_system_ytdlp_json(app, args).await;
                             }
-                            return Err("yt-dlp command failed".to_string());
+                            return Err(YtdlpRunError::Failed(stderr_output));
                         }
                     }
                     _ => {}
                 }
             }
             
+            report_ytdlp_warnings(app, None, &stderr_output);
             Ok(output)
         }
-        Err(_) => {
-            // Fallback to system yt-dlp
-            let output = Command::new("yt-dlp")
-                .args(args)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .await
-                .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
-            
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                // Parse stderr for user-friendly error
-                if let Some(parsed_error) = parse_ytdlp_error(&stderr) {
-                    return Err(parsed_error);
-                }
-                return Err("yt-dlp command failed".to_string());
-            }
-            
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        }
+        None => run_system_ytdlp_json(app, args).await,
+    }
+}
+
+/// Run the system yt-dlp (from PATH) once
+async fn run_system_ytdlp_json(app: &AppHandle, args: &[&str]) -> Result<String, YtdlpRunError> {
+    let output = Command::new("yt-dlp")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| YtdlpRunError::Spawn(format!("Failed to run yt-dlp: {}", e)))?;
+    
+    if !output.status.success() {
+        return Err(YtdlpRunError::Failed(String::from_utf8_lossy(&output.stderr).to_string()));
     }
+    
+    report_ytdlp_warnings(app, None, &String::from_utf8_lossy(&output.stderr));
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
 /// Get yt-dlp version
@@ -269,6 +515,7 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
             .args(["--version"])
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
+            .kill_on_drop(true)
             .output()
             .await
             .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
@@ -286,19 +533,15 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
     }
     
     // Fallback to sidecar
-    let sidecar_result = app.shell().sidecar("yt-dlp");
-    
-    let (version, is_bundled, binary_path) = match sidecar_result {
-        Ok(sidecar) => {
-            let (mut rx, _child) = sidecar
-                .args(["--version"])
-                .spawn()
-                .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
-            
+    let (version, is_bundled, binary_path) = match spawn_ytdlp_sidecar(app, ["--version"])? {
+        Some((mut rx, child)) => {
+            let mut child = SidecarGuard(Some(child));
             let mut output = String::new();
             while let Some(event) = rx.recv().await {
-                if let CommandEvent::Stdout(bytes) = event {
-                    output.push_str(&String::from_utf8_lossy(&bytes));
+                match event {
+                    CommandEvent::Stdout(bytes) => output.push_str(&String::from_utf8_lossy(&bytes)),
+                    CommandEvent::Terminated(_) => child.0 = None,
+                    _ => {}
                 }
             }
             
@@ -310,11 +553,12 @@ pub async fn get_ytdlp_version_internal(app: &AppHandle) -> Result<YtdlpVersionI
             
             (version, true, bin_path)
         }
-        Err(_) => {
+        None => {
             let output = Command::new("yt-dlp")
                 .args(["--version"])
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
+                .kill_on_drop(true)
                 .output()
                 .await
                 .map_err(|e| format!("yt-dlp not found: {}", e))?;
@@ -367,41 +611,133 @@ pub fn verify_sha256(data: &[u8], expected_hash: &str) -> bool {
     computed_hash.eq_ignore_ascii_case(expected_hash)
 }
 
+/// Browsers that yt-dlp can read cookies from
+pub const SUPPORTED_COOKIE_BROWSERS: &[&str] = &[
+    "chrome", "firefox", "edge", "safari", "brave", "chromium", "opera", "vivaldi", "whale",
+];
+
+/// Where yt-dlp should read authentication cookies from
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieSource {
+    /// Netscape-format cookies file (--cookies)
+    File(PathBuf),
+    /// Browser and optional profile name (--cookies-from-browser browser[:profile]).
+    /// On macOS, reading Chromium-based browsers' cookies may prompt for Keychain access.
+    Browser { name: String, profile: Option<String> },
+}
+
+/// Check a browser profile name or path before it is appended as "browser:profile".
+/// "::" would be read as yt-dlp's container separator; a single ':' is fine, as in
+/// Windows paths like `C:\Users\me\...\Profile 1`.
+pub fn validate_browser_profile(profile: &str) -> Result<String, String> {
+    let profile = profile.trim();
+    if profile.contains("::") {
+        return Err("Browser profile must not contain \"::\"".to_string());
+    }
+    match profile.chars().find(|c| c.is_control()) {
+        Some(c) => Err(format!("Invalid character '{}' in browser profile name", c.escape_default())),
+        None => Ok(profile.to_string()),
+    }
+}
+
+impl CookieSource {
+    /// Resolve and validate cookie settings as sent by the frontend
+    /// Returns None when cookies are off or not fully configured
+    pub fn from_settings(
+        cookie_mode: Option<&str>,
+        cookie_browser: Option<&str>,
+        cookie_browser_profile: Option<&str>,
+        cookie_file_path: Option<&str>,
+    ) -> Result<Option<Self>, String> {
+        match cookie_mode.unwrap_or("off") {
+            "browser" => {
+                let Some(browser) = cookie_browser.filter(|b| !b.is_empty()) else {
+                    return Ok(None);
+                };
+                let name = browser.to_lowercase();
+                if !SUPPORTED_COOKIE_BROWSERS.contains(&name.as_str()) {
+                    return Err(format!(
+                        "Unsupported browser for cookies: '{}'. Supported: {}",
+                        browser,
+                        SUPPORTED_COOKIE_BROWSERS.join(", ")
+                    ));
+                }
+                let profile = match cookie_browser_profile.filter(|p| !p.trim().is_empty()) {
+                    Some(profile) => Some(validate_browser_profile(profile)?),
+                    None => None,
+                };
+                Ok(Some(CookieSource::Browser { name, profile }))
+            }
+            "file" => {
+                let Some(file_path) = cookie_file_path.filter(|f| !f.is_empty()) else {
+                    return Ok(None);
+                };
+                let path = PathBuf::from(file_path);
+                if !path.is_file() {
+                    return Err(format!("Cookies file not found: {}", file_path));
+                }
+                Ok(Some(CookieSource::File(path)))
+            }
+            _ => Ok(None),
+        }
+    }
+    
+    /// Convert to yt-dlp args
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            CookieSource::File(path) => vec!["--cookies".to_string(), path.to_string_lossy().to_string()],
+            CookieSource::Browser { name, profile } => {
+                let spec = match profile {
+                    Some(profile) => format!("{}:{}", name, profile),
+                    None => name.clone(),
+                };
+                vec!["--cookies-from-browser".to_string(), spec]
+            }
+        }
+    }
+}
+
 /// Build cookie args for yt-dlp based on cookie settings
+/// Invalid settings are skipped here; commands validate them up front with `CookieSource::from_settings`
 pub fn build_cookie_args(
     cookie_mode: Option<&str>,
     cookie_browser: Option<&str>,
     cookie_browser_profile: Option<&str>,
     cookie_file_path: Option<&str>,
 ) -> Vec<String> {
-    let mut args = Vec::new();
+    CookieSource::from_settings(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path)
+        .ok()
+        .flatten()
+        .map(|source| source.to_args())
+        .unwrap_or_default()
+}
+
+/// Proxy schemes accepted by yt-dlp's --proxy
+const SUPPORTED_PROXY_SCHEMES: &[&str] = &["http", "https", "socks5"];
+
+/// Validate a proxy URL, returning None when the proxy is disabled
+pub fn validate_proxy_url(proxy_url: Option<&str>) -> Result<Option<String>, String> {
+    let Some(url) = proxy_url.map(str::trim).filter(|u| !u.is_empty() && *u != "off") else {
+        return Ok(None);
+    };
     
-    let mode = cookie_mode.unwrap_or("off");
-    match mode {
-        "browser" => {
-            if let Some(browser) = cookie_browser {
-                let mut cookie_arg = browser.to_string();
-                if let Some(profile) = cookie_browser_profile {
-                    if !profile.is_empty() {
-                        cookie_arg = format!("{}:{}", browser, profile);
-                    }
-                }
-                args.push("--cookies-from-browser".to_string());
-                args.push(cookie_arg);
-            }
-        }
-        "file" => {
-            if let Some(file_path) = cookie_file_path {
-                if !file_path.is_empty() {
-                    args.push("--cookies".to_string());
-                    args.push(file_path.to_string());
-                }
-            }
-        }
-        _ => {}
+    let (scheme, rest) = url.split_once("://")
+        .ok_or_else(|| format!("Invalid proxy URL '{}'. Expected format: http://host:port or socks5://host:port", url))?;
+    if !SUPPORTED_PROXY_SCHEMES.contains(&scheme.to_lowercase().as_str()) {
+        return Err(format!(
+            "Unsupported proxy scheme '{}'. Supported: {}",
+            scheme,
+            SUPPORTED_PROXY_SCHEMES.join(", ")
+        ));
     }
     
-    args
+    // Strip optional credentials and check there is a host
+    let host = rest.rsplit('@').next().unwrap_or("").trim_end_matches('/');
+    if host.is_empty() || host.starts_with(':') {
+        return Err(format!("Invalid proxy URL '{}': missing host", url));
+    }
+    
+    Ok(Some(url.to_string()))
 }
 
 /// Build proxy args for yt-dlp based on proxy URL
@@ -420,6 +756,89 @@ pub fn build_proxy_args(proxy_url: Option<&str>) -> Vec<String> {
     args
 }
 
+/// Network settings in effect; loaded from network_config.json at startup and replaced on save
+static NETWORK_CONFIG: LazyLock<RwLock<NetworkConfig>> = LazyLock::new(|| RwLock::new(NetworkConfig::default()));
+
+/// Network settings every yt-dlp run uses
+pub fn network_config() -> NetworkConfig {
+    NETWORK_CONFIG.read().map(|config| config.clone()).unwrap_or_default()
+}
+
+pub fn set_network_config(config: NetworkConfig) {
+    if let Ok(mut current) = NETWORK_CONFIG.write() {
+        *current = config;
+    }
+}
+
+/// yt-dlp args for the current network settings (socket timeout and retries)
+pub fn network_args() -> Vec<String> {
+    network_config().to_args()
+}
+
+/// Network/auth options shared by yt-dlp invocations.
+/// Socket timeout and retries always come from the saved `NetworkConfig`
+#[derive(Debug, Clone, Default)]
+pub struct YtdlpOptions {
+    pub cookies: Option<CookieSource>,
+    pub proxy: Option<String>,
+}
+
+impl YtdlpOptions {
+    /// Build options from frontend settings, validating cookies and proxy
+    pub fn from_settings(
+        cookie_mode: Option<&str>,
+        cookie_browser: Option<&str>,
+        cookie_browser_profile: Option<&str>,
+        cookie_file_path: Option<&str>,
+        proxy_url: Option<&str>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            cookies: CookieSource::from_settings(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path)?,
+            proxy: validate_proxy_url(proxy_url)?,
+        })
+    }
+    
+    /// Convert to yt-dlp args
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref cookies) = self.cookies {
+            args.extend(cookies.to_args());
+        }
+        args.extend(build_proxy_args(self.proxy.as_deref()));
+        args.extend(network_args());
+        args
+    }
+    
+    /// Append options to base args
+    fn apply(&self, base_args: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = base_args.iter().map(|s| s.to_string()).collect();
+        args.extend(self.to_args());
+        args
+    }
+}
+
+/// Helper to run yt-dlp command with options and get JSON output
+pub async fn run_ytdlp_json_with_options(
+    app: &AppHandle,
+    base_args: &[&str],
+    options: &YtdlpOptions,
+) -> Result<String, String> {
+    let args = options.apply(base_args);
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    run_ytdlp_json(app, &args_ref).await
+}
+
+/// Helper to run yt-dlp command with options and get output with stderr
+pub async fn run_ytdlp_with_stderr_and_options(
+    app: &AppHandle,
+    base_args: &[&str],
+    options: &YtdlpOptions,
+) -> Result<YtdlpOutput, String> {
+    let args = options.apply(base_args);
+    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    run_ytdlp_with_stderr(app, &args_ref).await
+}
+
 /// Helper to run yt-dlp command with cookie and proxy support and get JSON output
 pub async fn run_ytdlp_json_with_cookies(
     app: &AppHandle,
@@ -430,16 +849,8 @@ pub async fn run_ytdlp_json_with_cookies(
     cookie_file_path: Option<&str>,
     proxy_url: Option<&str>,
 ) -> Result<String, String> {
-    // Build full args with cookies and proxy
-    let cookie_args = build_cookie_args(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path);
-    let proxy_args = build_proxy_args(proxy_url);
-    let mut args: Vec<String> = base_args.iter().map(|s| s.to_string()).collect();
-    args.extend(cookie_args);
-    args.extend(proxy_args);
-    
-    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    
-    run_ytdlp_json(app, &args_ref).await
+    let options = YtdlpOptions::from_settings(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path, proxy_url)?;
+    run_ytdlp_json_with_options(app, base_args, &options).await
 }
 
 /// Helper to run yt-dlp command with cookie and proxy support and get output with stderr
@@ -452,14 +863,103 @@ pub async fn run_ytdlp_with_stderr_and_cookies(
     cookie_file_path: Option<&str>,
     proxy_url: Option<&str>,
 ) -> Result<YtdlpOutput, String> {
-    // Build full args with cookies and proxy
-    let cookie_args = build_cookie_args(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path);
-    let proxy_args = build_proxy_args(proxy_url);
-    let mut args: Vec<String> = base_args.iter().map(|s| s.to_string()).collect();
-    args.extend(cookie_args);
-    args.extend(proxy_args);
-    
-    let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    
-    run_ytdlp_with_stderr(app, &args_ref).await
+    let options = YtdlpOptions::from_settings(cookie_mode, cookie_browser, cookie_browser_profile, cookie_file_path, proxy_url)?;
+    run_ytdlp_with_stderr_and_options(app, base_args, &options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_errors_are_retried() {
+        assert!(is_transient_ytdlp_error("ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno 104] Connection reset by peer>"));
+        assert!(is_transient_ytdlp_error("ERROR: unable to download video data: HTTP Error 403: Forbidden"));
+        assert!(is_transient_ytdlp_error("ERROR: [youtube] abc: Unable to download webpage: The read operation timed out"));
+    }
+
+    #[test]
+    fn test_exec_format_errors() {
+        assert!(is_exec_format_error("Exec format error (os error 8)"));
+        assert!(is_exec_format_error("Bad CPU type in executable (os error 86)"));
+        assert!(is_exec_format_error("%1 is not a valid Win32 application. (os error 193)"));
+        assert!(!is_exec_format_error("No such file or directory (os error 2)"));
+    }
+
+    #[test]
+    fn test_permanent_errors_are_not_retried() {
+        assert!(!is_transient_ytdlp_error("ERROR: [youtube] abc: Video unavailable. This video has been removed by the uploader"));
+        assert!(!is_transient_ytdlp_error("ERROR: [youtube] abc: Private video. Sign in if you've been granted access to this video"));
+        assert!(!is_transient_ytdlp_error("ERROR: [youtube] abc: Unable to download webpage: HTTP Error 404: Not Found"));
+        assert!(!is_transient_ytdlp_error("ERROR: Unsupported URL: https://example.com"));
+        assert!(!is_transient_ytdlp_error(""));
+    }
+
+    #[test]
+    fn test_stderr_warnings_and_update_hint() {
+        let stderr = (1..=7)
+            .map(|i| format!("WARNING: [youtube] warning {}", i))
+            .chain(["[download] Destination: a.mp4".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let warnings = stderr_warnings(&stderr);
+        assert_eq!(warnings.len(), YTDLP_WARNING_LINES);
+        assert_eq!(warnings[0], "WARNING: [youtube] warning 3");
+        assert_eq!(warnings[4], "WARNING: [youtube] warning 7");
+        
+        assert!(is_outdated_warning("WARNING: You are using an outdated version of yt-dlp"));
+        // Part of every bug-report hint, not an update notice
+        assert!(!is_outdated_warning("ERROR: ...; please report this issue. Confirm you are on the latest version using  yt-dlp -U"));
+        assert!(!is_outdated_warning("WARNING: Requested format is not available"));
+        
+        let message = with_stderr_warnings(
+            "Unsupported URL".to_string(),
+            "WARNING: Requested format is not available\nWARNING: You are using an outdated version of yt-dlp",
+        );
+        assert!(message.starts_with("Unsupported URL\n\nyt-dlp warnings:\nWARNING: Requested format is not available"));
+        assert!(message.ends_with("Update it in Settings."));
+        assert_eq!(with_stderr_warnings("Failed".to_string(), "ERROR: nope"), "Failed");
+    }
+
+    #[test]
+    fn test_classify_missing_ffmpeg() {
+        let stderr = "ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path using --ffmpeg-location";
+        assert_eq!(classify_download_error(stderr).code(), "ffmpeg_missing");
+    }
+
+    #[test]
+    fn test_classify_members_only() {
+        let stderr = "ERROR: [youtube] abc123: This video is available to this channel's members on level: Member (or any higher level). Join this channel to get access to members-only content and other exclusive perks.";
+        assert_eq!(classify_download_error(stderr), DownloadError::MembersOnly);
+        assert!(parse_ytdlp_error(stderr).unwrap().contains("channel members"));
+    }
+
+    #[test]
+    fn test_cookies_from_browser_profile() {
+        let source = CookieSource::from_settings(Some("browser"), Some("Chrome"), Some(" Profile 1 "), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.to_args(), vec!["--cookies-from-browser", "chrome:Profile 1"]);
+        
+        let source = CookieSource::from_settings(Some("browser"), Some("firefox"), Some(""), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(source.to_args(), vec!["--cookies-from-browser", "firefox"]);
+        
+        assert!(CookieSource::from_settings(Some("browser"), Some("chrome"), Some("x::container"), None).is_err());
+        assert!(CookieSource::from_settings(Some("browser"), Some("chrome"), Some("Profile\n1"), None).is_err());
+        
+        let source = CookieSource::from_settings(
+            Some("browser"),
+            Some("chrome"),
+            Some(r"C:\Users\me\AppData\Local\Google\Chrome\User Data\Profile 1"),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            source.to_args()[1],
+            r"chrome:C:\Users\me\AppData\Local\Google\Chrome\User Data\Profile 1"
+        );
+    }
 }
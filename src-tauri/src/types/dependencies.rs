@@ -10,6 +10,35 @@ pub struct YtdlpVersionInfo {
     pub binary_path: String,
 }
 
+/// Non-fatal warnings from a yt-dlp run, emitted as `ytdlp-warning`
+#[derive(Clone, Serialize, Debug)]
+pub struct YtdlpWarning {
+    /// Download id when the warnings came from a download
+    pub id: Option<String>,
+    pub lines: Vec<String>,
+    /// yt-dlp suggested updating itself
+    pub outdated: bool,
+}
+
+/// Versions and origins of the external tools in use, for diagnostics and bug reports
+#[derive(Clone, Serialize, Debug)]
+pub struct ToolVersions {
+    pub ytdlp_version: Option<String>,
+    pub ffmpeg_version: Option<String>,
+    /// "bundled" (shipped or updated by the app) or "system"
+    pub ytdlp_source: Option<String>,
+    pub ffmpeg_source: Option<String>,
+}
+
+/// One row of the diagnostics screen, as returned by `run_healthcheck`
+#[derive(Clone, Serialize, Debug)]
+pub struct HealthCheck {
+    pub name: String,
+    pub ok: bool,
+    /// Version, error message or other short explanation
+    pub detail: String,
+}
+
 /// FFmpeg installation status
 #[derive(Clone, Serialize, Debug)]
 pub struct FfmpegStatus {
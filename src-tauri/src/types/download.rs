@@ -1,4 +1,5 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
 
 #[derive(Clone, Serialize)]
 pub struct DownloadProgress {
@@ -7,10 +8,403 @@ pub struct DownloadProgress {
     pub speed: String,
     pub eta: String,
     pub status: String,
+    /// Current step: "downloading", "recording", "merging", "extracting_audio", "converting", "embedding", "skipped", "paused" or "finished"
+    pub phase: String,
     pub title: Option<String>,
+    /// Position in download order (1..=playlist_count). With a reversed or shuffled
+    /// `PlaylistOrder` this is not the item's position in the playlist
     pub playlist_index: Option<u32>,
     pub playlist_count: Option<u32>,
+    /// Completion of the whole playlist, 0-100; None outside playlists
+    pub overall_percent: Option<f64>,
     pub filesize: Option<u64>,
     pub resolution: Option<String>,
     pub format_ext: Option<String>,
+    /// Live recordings have no known total, so `percent` carries no meaning
+    pub indeterminate: bool,
+}
+
+/// Classified download failure, serialized as `{ code, message }` for the frontend
+#[derive(Clone, Debug, PartialEq)]
+pub enum DownloadError {
+    VideoUnavailable,
+    GeoBlocked,
+    AgeRestricted,
+    /// Channel-membership or Premium-only video; the signed-in account lacks access
+    MembersOnly,
+    NetworkTimeout,
+    FormatUnavailable,
+    /// Every candidate format exceeded the max_filesize cap
+    FileTooLarge,
+    /// FFmpeg is not installed but the download needs it; carries the step that needs it,
+    /// e.g. "extract audio"
+    FfmpegMissing(String),
+    Cancelled,
+    /// Stopped by `pause_download`; `resume_download` continues it
+    Paused,
+    Unknown(String),
+}
+
+impl DownloadError {
+    /// Stable machine-readable code
+    pub fn code(&self) -> &'static str {
+        match self {
+            DownloadError::VideoUnavailable => "video_unavailable",
+            DownloadError::GeoBlocked => "geo_blocked",
+            DownloadError::AgeRestricted => "age_restricted",
+            DownloadError::MembersOnly => "members_only",
+            DownloadError::NetworkTimeout => "network_timeout",
+            DownloadError::FormatUnavailable => "format_unavailable",
+            DownloadError::FileTooLarge => "file_too_large",
+            DownloadError::FfmpegMissing(_) => "ffmpeg_missing",
+            DownloadError::Cancelled => "cancelled",
+            DownloadError::Paused => "paused",
+            DownloadError::Unknown(_) => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::VideoUnavailable => write!(f, "This video is unavailable. It may have been removed or made private."),
+            DownloadError::GeoBlocked => write!(f, "This video is not available in your region. Try a proxy in Settings."),
+            DownloadError::AgeRestricted => write!(f, "This video is age-restricted. Please enable authentication in Settings → Video Authentication to access it."),
+            DownloadError::MembersOnly => write!(f, "This video is only available to channel members (or Premium subscribers). Downloading it needs cookies from an account with access: enable authentication in Settings → Video Authentication and sign in with that account."),
+            DownloadError::NetworkTimeout => write!(f, "The connection timed out. Check your network or proxy settings and try again."),
+            DownloadError::FormatUnavailable => write!(f, "The requested quality or format is not available for this video. Try a different quality."),
+            DownloadError::FileTooLarge => write!(f, "No available format fits within your maximum file size. Raise the size limit or choose a lower quality."),
+            DownloadError::FfmpegMissing(step) => write!(f, "FFmpeg is required to {} but is not installed. Please install FFmpeg from Settings → Dependencies.", step),
+            DownloadError::Cancelled => write!(f, "Download cancelled"),
+            DownloadError::Paused => write!(f, "Download paused"),
+            DownloadError::Unknown(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Serialize for DownloadError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DownloadError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<String> for DownloadError {
+    fn from(msg: String) -> Self {
+        DownloadError::Unknown(msg)
+    }
+}
+
+impl From<&str> for DownloadError {
+    fn from(msg: &str) -> Self {
+        DownloadError::Unknown(msg.to_string())
+    }
+}
+
+/// SponsorBlock handling for a download
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "categories", rename_all = "lowercase")]
+pub enum SponsorBlockMode {
+    #[default]
+    Off,
+    /// Mark segments as chapters
+    Mark(Vec<String>),
+    /// Cut segments out of the file (re-encodes around the cuts)
+    Remove(Vec<String>),
+}
+
+/// Order in which playlist items are downloaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistOrder {
+    #[default]
+    Original,
+    /// Last item first (--playlist-reverse)
+    Reverse,
+    /// Random order (--playlist-random)
+    Shuffle,
+}
+
+/// Named output filename template offered in the UI
+#[derive(Clone, Debug, Serialize)]
+pub struct FilenamePreset {
+    pub id: String,
+    pub label: String,
+    pub template: String,
+}
+
+/// One item a download would produce, as resolved by a simulated (dry) run
+#[derive(Clone, Debug, Serialize)]
+pub struct PlannedDownload {
+    pub title: String,
+    pub filename: String,
+    pub format_id: String,
+    pub filesize_estimate: Option<u64>,
+}
+
+/// Approximate total size of a playlist download
+#[derive(Clone, Debug, Serialize)]
+pub struct PlaylistSizeEstimate {
+    pub total_bytes: u64,
+    pub item_count: u32,
+    /// Items whose selected format reported neither filesize nor filesize_approx
+    pub items_without_estimate: u32,
+}
+
+/// One download in a `download_batch` call; mirrors `download_video`'s parameters.
+/// Omitted fields fall back to the same defaults the frontend uses.
+/// Stored as JSON in the download_jobs table so failed downloads can be retried
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DownloadJob {
+    pub id: String,
+    pub url: String,
+    pub output_path: String,
+    pub quality: String,
+    pub format: String,
+    #[serde(default)]
+    pub download_playlist: bool,
+    pub video_codec: Option<String>,
+    pub audio_bitrate: Option<String>,
+    pub playlist_limit: Option<u32>,
+    pub subtitle_mode: Option<String>,
+    pub subtitle_langs: Option<String>,
+    #[serde(default)]
+    pub subtitle_embed: bool,
+    pub subtitle_format: Option<String>,
+    pub log_stderr: Option<bool>,
+    pub use_bun_runtime: Option<bool>,
+    pub use_actual_player_js: Option<bool>,
+    pub history_id: Option<String>,
+    pub cookie_mode: Option<String>,
+    pub cookie_browser: Option<String>,
+    pub cookie_browser_profile: Option<String>,
+    pub cookie_file_path: Option<String>,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub embed_chapters: Option<bool>,
+    pub proxy_url: Option<String>,
+    pub sections: Option<Vec<(String, String)>>,
+    pub sponsorblock: Option<SponsorBlockMode>,
+    pub rate_limit: Option<String>,
+    pub format_id: Option<String>,
+    pub archive_file: Option<std::path::PathBuf>,
+    pub skip_downloaded: Option<bool>,
+    pub filename_template: Option<String>,
+    pub restrict_filenames: Option<bool>,
+    pub windows_safe: Option<bool>,
+    pub max_filename_len: Option<u32>,
+    pub playlist_items: Option<String>,
+    pub max_filesize: Option<String>,
+    pub live: Option<bool>,
+    pub concurrent_fragments: Option<u32>,
+    pub title: Option<String>,
+    pub keep_separate: Option<bool>,
+    pub order: Option<PlaylistOrder>,
+    pub create_playlist_folder: Option<bool>,
+    pub write_info_json: Option<bool>,
+    pub write_description: Option<bool>,
+    pub thumbnail_format: Option<String>,
+    pub use_temp_dir: Option<bool>,
+}
+
+/// What a finished `download_video` call produced
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadResult {
+    /// Extra information for the user, one line per note, e.g. that SponsorBlock removal re-encodes
+    pub note: Option<String>,
+    /// Every file written, in download order (video then audio with keep_separate)
+    pub files: Vec<String>,
+    /// .info.json and .description files written next to the media
+    pub sidecar_files: Vec<String>,
+}
+
+/// Emitted as `summary-ready` when `download_and_summarize` has summarized its download,
+/// or given up trying
+#[derive(Clone, Debug, Serialize)]
+pub struct SummaryReady {
+    /// Download id
+    pub id: String,
+    /// History entry holding the file, transcript and summary
+    pub history_id: String,
+    pub summary: Option<String>,
+    /// Why there is no summary; the download itself succeeded
+    pub error: Option<String>,
+}
+
+/// What a finished `download_and_summarize` call produced
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadSummaryResult {
+    pub download: DownloadResult,
+    pub summary: SummaryReady,
+}
+
+/// Emitted as `ffmpeg-missing` when a download is refused because FFmpeg is not installed
+#[derive(Clone, Debug, Serialize)]
+pub struct FfmpegRequired {
+    pub id: String,
+    /// Step that needs FFmpeg, e.g. "extract audio"
+    pub step: String,
+}
+
+/// Result of one batch job, emitted as `download-job-finished`
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadJobOutcome {
+    pub id: String,
+    pub note: Option<String>,
+    pub error: Option<DownloadError>,
+}
+
+/// A download that ended in an error, as returned by `get_failed_downloads`
+#[derive(Clone, Debug, Serialize)]
+pub struct FailedDownload {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    /// `DownloadError` code and message of the failure
+    pub error_code: String,
+    pub error_message: String,
+    pub failed_at: String,
+}
+
+/// URLs read from an imported link list
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportedUrls {
+    /// Valid, normalized and de-duplicated URLs in file order
+    pub urls: Vec<String>,
+    /// Non-empty, non-comment lines that were not valid URLs
+    pub rejected: u32,
+}
+
+/// Named set of download options, applied by `download_with_profile`
+/// (stored as a list in download_profiles.json in app data). Unset options use download_video's defaults
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DownloadProfile {
+    pub name: String,
+    pub quality: String,
+    pub format: String,
+    #[serde(default)]
+    pub download_playlist: bool,
+    pub video_codec: Option<String>,
+    pub audio_bitrate: Option<String>,
+    pub subtitle_mode: Option<String>,
+    pub subtitle_langs: Option<String>,
+    #[serde(default)]
+    pub subtitle_embed: bool,
+    pub subtitle_format: Option<String>,
+    pub embed_metadata: Option<bool>,
+    pub embed_thumbnail: Option<bool>,
+    pub embed_chapters: Option<bool>,
+    pub thumbnail_format: Option<String>,
+    pub sponsorblock: Option<SponsorBlockMode>,
+    pub rate_limit: Option<String>,
+    pub filename_template: Option<String>,
+    pub restrict_filenames: Option<bool>,
+    pub windows_safe: Option<bool>,
+    pub max_filename_len: Option<u32>,
+    pub max_filesize: Option<String>,
+    pub concurrent_fragments: Option<u32>,
+    pub keep_separate: Option<bool>,
+    pub create_playlist_folder: Option<bool>,
+    pub write_info_json: Option<bool>,
+    pub write_description: Option<bool>,
+    pub skip_downloaded: Option<bool>,
+    pub use_temp_dir: Option<bool>,
+}
+
+impl DownloadProfile {
+    /// Expand into a download of `url` into `output_path`
+    pub fn to_job(&self, id: String, url: String, output_path: String) -> DownloadJob {
+        DownloadJob {
+            id,
+            url,
+            output_path,
+            quality: self.quality.clone(),
+            format: self.format.clone(),
+            download_playlist: self.download_playlist,
+            video_codec: self.video_codec.clone(),
+            audio_bitrate: self.audio_bitrate.clone(),
+            subtitle_mode: self.subtitle_mode.clone(),
+            subtitle_langs: self.subtitle_langs.clone(),
+            subtitle_embed: self.subtitle_embed,
+            subtitle_format: self.subtitle_format.clone(),
+            embed_metadata: self.embed_metadata,
+            embed_thumbnail: self.embed_thumbnail,
+            embed_chapters: self.embed_chapters,
+            sponsorblock: self.sponsorblock.clone(),
+            rate_limit: self.rate_limit.clone(),
+            skip_downloaded: self.skip_downloaded,
+            filename_template: self.filename_template.clone(),
+            restrict_filenames: self.restrict_filenames,
+            windows_safe: self.windows_safe,
+            max_filename_len: self.max_filename_len,
+            max_filesize: self.max_filesize.clone(),
+            concurrent_fragments: self.concurrent_fragments,
+            keep_separate: self.keep_separate,
+            create_playlist_folder: self.create_playlist_folder,
+            write_info_json: self.write_info_json,
+            write_description: self.write_description,
+            thumbnail_format: self.thumbnail_format.clone(),
+            use_temp_dir: self.use_temp_dir,
+            ..Default::default()
+        }
+    }
+}
+
+/// Persisted download defaults (stored as download_config.json in app data)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DownloadConfig {
+    /// Default cookie mode: "off", "browser" or "file"
+    #[serde(default)]
+    pub cookie_mode: Option<String>,
+    #[serde(default)]
+    pub cookie_browser: Option<String>,
+    #[serde(default)]
+    pub cookie_browser_profile: Option<String>,
+    #[serde(default)]
+    pub cookie_file_path: Option<String>,
+    /// Default download rate limit, e.g. "2M" or "500K"
+    #[serde(default)]
+    pub rate_limit: Option<String>,
+    /// Subtitle languages to try for transcripts, most preferred first, e.g. ["vi", "en"]
+    #[serde(default)]
+    pub preferred_langs: Vec<String>,
+}
+
+/// Network resilience settings applied to every yt-dlp run (stored as network_config.json in app data)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Seconds to wait on a stalled connection before giving up (--socket-timeout)
+    pub socket_timeout: u32,
+    /// Attempts for a failed HTTP request (--retries)
+    pub retries: u32,
+    /// Attempts for a failed DASH/HLS fragment (--fragment-retries)
+    pub fragment_retries: u32,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        // yt-dlp's own retry defaults, with a socket timeout short enough to notice a dead connection
+        Self {
+            socket_timeout: 30,
+            retries: 10,
+            fragment_retries: 10,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Convert to yt-dlp args
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--socket-timeout".to_string(),
+            self.socket_timeout.to_string(),
+            "--retries".to_string(),
+            self.retries.to_string(),
+            "--fragment-retries".to_string(),
+            self.fragment_retries.to_string(),
+        ]
+    }
 }
@@ -13,7 +13,49 @@ pub struct HistoryEntry {
     pub quality: Option<String>,
     pub format: Option<String>,
     pub source: Option<String>, // "youtube", "tiktok", etc.
+    #[serde(default)]
+    pub channel: Option<String>, // Channel or uploader name
     pub downloaded_at: String,
     pub file_exists: bool,
     pub summary: Option<String>, // AI-generated summary
+    pub summary_provider: Option<String>, // e.g. "Gemini", "Ollama"
+    pub summary_model: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>, // User labels, e.g. a project name
+}
+
+/// Number of downloads for one format, source or channel
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct StatCount {
+    pub name: String,
+    pub count: u64,
+}
+
+/// Number of downloads on one day
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct DailyCount {
+    /// UTC date, YYYY-MM-DD
+    pub date: String,
+    pub count: u64,
+}
+
+/// Aggregated download history for the statistics view
+#[derive(Clone, Serialize, Debug)]
+pub struct DownloadStats {
+    pub total_downloads: u64,
+    /// Sum of known file sizes; entries without a size count as 0
+    pub total_bytes: u64,
+    /// Most used first; entries without a format are counted as "unknown"
+    pub by_format: Vec<StatCount>,
+    pub by_source: Vec<StatCount>,
+    /// The last 30 days, oldest first, including days without downloads
+    pub per_day: Vec<DailyCount>,
+    pub top_channel: Option<StatCount>,
+}
+
+/// A page of history entries with the total match count for pagination
+#[derive(Clone, Serialize, Debug)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub total: i64,
 }
@@ -11,11 +11,35 @@ pub struct VideoInfo {
     pub uploader: Option<String>,
     pub upload_date: Option<String>,
     pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    /// Subscriber/follower count of the uploader's channel
+    pub channel_follower_count: Option<u64>,
     pub description: Option<String>,
     pub is_playlist: bool,
     pub playlist_count: Option<u32>,
     pub extractor: Option<String>,
     pub extractor_key: Option<String>,
+    /// Chapter markers; omitted for videos without chapters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapters: Option<Vec<Chapter>>,
+}
+
+/// Chapter marker from yt-dlp's `chapters` array
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: Option<f64>,
+}
+
+/// One top-level comment from yt-dlp's `comments` array
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Comment {
+    pub author: Option<String>,
+    pub text: String,
+    pub like_count: Option<u64>,
+    /// Unix timestamp of when the comment was posted
+    pub timestamp: Option<i64>,
 }
 
 /// Format option from yt-dlp
@@ -37,10 +61,48 @@ pub struct FormatOption {
 }
 
 /// Response containing video info and available formats
-#[derive(Clone, Serialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct VideoInfoResponse {
     pub info: VideoInfo,
     pub formats: Vec<FormatOption>,
+    /// True when served from the info cache rather than a fresh yt-dlp fetch
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// Format option with a human readable size, as listed by `get_grouped_formats`
+#[derive(Clone, Serialize, Debug)]
+pub struct GroupedFormat {
+    #[serde(flatten)]
+    pub format: FormatOption,
+    pub filesize_human: Option<String>,
+}
+
+/// Available formats split into video-only, audio-only and combined (muxed) streams
+#[derive(Clone, Serialize, Debug)]
+pub struct GroupedFormats {
+    pub video_only: Vec<GroupedFormat>,
+    pub audio_only: Vec<GroupedFormat>,
+    pub combined: Vec<GroupedFormat>,
+}
+
+/// Whether a requested quality exists for a video, as checked by `check_format_available`
+#[derive(Clone, Serialize, Debug)]
+pub struct FormatAvailability {
+    pub available: bool,
+    /// Tallest video stream offered; None for audio-only sources
+    pub max_height: Option<u32>,
+    /// Quality yt-dlp will settle for instead, e.g. "1080p", when `available` is false
+    pub will_fallback_to: Option<String>,
+}
+
+/// Heights on offer for a video, as returned by `get_available_resolutions`
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct AvailableResolutions {
+    /// Distinct video heights, lowest first, e.g. [360, 480, 720, 1080]
+    pub heights: Vec<u32>,
+    /// Whether an audio-only stream exists
+    pub has_audio_only: bool,
 }
 
 /// Playlist entry with basic video info
@@ -54,6 +116,43 @@ pub struct PlaylistVideoEntry {
     pub channel: Option<String>,
 }
 
+/// What a URL points at, as reported by `classify_url`
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UrlKind {
+    SingleVideo,
+    /// `count` is None when the site doesn't report the playlist length up front
+    Playlist { count: Option<u32> },
+    Channel,
+}
+
+/// Entries returned by `get_playlist_entries`
+#[derive(Clone, Serialize, Debug)]
+pub struct PlaylistEntries {
+    pub entries: Vec<PlaylistVideoEntry>,
+    /// Stopped early by `stop_playlist_fetch`; `entries` holds the ones read so far
+    pub truncated: bool,
+}
+
+/// One playlist entry, emitted as `info-progress` while `fetch_playlist_streaming` runs
+#[derive(Clone, Serialize, Debug)]
+pub struct PlaylistEntryProgress {
+    pub url: String,
+    /// 1-based position among the entries found so far
+    pub index: u32,
+    pub entry: PlaylistVideoEntry,
+}
+
+/// Emitted as `info-progress-done` when `fetch_playlist_streaming` finishes
+#[derive(Clone, Serialize, Debug)]
+pub struct PlaylistFetchDone {
+    pub url: String,
+    pub count: u32,
+    pub error: Option<String>,
+    /// Stopped early by `stop_playlist_fetch`
+    pub truncated: bool,
+}
+
 /// Subtitle information
 #[derive(Clone, Serialize, Debug)]
 pub struct SubtitleInfo {
@@ -61,3 +160,23 @@ pub struct SubtitleInfo {
     pub name: String,
     pub is_auto: bool,
 }
+
+/// Result of a subtitle-only download
+#[derive(Clone, Serialize, Debug)]
+pub struct SubtitleDownloadResult {
+    /// Paths of the subtitle files written to disk
+    pub files: Vec<String>,
+    /// Requested languages that produced a file
+    pub downloaded: Vec<String>,
+    /// Requested languages that were not available for this video
+    pub missing: Vec<String>,
+}
+
+/// A pasted URL in canonical form, as produced by `normalize_url`
+#[derive(Clone, Serialize, Debug)]
+pub struct NormalizedUrl {
+    pub canonical: String,
+    pub video_id: Option<String>,
+    pub playlist_id: Option<String>,
+    pub is_short: bool,
+}
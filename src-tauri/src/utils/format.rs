@@ -1,3 +1,6 @@
+use std::fmt;
+use std::str::FromStr;
+
 /// Format file size in human readable format
 pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -15,32 +18,285 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Format file size with binary units and one decimal, e.g. "45.2 MiB"
+pub fn human_filesize(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Output container or audio format of a download
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DownloadFormat {
+    Mp4,
+    Mkv,
+    Webm,
+    Mp3,
+    M4a,
+    Opus,
+}
+
+impl DownloadFormat {
+    pub const ALL: [DownloadFormat; 6] = [
+        DownloadFormat::Mp4,
+        DownloadFormat::Mkv,
+        DownloadFormat::Webm,
+        DownloadFormat::Mp3,
+        DownloadFormat::M4a,
+        DownloadFormat::Opus,
+    ];
+
+    /// Name used by the frontend and by yt-dlp (--merge-output-format, --audio-format)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DownloadFormat::Mp4 => "mp4",
+            DownloadFormat::Mkv => "mkv",
+            DownloadFormat::Webm => "webm",
+            DownloadFormat::Mp3 => "mp3",
+            DownloadFormat::M4a => "m4a",
+            DownloadFormat::Opus => "opus",
+        }
+    }
+
+    /// Audio formats are extracted from the download instead of merged into a container
+    pub fn is_audio(self) -> bool {
+        matches!(self, DownloadFormat::Mp3 | DownloadFormat::M4a | DownloadFormat::Opus)
+    }
+}
+
+impl FromStr for DownloadFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        DownloadFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| format!(
+                "Unsupported output format: {} (expected one of {})",
+                value,
+                DownloadFormat::ALL.map(DownloadFormat::as_str).join(", ")
+            ))
+    }
+}
+
+impl fmt::Display for DownloadFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Quality preset of a download
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    /// Audio only
+    Audio,
+    P360,
+    P480,
+    P720,
+    P1080,
+    P2k,
+    P4k,
+    P8k,
+    /// Highest available, no height cap
+    Best,
+}
+
+impl Quality {
+    pub const ALL: [Quality; 9] = [
+        Quality::Audio,
+        Quality::P360,
+        Quality::P480,
+        Quality::P720,
+        Quality::P1080,
+        Quality::P2k,
+        Quality::P4k,
+        Quality::P8k,
+        Quality::Best,
+    ];
+
+    /// Name used by the frontend
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Quality::Audio => "audio",
+            Quality::P360 => "360",
+            Quality::P480 => "480",
+            Quality::P720 => "720",
+            Quality::P1080 => "1080",
+            Quality::P2k => "2k",
+            Quality::P4k => "4k",
+            Quality::P8k => "8k",
+            Quality::Best => "best",
+        }
+    }
+
+    /// Maximum video height; None for best and audio
+    pub fn height(self) -> Option<u32> {
+        match self {
+            Quality::P8k => Some(4320),
+            Quality::P4k => Some(2160),
+            Quality::P2k => Some(1440),
+            Quality::P1080 => Some(1080),
+            Quality::P720 => Some(720),
+            Quality::P480 => Some(480),
+            Quality::P360 => Some(360),
+            Quality::Audio | Quality::Best => None,
+        }
+    }
+
+    /// Above 1080p, where VP9 is preferred regardless of the codec choice
+    fn is_high_res(self) -> bool {
+        matches!(self, Quality::P8k | Quality::P4k | Quality::P2k)
+    }
+}
+
+impl FromStr for Quality {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Quality::ALL
+            .into_iter()
+            .find(|quality| quality.as_str() == value)
+            .ok_or_else(|| format!(
+                "Unsupported quality: {} (expected one of {})",
+                value,
+                Quality::ALL.map(Quality::as_str).join(", ")
+            ))
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Image formats thumbnails can be converted to with --convert-thumbnails
+pub const THUMBNAIL_FORMATS: &[&str] = &["jpg", "png", "webp"];
+
+/// Validate a thumbnail format ("jpeg" is accepted as jpg). Defaults to jpg, which every
+/// player accepts as cover art; some choke on webp
+pub fn validate_thumbnail_format(format: Option<&str>) -> Result<&'static str, String> {
+    let format = format.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty());
+    let Some(format) = format else {
+        return Ok("jpg");
+    };
+    let format = if format == "jpeg" { "jpg".to_string() } else { format };
+    THUMBNAIL_FORMATS
+        .iter()
+        .find(|f| **f == format)
+        .copied()
+        .ok_or_else(|| format!(
+            "Unsupported thumbnail format: {} (expected one of {})",
+            format,
+            THUMBNAIL_FORMATS.join(", ")
+        ))
+}
+
 /// Build yt-dlp format string based on quality, format and codec preferences
-pub fn build_format_string(quality: &str, format: &str, video_codec: &str) -> String {
+pub fn build_format_string(quality: Quality, format: DownloadFormat, video_codec: &str) -> String {
     // Audio-only formats
-    if quality == "audio" || format == "mp3" || format == "m4a" || format == "opus" {
+    if quality == Quality::Audio || format.is_audio() {
         return match format {
-            "mp3" => "bestaudio/best".to_string(),
-            "m4a" => "bestaudio[ext=m4a]/bestaudio/best".to_string(),
-            "opus" => "bestaudio[ext=webm]/bestaudio/best".to_string(),
-            _ => "bestaudio[ext=m4a]/bestaudio/best".to_string(),
-        };
+            DownloadFormat::Mp3 => "bestaudio/best",
+            DownloadFormat::Opus => "bestaudio[ext=webm]/bestaudio/best",
+            // "audio" quality with a video container falls back to m4a audio
+            DownloadFormat::M4a | DownloadFormat::Mp4 | DownloadFormat::Mkv | DownloadFormat::Webm => {
+                "bestaudio[ext=m4a]/bestaudio/best"
+            }
+        }
+        .to_string();
     }
     
-    let height = match quality {
-        "8k" => Some("4320"),
-        "4k" => Some("2160"),
-        "2k" => Some("1440"),
-        "1080" => Some("1080"),
-        "720" => Some("720"),
-        "480" => Some("480"),
-        "360" => Some("360"),
-        _ => None,
-    };
+    let height = quality.height();
+    
+    let is_high_res = quality.is_high_res();
+    let codec_filter = codec_filter(quality, video_codec);
     
-    // Build codec filter based on selection
-    let is_high_res = matches!(quality, "8k" | "4k" | "2k");
-    let codec_filter = if is_high_res {
+    match format {
+        DownloadFormat::Mp4 => {
+            if let Some(h) = height {
+                if is_high_res {
+                    format!(
+                        "bestvideo[height<={}][vcodec^=vp9]+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                        h, h, h
+                    )
+                } else if !codec_filter.is_empty() {
+                    format!(
+                        "bestvideo[height<={}]{}[ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]{}+bestaudio/bestvideo[height<={}][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                        h, codec_filter, h, codec_filter, h, h, h
+                    )
+                } else {
+                    format!(
+                        "bestvideo[height<={}][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                        h, h, h
+                    )
+                }
+            } else {
+                "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best".to_string()
+            }
+        }
+        // WebM only holds VP9/AV1 with Opus/Vorbis, so prefer native webm streams over the codec choice
+        DownloadFormat::Webm => match height {
+            Some(h) => format!(
+                "bestvideo[height<={}][ext=webm]+bestaudio[ext=webm]/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                h, h, h
+            ),
+            None => "bestvideo[ext=webm]+bestaudio[ext=webm]/bestvideo+bestaudio/best".to_string(),
+        },
+        // mkv: any codec combination merges cleanly (audio formats returned above)
+        DownloadFormat::Mkv | DownloadFormat::Mp3 | DownloadFormat::M4a | DownloadFormat::Opus => match height {
+            Some(h) if is_high_res => format!(
+                "bestvideo[height<={}][vcodec^=vp9]+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                h, h, h
+            ),
+            Some(h) if !codec_filter.is_empty() => format!(
+                "bestvideo[height<={}]{}+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
+                h, codec_filter, h, h
+            ),
+            Some(h) => format!("bestvideo[height<={}]+bestaudio/best[height<={}]/best", h, h),
+            None => "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best".to_string(),
+        },
+    }
+}
+
+/// Looser selectors to fall back on when `build_format_string` finds no matching format:
+/// first without container (ext) constraints, then also without the height cap.
+/// Entries that would repeat the original selector or an earlier fallback are skipped
+pub fn relaxed_format_strings(quality: Quality, format: DownloadFormat, video_codec: &str) -> Vec<String> {
+    let mut seen = vec![build_format_string(quality, format, video_codec)];
+    let mut fallbacks = Vec::new();
+    // Dropping the height cap keeps the codec the user picked for this quality
+    let uncapped = match codec_filter(quality, video_codec) {
+        "" => build_format_string(Quality::Best, DownloadFormat::Mkv, video_codec),
+        filter => format!("bestvideo{}+bestaudio/bestvideo+bestaudio/best", filter),
+    };
+    // The mkv selectors carry no ext filters; the output container still comes from --merge-output-format
+    for candidate in [
+        build_format_string(quality, DownloadFormat::Mkv, video_codec),
+        uncapped,
+    ] {
+        if !seen.contains(&candidate) {
+            seen.push(candidate.clone());
+            fallbacks.push(candidate);
+        }
+    }
+    fallbacks
+}
+
+/// Video codec filter for a quality preset and codec choice
+fn codec_filter(quality: Quality, video_codec: &str) -> &'static str {
+    if quality.is_high_res() {
         "[vcodec^=vp9]" // Prefer VP9 for high-res
     } else {
         match video_codec {
@@ -49,44 +305,311 @@ pub fn build_format_string(quality: &str, format: &str, video_codec: &str) -> St
             "av1" => "[vcodec^=av01]",
             _ => "", // auto - no codec filter
         }
+    }
+}
+
+/// Build a "video,audio" format string that downloads the best matching video and audio
+/// streams as two separate files instead of merging them. The container only steers
+/// which native streams are preferred, since nothing is remuxed
+pub fn build_separate_format_string(quality: Quality, format: DownloadFormat, video_codec: &str) -> String {
+    let height = quality.height()
+        .map(|h| format!("[height<={}]", h))
+        .unwrap_or_default();
+    let codec = codec_filter(quality, video_codec);
+    let (video_ext, audio_ext) = match format {
+        DownloadFormat::Mp4 => ("[ext=mp4]", "[ext=m4a]"),
+        DownloadFormat::Webm => ("[ext=webm]", "[ext=webm]"),
+        DownloadFormat::Mkv | DownloadFormat::Mp3 | DownloadFormat::M4a | DownloadFormat::Opus => ("", ""),
     };
     
-    if format == "mp4" {
-        if let Some(h) = height {
-            if is_high_res {
-                format!(
-                    "bestvideo[height<={}][vcodec^=vp9]+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
-                    h, h, h
-                )
-            } else if !codec_filter.is_empty() {
-                format!(
-                    "bestvideo[height<={}]{}[ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]{}+bestaudio/bestvideo[height<={}][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
-                    h, codec_filter, h, codec_filter, h, h, h
-                )
-            } else {
+    let mut video = vec![
+        format!("bestvideo{}{}{}", height, codec, video_ext),
+        format!("bestvideo{}{}", height, codec),
+        format!("bestvideo{}", height),
+        "bestvideo".to_string(),
+    ];
+    video.dedup();
+    let mut audio = vec![format!("bestaudio{}", audio_ext), "bestaudio".to_string()];
+    audio.dedup();
+    
+    format!("{},{}", video.join("/"), audio.join("/"))
+}
+
+/// Parse a timestamp like "HH:MM:SS", "MM:SS" or "SS" (fractional seconds allowed) into seconds
+pub fn parse_timestamp(value: &str) -> Option<f64> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    
+    let mut seconds = 0.0;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return None;
+        }
+        let num: f64 = part.parse().ok()?;
+        // Only the last component may carry a fraction; minutes/seconds must stay below 60
+        let is_last = i == parts.len() - 1;
+        if !is_last && part.contains('.') {
+            return None;
+        }
+        if i > 0 && num >= 60.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + num;
+    }
+    Some(seconds)
+}
+
+/// Validate a yt-dlp rate limit like "500K", "2M" or "1.5G"
+pub fn validate_rate_limit(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    normalize_size(value)
+        .ok_or_else(|| format!("Invalid rate limit '{}'. Use a number followed by K, M or G (e.g. 500K, 2M)", value))
+}
+
+/// Validate a yt-dlp max file size like "100M" or "1.5G"
+pub fn validate_max_filesize(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    normalize_size(value)
+        .ok_or_else(|| format!("Invalid max file size '{}'. Use a number followed by K, M or G (e.g. 100M, 2G)", value))
+}
+
+/// Normalize "<number><K|M|G>" with an uppercase unit; None if malformed.
+/// The number is plain digits with an optional decimal part, as yt-dlp parses it;
+/// Rust's float syntax ("1e3", "+2", "inf") is rejected
+fn normalize_size(value: &str) -> Option<String> {
+    let unit = value.chars().last()?.to_ascii_uppercase();
+    if !matches!(unit, 'K' | 'M' | 'G') {
+        return None;
+    }
+    
+    let number = &value[..value.len() - 1];
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let well_formed = match number.split_once('.') {
+        Some((whole, fraction)) => is_digits(whole) && is_digits(fraction),
+        None => is_digits(number),
+    };
+    if !well_formed {
+        return None;
+    }
+    
+    match number.parse::<f64>() {
+        Ok(n) if n > 0.0 && n.is_finite() => Some(format!("{}{}", number, unit)),
+        _ => None,
+    }
+}
+
+/// Bias a format selector toward streams under `max_filesize`: every alternative is
+/// tried first with a size filter (unknown sizes allowed), then the original selector.
+/// Merged formats are filtered per stream, so the combined file can still exceed the
+/// cap; yt-dlp's --max-filesize catches that case. Comma-separated selectors (separate
+/// downloads) are limited one by one.
+pub fn apply_filesize_limit(format_string: &str, max_filesize: &str) -> String {
+    if format_string.contains(',') {
+        return format_string
+            .split(',')
+            .map(|selector| apply_filesize_limit(selector, max_filesize))
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+    let size_filter = format!("[filesize<?{0}][filesize_approx<?{0}]", max_filesize);
+    let limited: Vec<String> = format_string
+        .split('/')
+        .map(|alternative| {
+            alternative
+                .split('+')
+                .map(|stream| format!("{}{}", stream, size_filter))
+                .collect::<Vec<_>>()
+                .join("+")
+        })
+        .collect();
+    format!("{}/{}", limited.join("/"), format_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_formats() {
+        assert_eq!(build_format_string(Quality::Best, DownloadFormat::Mp3, "auto"), "bestaudio/best");
+        assert_eq!(build_format_string(Quality::Best, DownloadFormat::M4a, "auto"), "bestaudio[ext=m4a]/bestaudio/best");
+        assert_eq!(build_format_string(Quality::Best, DownloadFormat::Opus, "auto"), "bestaudio[ext=webm]/bestaudio/best");
+        // "audio" quality with a video container falls back to m4a audio
+        assert_eq!(build_format_string(Quality::Audio, DownloadFormat::Mp4, "auto"), "bestaudio[ext=m4a]/bestaudio/best");
+    }
+
+    #[test]
+    fn test_high_res_prefers_vp9() {
+        for (quality, height) in [(Quality::P8k, 4320), (Quality::P4k, 2160), (Quality::P2k, 1440)] {
+            let expected = format!(
+                "bestvideo[height<={h}][vcodec^=vp9]+bestaudio/bestvideo[height<={h}]+bestaudio/best[height<={h}]/best",
+                h = height
+            );
+            assert_eq!(build_format_string(quality, DownloadFormat::Mp4, "h264"), expected, "mp4 {}", quality);
+            assert_eq!(build_format_string(quality, DownloadFormat::Mkv, "auto"), expected, "mkv {}", quality);
+        }
+    }
+
+    #[test]
+    fn test_mp4_with_height_cap() {
+        for (quality, height) in [(Quality::P1080, 1080), (Quality::P720, 720), (Quality::P480, 480), (Quality::P360, 360)] {
+            assert_eq!(
+                build_format_string(quality, DownloadFormat::Mp4, "auto"),
                 format!(
-                    "bestvideo[height<={}][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
-                    h, h, h
-                )
-            }
-        } else {
-            "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best".to_string()
+                    "bestvideo[height<={h}][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<={h}]+bestaudio/best[height<={h}]/best",
+                    h = height
+                ),
+                "{}",
+                quality
+            );
         }
-    } else if let Some(h) = height {
-        if is_high_res {
-            format!(
-                "bestvideo[height<={}][vcodec^=vp9]+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
-                h, h, h
-            )
-        } else if !codec_filter.is_empty() {
-            format!(
-                "bestvideo[height<={}]{}+bestaudio/bestvideo[height<={}]+bestaudio/best[height<={}]/best",
-                h, codec_filter, h, h
-            )
-        } else {
-            format!("bestvideo[height<={}]+bestaudio/best[height<={}]/best", h, h)
+    }
+
+    #[test]
+    fn test_mp4_with_codec_filter() {
+        assert_eq!(
+            build_format_string(Quality::P1080, DownloadFormat::Mp4, "h264"),
+            "bestvideo[height<=1080][vcodec^=avc][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<=1080][vcodec^=avc]+bestaudio/bestvideo[height<=1080][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<=1080]+bestaudio/best[height<=1080]/best"
+        );
+        assert_eq!(
+            build_format_string(Quality::P720, DownloadFormat::Mp4, "av1"),
+            "bestvideo[height<=720][vcodec^=av01][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<=720][vcodec^=av01]+bestaudio/bestvideo[height<=720][ext=mp4]+bestaudio[ext=m4a]/bestvideo[height<=720]+bestaudio/best[height<=720]/best"
+        );
+    }
+
+    #[test]
+    fn test_mp4_without_height_cap() {
+        assert_eq!(
+            build_format_string(Quality::Best, DownloadFormat::Mp4, "h264"),
+            "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best"
+        );
+    }
+
+    #[test]
+    fn test_non_mp4_with_height() {
+        assert_eq!(
+            build_format_string(Quality::P720, DownloadFormat::Mkv, "auto"),
+            "bestvideo[height<=720]+bestaudio/best[height<=720]/best"
+        );
+        assert_eq!(
+            build_format_string(Quality::P480, DownloadFormat::Mkv, "vp9"),
+            "bestvideo[height<=480][vcodec^=vp9]+bestaudio/bestvideo[height<=480]+bestaudio/best[height<=480]/best"
+        );
+    }
+
+    #[test]
+    fn test_webm_prefers_native_streams() {
+        assert_eq!(
+            build_format_string(Quality::P1080, DownloadFormat::Webm, "h264"),
+            "bestvideo[height<=1080][ext=webm]+bestaudio[ext=webm]/bestvideo[height<=1080]+bestaudio/best[height<=1080]/best"
+        );
+        assert_eq!(
+            build_format_string(Quality::Best, DownloadFormat::Webm, "auto"),
+            "bestvideo[ext=webm]+bestaudio[ext=webm]/bestvideo+bestaudio/best"
+        );
+    }
+
+    #[test]
+    fn test_apply_filesize_limit() {
+        assert_eq!(
+            apply_filesize_limit("bestvideo[height<=720]+bestaudio/best", "100M"),
+            "bestvideo[height<=720][filesize<?100M][filesize_approx<?100M]+bestaudio[filesize<?100M][filesize_approx<?100M]/best[filesize<?100M][filesize_approx<?100M]/bestvideo[height<=720]+bestaudio/best"
+        );
+        assert_eq!(validate_max_filesize("100m").unwrap(), "100M");
+        assert!(validate_max_filesize("100").is_err());
+        assert_eq!(validate_max_filesize("1.5g").unwrap(), "1.5G");
+        for rejected in ["1e3M", "+2M", "1_0M", "1.2.3M", ".5M", "5.M", "0M", "M"] {
+            assert!(validate_max_filesize(rejected).is_err(), "{}", rejected);
         }
-    } else {
-        "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best".to_string()
+    }
+
+    #[test]
+    fn test_validate_rate_limit() {
+        assert_eq!(validate_rate_limit(" 500k ").unwrap(), "500K");
+        assert_eq!(validate_rate_limit("2M").unwrap(), "2M");
+        assert!(validate_rate_limit("1e3K").is_err());
+        assert!(validate_rate_limit("fast").is_err());
+    }
+
+    #[test]
+    fn test_relaxed_format_strings() {
+        assert_eq!(
+            relaxed_format_strings(Quality::P1080, DownloadFormat::Mp4, "auto"),
+            vec![
+                "bestvideo[height<=1080]+bestaudio/best[height<=1080]/best".to_string(),
+                "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best".to_string(),
+            ]
+        );
+        assert_eq!(
+            relaxed_format_strings(Quality::P1080, DownloadFormat::Mp4, "h264").last().map(String::as_str),
+            Some("bestvideo[vcodec^=avc]+bestaudio/bestvideo+bestaudio/best")
+        );
+        // mkv has no ext constraints to drop
+        assert_eq!(relaxed_format_strings(Quality::P720, DownloadFormat::Mkv, "auto").len(), 1);
+        assert!(relaxed_format_strings(Quality::Best, DownloadFormat::Mkv, "auto").is_empty());
+    }
+
+    #[test]
+    fn test_separate_format_string() {
+        assert_eq!(
+            build_separate_format_string(Quality::P1080, DownloadFormat::Mp4, "h264"),
+            "bestvideo[height<=1080][vcodec^=avc][ext=mp4]/bestvideo[height<=1080][vcodec^=avc]/bestvideo[height<=1080]/bestvideo,bestaudio[ext=m4a]/bestaudio"
+        );
+        assert_eq!(build_separate_format_string(Quality::Best, DownloadFormat::Mkv, "auto"), "bestvideo,bestaudio");
+        assert_eq!(
+            apply_filesize_limit("bestvideo,bestaudio", "1G"),
+            "bestvideo[filesize<?1G][filesize_approx<?1G]/bestvideo,bestaudio[filesize<?1G][filesize_approx<?1G]/bestaudio"
+        );
+    }
+
+    #[test]
+    fn test_parse_download_format() {
+        for format in DownloadFormat::ALL {
+            assert_eq!(format.as_str().parse::<DownloadFormat>(), Ok(format));
+        }
+        assert!("avi".parse::<DownloadFormat>().is_err());
+        assert!("".parse::<DownloadFormat>().is_err());
+        assert!("MP4".parse::<DownloadFormat>().is_err());
+    }
+
+    #[test]
+    fn test_validate_thumbnail_format() {
+        assert_eq!(validate_thumbnail_format(None), Ok("jpg"));
+        assert_eq!(validate_thumbnail_format(Some(" ")), Ok("jpg"));
+        assert_eq!(validate_thumbnail_format(Some("PNG")), Ok("png"));
+        assert_eq!(validate_thumbnail_format(Some("jpeg")), Ok("jpg"));
+        assert_eq!(validate_thumbnail_format(Some("webp")), Ok("webp"));
+        assert!(validate_thumbnail_format(Some("gif")).is_err());
+    }
+
+    #[test]
+    fn test_parse_quality() {
+        for quality in Quality::ALL {
+            assert_eq!(quality.as_str().parse::<Quality>(), Ok(quality));
+        }
+        assert_eq!("1080".parse::<Quality>().unwrap().height(), Some(1080));
+        assert!("1080p".parse::<Quality>().is_err());
+        assert!("unknown".parse::<Quality>().is_err());
+    }
+
+    #[test]
+    fn test_fallback_default() {
+        assert_eq!(
+            build_format_string(Quality::Best, DownloadFormat::Mkv, "auto"),
+            "bestvideo[vcodec^=vp9]+bestaudio/bestvideo+bestaudio/best"
+        );
+    }
+
+    #[test]
+    fn test_human_filesize() {
+        assert_eq!(human_filesize(0), "0 B");
+        assert_eq!(human_filesize(1023), "1023 B");
+        assert_eq!(human_filesize(1024), "1.0 KiB");
+        assert_eq!(human_filesize(47_395_635), "45.2 MiB");
+        assert_eq!(human_filesize(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        // Sizes past TiB stay in TiB
+        assert_eq!(human_filesize(2048 * 1024_u64.pow(4)), "2048.0 TiB");
     }
 }
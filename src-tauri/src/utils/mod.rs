@@ -2,8 +2,10 @@ mod format;
 mod progress;
 mod extract;
 mod path;
+mod url;
 
 pub use format::*;
 pub use progress::*;
 pub use extract::*;
 pub use path::*;
+pub use url::*;
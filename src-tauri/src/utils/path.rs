@@ -27,3 +27,64 @@ pub fn sanitize_output_path(path: &str) -> Result<String, String> {
         .ok_or_else(|| "Invalid output path: contains invalid UTF-8".to_string())
         .map(|s| s.to_string())
 }
+
+/// Move a file, renaming it when both paths are on the same filesystem. Otherwise it is copied
+/// next to `to` under a temporary name and renamed into place, so `to` never holds a partial copy
+pub fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    
+    let mut partial_name = to.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    partial_name.push(".partial");
+    let partial = to.with_file_name(partial_name);
+    if let Err(e) = std::fs::copy(from, &partial).and_then(|_| std::fs::rename(&partial, to)) {
+        std::fs::remove_file(&partial).ok();
+        return Err(format!("Failed to move {} to {}: {}", from.display(), to.display(), e));
+    }
+    std::fs::remove_file(from).ok();
+    Ok(())
+}
+
+/// Move every file under `from` to the same relative location under `to`, creating folders as needed
+pub fn move_dir_contents(from: &Path, to: &Path) -> Result<(), String> {
+    let entries = std::fs::read_dir(from)
+        .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            std::fs::create_dir_all(&dest)
+                .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+            move_dir_contents(&entry.path(), &dest)?;
+        } else {
+            move_file(&entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_dir_contents() {
+        let root = std::env::temp_dir().join(format!("youwee_move_{}", uuid::Uuid::new_v4()));
+        let from = root.join("from");
+        let to = root.join("to");
+        std::fs::create_dir_all(from.join("Playlist")).unwrap();
+        std::fs::create_dir_all(to.join("Playlist")).unwrap();
+        std::fs::write(from.join("video.mp4"), "video").unwrap();
+        std::fs::write(from.join("Playlist").join("1 - item.mp4"), "item").unwrap();
+        std::fs::write(to.join("Playlist").join("existing.mp4"), "old").unwrap();
+        
+        move_dir_contents(&from, &to).unwrap();
+        
+        assert_eq!(std::fs::read_to_string(to.join("video.mp4")).unwrap(), "video");
+        assert_eq!(std::fs::read_to_string(to.join("Playlist").join("1 - item.mp4")).unwrap(), "item");
+        assert!(to.join("Playlist").join("existing.mp4").exists());
+        assert!(!from.join("video.mp4").exists());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
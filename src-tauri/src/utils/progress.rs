@@ -1,27 +1,164 @@
+use std::sync::LazyLock;
+use regex::Regex;
+
+/// "Downloading item N of M" playlist marker
+pub static PLAYLIST_ITEM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Downloading item (\d+) of (\d+)").unwrap());
+/// Total size of the stream being downloaded, e.g. "of 50.00MiB"
+pub static FILESIZE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"of\s+~?\s*(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB)").unwrap());
+static PERCENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)%").unwrap());
+static SPEED_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bat\s+(\S+)").unwrap());
+static ETA_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bETA\s+(\S+)").unwrap());
+/// Live recording progress has no total, e.g. "[download]   10.52MiB at  524.92KiB/s (00:00:20)"
+static LIVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[download\]\s+(\d+(?:\.\d+)?)\s*(GiB|MiB|KiB|B)\s+at\s+(\S+)").unwrap()
+});
+
 /// Parse yt-dlp progress output
 /// Returns (percent, speed, eta, playlist_index, playlist_count)
+/// Speed and ETA are reported by yt-dlp, so they already reflect any --limit-rate throttle
 pub fn parse_progress(line: &str) -> Option<(f64, String, String, Option<u32>, Option<u32>)> {
     let mut playlist_index: Option<u32> = None;
     let mut playlist_count: Option<u32> = None;
     
     // Check for playlist progress
     if line.contains("Downloading item") {
-        let re = regex::Regex::new(r"Downloading item (\d+) of (\d+)").ok()?;
-        if let Some(caps) = re.captures(line) {
+        if let Some(caps) = PLAYLIST_ITEM_RE.captures(line) {
             playlist_index = caps.get(1).and_then(|m| m.as_str().parse().ok());
             playlist_count = caps.get(2).and_then(|m| m.as_str().parse().ok());
         }
     }
     
-    if line.contains("[download]") && line.contains("%") {
-        let re = regex::Regex::new(r"(\d+\.?\d*)%.*?(?:at\s+(\S+))?.*?(?:ETA\s+(\S+))?").ok()?;
-        if let Some(caps) = re.captures(line) {
-            let percent: f64 = caps.get(1)?.as_str().parse().ok()?;
-            let speed = caps.get(2).map(|m| m.as_str().to_string()).unwrap_or_default();
-            let eta = caps.get(3).map(|m| m.as_str().to_string()).unwrap_or_default();
-            return Some((percent, speed, eta, playlist_index, playlist_count));
-        }
+    if line.contains("[download]") && line.contains('%') {
+        // Match each field separately so optional parts can't swallow each other
+        let percent: f64 = PERCENT_RE.captures(line)?.get(1)?.as_str().parse().ok()?;
+        let speed = SPEED_RE.captures(line)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let eta = ETA_RE.captures(line)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        return Some((percent, speed, eta, playlist_index, playlist_count));
     }
     
     None
 }
+
+/// Parse the stream size from a progress line, in bytes
+pub fn parse_stream_size(line: &str) -> Option<u64> {
+    let caps = FILESIZE_RE.captures(line)?;
+    let size: f64 = caps.get(1)?.as_str().parse().ok()?;
+    let bytes = match caps.get(2)?.as_str() {
+        "GiB" => size * 1024.0 * 1024.0 * 1024.0,
+        "MiB" => size * 1024.0 * 1024.0,
+        "KiB" => size * 1024.0,
+        _ => size,
+    };
+    Some(bytes as u64)
+}
+
+/// Parse a live recording progress line, which reports bytes so far instead of a percent
+/// Returns (downloaded_bytes, speed)
+pub fn parse_live_progress(line: &str) -> Option<(u64, String)> {
+    let line = line.trim();
+    if line.contains('%') {
+        return None;
+    }
+    let caps = LIVE_RE.captures(line)?;
+    let size: f64 = caps.get(1)?.as_str().parse().ok()?;
+    let bytes = match caps.get(2)?.as_str() {
+        "GiB" => size * 1024.0 * 1024.0 * 1024.0,
+        "MiB" => size * 1024.0 * 1024.0,
+        "KiB" => size * 1024.0,
+        _ => size,
+    };
+    Some((bytes as u64, caps.get(3)?.as_str().to_string()))
+}
+
+/// Detect yt-dlp post-processing steps (merge, audio extraction, conversion)
+/// Returns the phase name reported to the frontend
+pub fn detect_postprocess_phase(line: &str) -> Option<&'static str> {
+    let line = line.trim_start();
+    if line.starts_with("[Merger]") {
+        Some("merging")
+    } else if line.starts_with("[ExtractAudio]") {
+        Some("extracting_audio")
+    } else if line.starts_with("[VideoConvertor]") || line.starts_with("[VideoRemuxer]") {
+        Some("converting")
+    } else if line.starts_with("[EmbedThumbnail]")
+        || line.starts_with("[EmbedSubtitle]")
+        || line.starts_with("[Metadata]")
+    {
+        Some("embedding")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_standard_line() {
+        let (percent, speed, eta, _, _) =
+            parse_progress("[download]  23.4% of 50.00MiB at 1.20MiB/s ETA 00:32").unwrap();
+        assert_eq!(percent, 23.4);
+        assert_eq!(speed, "1.20MiB/s");
+        assert_eq!(eta, "00:32");
+    }
+    
+    #[test]
+    fn test_parse_fragment_line() {
+        let (percent, speed, eta, _, _) =
+            parse_progress("[download]  45.0% of ~  12.34MiB at  2.00MiB/s ETA 00:10").unwrap();
+        assert_eq!(percent, 45.0);
+        assert_eq!(speed, "2.00MiB/s");
+        assert_eq!(eta, "00:10");
+    }
+    
+    #[test]
+    fn test_parse_frag_count_line() {
+        let (percent, speed, eta, _, _) =
+            parse_progress("[download]  30.0% of ~ 100.00MiB at 5.00MiB/s ETA 00:14 (frag 3/10)").unwrap();
+        assert_eq!(percent, 30.0);
+        assert_eq!(speed, "5.00MiB/s");
+        assert_eq!(eta, "00:14");
+    }
+    
+    #[test]
+    fn test_parse_completed_line() {
+        let (percent, speed, eta, _, _) =
+            parse_progress("[download] 100% of 50.00MiB in 00:00:12 at 4.03MiB/s").unwrap();
+        assert_eq!(percent, 100.0);
+        assert_eq!(speed, "4.03MiB/s");
+        assert_eq!(eta, "");
+    }
+    
+    #[test]
+    fn test_parse_post_processing_line() {
+        assert!(parse_progress("[Merger] Merging formats into \"video.mp4\"").is_none());
+        assert!(parse_progress("[ExtractAudio] Destination: song.mp3").is_none());
+    }
+    
+    #[test]
+    fn test_parse_stream_size() {
+        assert_eq!(parse_stream_size("[download]  23.4% of 50.00MiB at 1.20MiB/s"), Some(52_428_800));
+        assert_eq!(parse_stream_size("[download]  45.0% of ~  2.00KiB at 1.00KiB/s"), Some(2048));
+        assert_eq!(parse_stream_size("[download] Destination: video.mp4"), None);
+    }
+    
+    #[test]
+    fn test_parse_live_progress() {
+        let (bytes, speed) =
+            parse_live_progress("[download]   10.00MiB at  524.92KiB/s (00:00:20)").unwrap();
+        assert_eq!(bytes, 10_485_760);
+        assert_eq!(speed, "524.92KiB/s");
+        assert!(parse_live_progress("[download]   1.50KiB at 1.00KiB/s (frag 3/?)").is_some());
+        assert!(parse_live_progress("[download]  23.4% of 50.00MiB at 1.20MiB/s ETA 00:32").is_none());
+        assert!(parse_live_progress("[download] Destination: stream.mp4").is_none());
+    }
+}
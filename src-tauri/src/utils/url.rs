@@ -0,0 +1,211 @@
+use crate::types::NormalizedUrl;
+
+/// Query params that only track where a link was shared from. `feature` and `pp` are
+/// YouTube's own and may mean something on other sites
+fn is_tracking_param(name: &str, youtube: bool) -> bool {
+    name.starts_with("utm_")
+        || matches!(name, "si" | "fbclid" | "gclid")
+        || (youtube && matches!(name, "feature" | "pp"))
+}
+
+/// YouTube video ids are 11 chars of [A-Za-z0-9_-]
+fn is_youtube_video_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_youtube_host(host: &str) -> bool {
+    matches!(
+        host,
+        "youtube.com" | "www.youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" | "www.youtube-nocookie.com"
+    )
+}
+
+/// Whether a URL points at YouTube, judged by its host rather than a substring match,
+/// so YouTube-only extractor args are never passed for other sites
+pub fn is_youtube_url(url: &str) -> bool {
+    let rest = url.trim().split_once("://").map_or(url.trim(), |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or_default().to_lowercase();
+    host == "youtu.be" || is_youtube_host(&host)
+}
+
+/// Canonicalize a pasted URL before handing it to yt-dlp.
+/// YouTube watch, youtu.be, Shorts, embed and live links become
+/// `https://www.youtube.com/watch?v=<id>` (keeping `list=` when present); other
+/// URLs only lose their fragment and tracking params, and keep their path as is.
+/// Rejects input that isn't an http(s) URL so we never spawn yt-dlp for it.
+pub fn normalize_url(url: &str) -> Result<NormalizedUrl, String> {
+    let trimmed = url.trim();
+    if trimmed.is_empty() {
+        return Err("URL is empty".to_string());
+    }
+    if trimmed.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid URL: {}", trimmed));
+    }
+
+    // Accept scheme-less links like "youtu.be/abc" or "www.youtube.com/watch?v=abc"
+    let with_scheme = if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        trimmed.to_string()
+    } else if trimmed.contains("://") {
+        return Err("Only http and https URLs are supported".to_string());
+    } else {
+        format!("https://{}", trimmed)
+    };
+
+    let (scheme, rest) = with_scheme.split_once("://").unwrap_or(("https", &with_scheme));
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let (host_and_path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = host_and_path.split_once('/').unwrap_or((host_and_path, ""));
+    let host = host.to_lowercase();
+    // Dotless hosts (localhost:8080, intranet names) are fine for the generic extractor
+    if host.is_empty() || host.starts_with(':') {
+        return Err(format!("Invalid URL: {}", trimmed));
+    }
+
+    let params: Vec<(&str, &str)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .collect();
+    let param = |name: &str| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v).filter(|v| !v.is_empty());
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let youtube = host == "youtu.be" || is_youtube_host(&host);
+
+    if youtube {
+        let (video_id, is_short) = if host == "youtu.be" {
+            (segments.first().copied(), false)
+        } else {
+            match segments.as_slice() {
+                ["watch"] => (param("v"), false),
+                ["shorts", id, ..] => (Some(*id), true),
+                ["embed", id, ..] | ["live", id, ..] | ["v", id, ..] => (Some(*id), false),
+                _ => (None, false),
+            }
+        };
+        let playlist_id = param("list").map(|id| id.to_string());
+
+        if let Some(id) = video_id {
+            if !is_youtube_video_id(id) {
+                return Err(format!("Invalid YouTube video id: {}", id));
+            }
+            let canonical = match &playlist_id {
+                Some(list) => format!("https://www.youtube.com/watch?v={}&list={}", id, list),
+                None => format!("https://www.youtube.com/watch?v={}", id),
+            };
+            return Ok(NormalizedUrl {
+                canonical,
+                video_id: Some(id.to_string()),
+                playlist_id,
+                is_short,
+            });
+        }
+
+        if segments.as_slice() == ["playlist"] {
+            if let Some(list) = &playlist_id {
+                return Ok(NormalizedUrl {
+                    canonical: format!("https://www.youtube.com/playlist?list={}", list),
+                    video_id: None,
+                    playlist_id,
+                    is_short: false,
+                });
+            }
+        }
+    }
+
+    // Anything else (channels, other sites): drop fragment and tracking params only
+    let kept: Vec<String> = params
+        .iter()
+        .filter(|(name, _)| !is_tracking_param(name, youtube))
+        .map(|(name, value)| if value.is_empty() { name.to_string() } else { format!("{}={}", name, value) })
+        .collect();
+    // Trailing slashes only carry no meaning on YouTube; other sites keep the path as given
+    let path = if youtube { path.trim_end_matches('/') } else { path };
+    let base = if path.is_empty() && (youtube || !host_and_path.contains('/')) {
+        format!("{}://{}", scheme, host)
+    } else {
+        format!("{}://{}/{}", scheme, host, path)
+    };
+    let canonical = if kept.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, kept.join("&"))
+    };
+
+    Ok(NormalizedUrl {
+        canonical,
+        video_id: None,
+        playlist_id: param("list").map(|id| id.to_string()),
+        is_short: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_youtube_variants() {
+        let expected = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s&si=abc",
+            "https://youtu.be/dQw4w9WgXcQ?si=tracking",
+            "youtu.be/dQw4w9WgXcQ",
+            "https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+        ] {
+            assert_eq!(normalize_url(url).unwrap().canonical, expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_normalize_shorts_and_playlists() {
+        let short = normalize_url("https://youtube.com/shorts/dQw4w9WgXcQ?feature=share").unwrap();
+        assert!(short.is_short);
+        assert_eq!(short.video_id.as_deref(), Some("dQw4w9WgXcQ"));
+
+        let with_list = normalize_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL123&index=2").unwrap();
+        assert_eq!(with_list.canonical, "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL123");
+        assert_eq!(with_list.playlist_id.as_deref(), Some("PL123"));
+
+        let playlist = normalize_url("https://www.youtube.com/playlist?list=PL123&si=x").unwrap();
+        assert_eq!(playlist.canonical, "https://www.youtube.com/playlist?list=PL123");
+        assert!(playlist.video_id.is_none());
+    }
+
+    #[test]
+    fn test_normalize_other_sites_and_invalid_input() {
+        assert_eq!(
+            normalize_url("https://vimeo.com/12345?utm_source=x#t=10").unwrap().canonical,
+            "https://vimeo.com/12345"
+        );
+        assert!(normalize_url("").is_err());
+        assert!(normalize_url("not a url").is_err());
+        assert!(normalize_url("ftp://example.com/file").is_err());
+        assert_eq!(
+            normalize_url("localhost:8080/videos/clip.mp4").unwrap().canonical,
+            "https://localhost:8080/videos/clip.mp4"
+        );
+        // Only YouTube's own share params and trailing slash are dropped on YouTube
+        assert_eq!(
+            normalize_url("https://example.com/watch/?feature=hd&pp=2&si=x").unwrap().canonical,
+            "https://example.com/watch/?feature=hd&pp=2"
+        );
+        assert_eq!(
+            normalize_url("https://www.youtube.com/@handle/?feature=shared").unwrap().canonical,
+            "https://www.youtube.com/@handle"
+        );
+        assert!(normalize_url("https://:8080/video").is_err());
+        assert!(normalize_url("https://youtu.be/short").is_err());
+    }
+
+    #[test]
+    fn test_is_youtube_url() {
+        assert!(is_youtube_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(is_youtube_url("https://youtu.be/dQw4w9WgXcQ"));
+        assert!(is_youtube_url("music.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!is_youtube_url("https://vimeo.com/12345?ref=youtube.com"));
+        assert!(!is_youtube_url("https://notyoutube.com/watch?v=dQw4w9WgXcQ"));
+    }
+}
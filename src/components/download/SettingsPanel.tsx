@@ -516,6 +516,12 @@ export function SettingsPanel({
                       <SelectItem value="128" className="text-xs">
                         {t('settings.standardAudio')}
                       </SelectItem>
+                      <SelectItem value="192" className="text-xs">
+                        {t('settings.highAudio')}
+                      </SelectItem>
+                      <SelectItem value="320" className="text-xs">
+                        {t('settings.maximumAudio')}
+                      </SelectItem>
                     </SelectContent>
                   </Select>
                 </div>
@@ -226,6 +226,12 @@ export function UniversalSettingsPanel({
                     <SelectItem value="128" className="text-xs">
                       {t('settings.standardAudio')}
                     </SelectItem>
+                    <SelectItem value="192" className="text-xs">
+                      {t('settings.highAudio')}
+                    </SelectItem>
+                    <SelectItem value="320" className="text-xs">
+                      {t('settings.maximumAudio')}
+                    </SelectItem>
                   </SelectContent>
                 </Select>
               </div>
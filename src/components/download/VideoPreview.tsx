@@ -163,6 +163,7 @@ export function VideoPreview({ url, onClose, onFormatSelect, className }: VideoP
           cookieBrowserProfile: cookieSettings.browserProfile || null,
           cookieFilePath: cookieSettings.filePath || null,
           proxyUrl: buildProxyUrl(proxySettings) || null,
+          descriptionMaxLen: 200,
         });
         if (!cancelled) {
           setData(result);
@@ -105,7 +105,7 @@ export const SEARCHABLE_SETTINGS: SearchableSetting[] = [
     id: 'ai-provider',
     label: 'AI Provider',
     description: 'Choose AI service provider',
-    keywords: ['provider', 'gemini', 'openai', 'ollama', 'deepseek', 'qwen', 'gpt', 'claude'],
+    keywords: ['provider', 'gemini', 'openai', 'ollama', 'deepseek', 'qwen', 'anthropic', 'groq', 'openrouter', 'gpt', 'claude'],
     section: 'ai',
   },
   {
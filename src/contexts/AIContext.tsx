@@ -115,6 +115,8 @@ const defaultConfig: AIConfig = {
   summary_style: 'concise',
   summary_language: 'auto',
   timeout_seconds: 120,
+  temperature: 0.7,
+  max_tokens: 2048,
   transcript_languages: ['en'],
   whisper_enabled: false,
   whisper_api_key: undefined,
@@ -20,13 +20,14 @@ import type {
   DownloadSettings,
   Format,
   ItemDownloadSettings,
-  PlaylistVideoEntry,
+  PlaylistEntries,
   ProxySettings,
   Quality,
   SubtitleFormat,
   SubtitleMode,
   VideoCodec,
 } from '@/lib/types';
+import { getDownloadErrorMessage } from '@/lib/utils';
 
 const STORAGE_KEY = 'youwee-settings';
 const COOKIE_STORAGE_KEY = 'youwee-cookie-settings';
@@ -277,7 +278,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
                 eta: progress.eta,
                 title: progress.title || item.title,
                 status:
-                  progress.status === 'finished'
+                  progress.status === 'finished' || progress.status === 'suspect'
                     ? 'completed'
                     : progress.status === 'error'
                       ? 'error'
@@ -285,7 +286,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
                 playlistIndex: progress.playlist_index,
                 playlistTotal: progress.playlist_count,
                 // Store completed info when finished
-                ...(progress.status === 'finished'
+                ...(progress.status === 'finished' || progress.status === 'suspect'
                   ? {
                       completedFilesize: progress.filesize,
                       completedResolution: progress.resolution,
@@ -383,7 +384,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
     async (url: string): Promise<string[]> => {
       try {
         const limit = settings.playlistLimit > 0 ? settings.playlistLimit : undefined;
-        const entries = await invoke<PlaylistVideoEntry[]>('get_playlist_entries', {
+        const { entries } = await invoke<PlaylistEntries>('get_playlist_entries', {
           url,
           limit,
           cookieMode: cookieSettings.mode,
@@ -622,6 +623,8 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
           embedThumbnail: settings.embedThumbnail,
           // No history_id for new downloads
           historyId: null,
+          // Playlist entries already carry their title; single URLs use the URL as a placeholder
+          title: item.title && item.title !== item.url ? item.title : null,
         });
 
         setItems((items) =>
@@ -630,7 +633,7 @@ export function DownloadProvider({ children }: { children: ReactNode }) {
       } catch (error) {
         setItems((items) =>
           items.map((i) =>
-            i.id === item.id ? { ...i, status: 'error', error: String(error) } : i,
+            i.id === item.id ? { ...i, status: 'error', error: getDownloadErrorMessage(error) } : i,
           ),
         );
       }
@@ -2,6 +2,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { createContext, type ReactNode, useCallback, useContext, useEffect, useState } from 'react';
 import type { DownloadProgress, HistoryEntry, HistoryFilter } from '@/lib/types';
+import { getDownloadErrorMessage } from '@/lib/utils';
 
 // Re-download task state
 interface RedownloadTask {
@@ -61,7 +62,7 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
         // Find task by downloadId
         for (const [entryId, task] of newMap.entries()) {
           if (task.downloadId === progress.id) {
-            if (progress.status === 'finished') {
+            if (progress.status === 'finished' || progress.status === 'suspect') {
               newMap.set(entryId, {
                 ...task,
                 status: 'completed',
@@ -320,7 +321,7 @@ export function HistoryProvider({ children }: { children: ReactNode }) {
             newMap.set(entry.id, {
               ...task,
               status: 'error',
-              error: error instanceof Error ? error.message : 'Download failed',
+              error: getDownloadErrorMessage(error),
             });
           }
           return newMap;
@@ -23,6 +23,7 @@ import type {
   ProxySettings,
   Quality,
 } from '@/lib/types';
+import { getDownloadErrorMessage } from '@/lib/utils';
 
 const STORAGE_KEY = 'youwee-universal-settings';
 const COOKIE_STORAGE_KEY = 'youwee-cookie-settings';
@@ -206,13 +207,13 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
                 eta: progress.eta,
                 title: progress.title || item.title,
                 status:
-                  progress.status === 'finished'
+                  progress.status === 'finished' || progress.status === 'suspect'
                     ? 'completed'
                     : progress.status === 'error'
                       ? 'error'
                       : 'downloading',
                 // Store completed info when finished
-                ...(progress.status === 'finished'
+                ...(progress.status === 'finished' || progress.status === 'suspect'
                   ? {
                       completedFilesize: progress.filesize,
                       completedResolution: progress.resolution,
@@ -409,7 +410,7 @@ export function UniversalProvider({ children }: { children: ReactNode }) {
       } catch (error) {
         setItems((items) =>
           items.map((i) =>
-            i.id === item.id ? { ...i, status: 'error', error: String(error) } : i,
+            i.id === item.id ? { ...i, status: 'error', error: getDownloadErrorMessage(error) } : i,
           ),
         );
       }
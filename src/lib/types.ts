@@ -1,7 +1,7 @@
 export type Quality = 'best' | '8k' | '4k' | '2k' | '1080' | '720' | '480' | '360' | 'audio';
 export type Format = 'mp4' | 'mkv' | 'webm' | 'mp3' | 'm4a' | 'opus';
 export type VideoCodec = 'h264' | 'vp9' | 'av1' | 'auto';
-export type AudioBitrate = 'auto' | '128';
+export type AudioBitrate = 'auto' | '128' | '192' | '320';
 export type SubtitleMode = 'off' | 'auto' | 'manual';
 export type SubtitleFormat = 'srt' | 'vtt' | 'ass';
 
@@ -89,21 +89,119 @@ export interface DownloadSettings {
   embedThumbnail: boolean; // Embed thumbnail as cover art (requires FFmpeg)
 }
 
+export type DownloadErrorCode =
+  | 'video_unavailable'
+  | 'geo_blocked'
+  | 'age_restricted'
+  | 'members_only'
+  | 'network_timeout'
+  | 'format_unavailable'
+  | 'file_too_large'
+  | 'ffmpeg_missing'
+  | 'cancelled'
+  | 'paused'
+  | 'unknown';
+
+// Structured error returned by download_video
+export interface DownloadError {
+  code: DownloadErrorCode;
+  message: string;
+}
+
 export interface DownloadProgress {
   id: string;
   percent: number;
   speed: string;
   eta: string;
+  // 'finished', 'paused', or 'suspect' when the file is much smaller than yt-dlp expected (possibly truncated)
   status: string;
+  // 'downloading' | 'recording' | 'merging' | 'extracting_audio' | 'converting' | 'embedding' | 'skipped' | 'paused' | 'finished'
+  phase?: string;
   title?: string;
+  // Position in download order, not in the playlist, when the order is reversed or shuffled
   playlist_index?: number;
   playlist_count?: number;
+  // Completion of the whole playlist (0-100), for a single progress bar
+  overall_percent?: number;
   // Additional info for completed downloads
   filesize?: number;
   resolution?: string;
   format_ext?: string;
+  // Live recordings report bytes so far (filesize) but no percent
+  indeterminate: boolean;
+}
+
+// Returned by download_video
+export interface DownloadResult {
+  // Notes for the user, one per line (e.g. missing subtitle languages)
+  note?: string;
+  // Every file written; video then audio when keepSeparate is set
+  files: string[];
+  // .info.json and .description files written next to the media
+  sidecar_files: string[];
 }
 
+// A download that ended in an error (get_failed_downloads); retry with retry_failed_download
+export interface FailedDownload {
+  id: string;
+  url: string;
+  title?: string;
+  error_code: DownloadErrorCode;
+  error_message: string;
+  failed_at: string; // RFC 3339
+}
+
+// Socket timeout and retries applied to every yt-dlp run (get/save_network_config)
+export interface NetworkConfig {
+  socket_timeout: number;
+  retries: number;
+  fragment_retries: number;
+}
+
+// Named set of download options (save_profile / list_profiles / download_with_profile).
+// Unset options use download_video's defaults
+export interface DownloadProfile {
+  name: string;
+  quality: string;
+  format: string;
+  download_playlist?: boolean;
+  video_codec?: string;
+  audio_bitrate?: string;
+  subtitle_mode?: string;
+  subtitle_langs?: string;
+  subtitle_embed?: boolean;
+  subtitle_format?: string;
+  embed_metadata?: boolean;
+  embed_thumbnail?: boolean;
+  embed_chapters?: boolean;
+  thumbnail_format?: string;
+  sponsorblock?: { mode: 'off' } | { mode: 'mark' | 'remove'; categories: string[] };
+  rate_limit?: string;
+  filename_template?: string;
+  restrict_filenames?: boolean;
+  windows_safe?: boolean;
+  max_filename_len?: number;
+  max_filesize?: string;
+  concurrent_fragments?: number;
+  keep_separate?: boolean;
+  create_playlist_folder?: boolean;
+  write_info_json?: boolean;
+  write_description?: boolean;
+  skip_downloaded?: boolean;
+  use_temp_dir?: boolean;
+}
+
+// One comment from get_video_comments; sites often omit author, likes or timestamp
+export interface VideoComment {
+  author?: string;
+  text: string;
+  like_count?: number;
+  timestamp?: number;
+}
+
+// Playlist download order for download_video
+export type PlaylistOrder = 'original' | 'reverse' | 'shuffle';
+
 export interface VideoInfo {
   id: string;
   title: string;
@@ -112,11 +210,21 @@ export interface VideoInfo {
   channel: string;
   upload_date: string;
   view_count: number;
+  // Not every platform reports these
+  like_count?: number;
+  channel_follower_count?: number;
   is_playlist: boolean;
   playlist_count?: number;
   // Source detection
   extractor?: string;
   extractor_key?: string;
+  chapters?: Chapter[];
+}
+
+export interface Chapter {
+  title: string;
+  start_time: number;
+  end_time?: number;
 }
 
 export interface FormatOption {
@@ -137,6 +245,64 @@ export interface FormatOption {
 export interface VideoInfoResponse {
   info: VideoInfo;
   formats: FormatOption[];
+  // True when served from the info cache
+  cached: boolean;
+}
+
+export interface GroupedFormat extends FormatOption {
+  filesize_human?: string;
+}
+
+export interface GroupedFormats {
+  video_only: GroupedFormat[];
+  audio_only: GroupedFormat[];
+  combined: GroupedFormat[];
+}
+
+export interface HealthCheck {
+  // 'ytdlp' | 'ffmpeg' | 'network' | 'ai'
+  name: string;
+  ok: boolean;
+  detail: string;
+}
+
+// Payload of the `ytdlp-warning` event
+export interface YtdlpWarning {
+  id?: string; // Download id when the warnings came from a download
+  lines: string[];
+  outdated: boolean; // yt-dlp suggested updating itself
+}
+
+// Payload of the `summary-ready` event emitted by download_and_summarize
+export interface SummaryReady {
+  id: string; // Download id
+  history_id: string;
+  summary?: string;
+  error?: string; // Why there is no summary; the download itself succeeded
+}
+
+export interface DownloadSummaryResult {
+  download: DownloadResult;
+  summary: SummaryReady;
+}
+
+// Payload of the `ffmpeg-missing` event, emitted when a download is refused because FFmpeg is not installed
+export interface FfmpegRequired {
+  id: string;
+  step: string; // What needs FFmpeg, e.g. 'extract audio'
+}
+
+export interface FormatAvailability {
+  available: boolean;
+  // Tallest video stream offered; absent for audio-only sources
+  max_height?: number;
+  // e.g. '1080p' when the requested quality is not available
+  will_fallback_to?: string;
+}
+
+export interface AvailableResolutions {
+  heights: number[]; // Distinct video heights, lowest first
+  has_audio_only: boolean;
 }
 
 export interface PlaylistInfo {
@@ -158,6 +324,12 @@ export interface SubtitleInfo {
   isAutoGenerated: boolean;
 }
 
+// Returned by classify_url
+export type UrlKind =
+  | { kind: 'single_video' }
+  | { kind: 'playlist'; count?: number } // count is missing when the site doesn't report it
+  | { kind: 'channel' };
+
 export interface PlaylistVideoEntry {
   id: string;
   title: string;
@@ -167,6 +339,27 @@ export interface PlaylistVideoEntry {
   channel?: string;
 }
 
+// Result of get_playlist_entries
+export interface PlaylistEntries {
+  entries: PlaylistVideoEntry[];
+  truncated: boolean; // Stopped early by stop_playlist_fetch; entries holds the ones read so far
+}
+
+// Payload of the `info-progress` event emitted by fetch_playlist_streaming
+export interface PlaylistEntryProgress {
+  url: string;
+  index: number; // 1-based position among the entries found so far
+  entry: PlaylistVideoEntry;
+}
+
+// Payload of the `info-progress-done` event
+export interface PlaylistFetchDone {
+  url: string;
+  count: number;
+  error?: string;
+  truncated: boolean; // Stopped early by stop_playlist_fetch
+}
+
 // Log types
 export type LogType = 'command' | 'success' | 'error' | 'stderr' | 'info';
 
@@ -193,9 +386,33 @@ export interface HistoryEntry {
   quality?: string;
   format?: string;
   source?: string; // "youtube", "tiktok", etc.
+  channel?: string; // Channel or uploader name
   downloaded_at: string; // ISO 8601
   file_exists: boolean;
   summary?: string; // AI-generated summary
+  summary_provider?: string; // e.g. 'Gemini', 'Ollama'
+  summary_model?: string;
+  tags: string[]; // User labels, e.g. a project name
+}
+
+// Returned by get_download_stats
+export interface StatCount {
+  name: string;
+  count: number;
+}
+
+export interface DailyCount {
+  date: string; // UTC date, YYYY-MM-DD
+  count: number;
+}
+
+export interface DownloadStats {
+  total_downloads: number;
+  total_bytes: number;
+  by_format: StatCount[]; // Most used first
+  by_source: StatCount[];
+  per_day: DailyCount[]; // Last 30 days, oldest first
+  top_channel?: StatCount;
 }
 
 export type HistoryFilter =
@@ -209,7 +426,16 @@ export type HistoryFilter =
 export type HistorySort = 'recent' | 'oldest' | 'title' | 'size';
 
 // AI types
-export type AIProvider = 'gemini' | 'openai' | 'deepseek' | 'qwen' | 'ollama' | 'proxy';
+export type AIProvider =
+  | 'gemini'
+  | 'openai'
+  | 'deepseek'
+  | 'qwen'
+  | 'ollama'
+  | 'proxy'
+  | 'anthropic'
+  | 'groq'
+  | 'openrouter';
 export type SummaryStyle = 'short' | 'concise' | 'detailed';
 
 // Network Proxy types
@@ -256,15 +482,34 @@ export interface AIConfig {
   model: string;
   ollama_url?: string;
   proxy_url?: string; // Custom OpenAI-compatible API endpoint
+  base_url?: string; // Overrides the OpenAI API base (e.g. a LiteLLM proxy)
   summary_style: SummaryStyle;
   summary_language: string;
-  timeout_seconds?: number; // Timeout for AI generation (default 120s)
+  custom_prompt?: string; // Replaces the built-in summary instructions; the transcript is still appended
+  timeout_seconds?: number; // Timeout for AI requests (default 120s, not applied to Ollama)
+  temperature?: number; // Sampling temperature for summaries, 0.0–2.0 (default 0.7)
+  max_tokens?: number; // Output token limit for summaries (default 2048)
   transcript_languages?: string[]; // Languages to try for transcript extraction (order matters)
   // Whisper settings
   whisper_enabled?: boolean; // Enable Whisper as fallback transcription
   whisper_api_key?: string; // Separate OpenAI key for Whisper (used when provider !== 'openai')
 }
 
+export interface TranslationResult {
+  kind: 'translation';
+  translation: string;
+  target_language: string;
+  provider: string;
+  model: string;
+}
+
+export interface QaAnswer {
+  question: string;
+  answer: string;
+  provider: string;
+  model: string;
+}
+
 // Available languages (shared between transcript extraction and summary output)
 export const LANGUAGE_OPTIONS = [
   { code: 'en', name: 'English' },
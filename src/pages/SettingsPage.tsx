@@ -213,6 +213,9 @@ function AISettingsContent({
                     qwen: 'qwen-turbo',
                     ollama: 'llama3.2',
                     proxy: 'gpt-4o-mini',
+                    anthropic: 'claude-3-5-sonnet-latest',
+                    groq: 'llama-3.3-70b-versatile',
+                    openrouter: 'openai/gpt-4o-mini',
                   };
                   ai.updateConfig({
                     provider: v as AIProvider,
@@ -228,6 +231,9 @@ function AISettingsContent({
                   <SelectItem value="openai">OpenAI</SelectItem>
                   <SelectItem value="deepseek">DeepSeek</SelectItem>
                   <SelectItem value="qwen">Qwen</SelectItem>
+                  <SelectItem value="anthropic">Anthropic</SelectItem>
+                  <SelectItem value="groq">Groq</SelectItem>
+                  <SelectItem value="openrouter">OpenRouter</SelectItem>
                   <SelectItem value="proxy">{t('ai.proxyCustom')}</SelectItem>
                   <SelectItem value="ollama">{t('ai.ollamaLocal')}</SelectItem>
                 </SelectContent>
@@ -293,7 +299,13 @@ function AISettingsContent({
                               ? 'https://platform.deepseek.com/api_keys'
                               : ai.config.provider === 'qwen'
                                 ? 'https://dashscope.console.aliyun.com/apiKey'
-                                : '#'
+                                : ai.config.provider === 'anthropic'
+                                  ? 'https://console.anthropic.com/settings/keys'
+                                  : ai.config.provider === 'groq'
+                                    ? 'https://console.groq.com/keys'
+                                    : ai.config.provider === 'openrouter'
+                                      ? 'https://openrouter.ai/settings/keys'
+                                      : '#'
                       }
                       target="_blank"
                       rel="noopener noreferrer"
@@ -307,7 +319,13 @@ function AISettingsContent({
                             ? 'DeepSeek Platform'
                             : ai.config.provider === 'qwen'
                               ? 'Alibaba DashScope'
-                              : 'Provider'}
+                              : ai.config.provider === 'anthropic'
+                                ? 'Anthropic Console'
+                                : ai.config.provider === 'groq'
+                                  ? 'GroqCloud Console'
+                                  : ai.config.provider === 'openrouter'
+                                    ? 'OpenRouter'
+                                    : 'Provider'}
                     </a>
                   </p>
                 )}
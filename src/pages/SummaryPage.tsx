@@ -41,6 +41,8 @@ interface VideoInfo {
 
 interface SummaryResult {
   summary: string;
+  provider: string;
+  model: string;
   videoInfo: VideoInfo;
 }
 
@@ -151,17 +153,22 @@ export function SummaryPage() {
 
       // Step 3: Generate summary with local settings
       setLoadingStatus(t('summary.loading.generating'));
-      const summaryResult = await invoke<{ summary: string }>('generate_summary_with_options', {
-        transcript,
-        style: summaryStyle,
-        language: summaryLanguage,
-        title: videoInfo.title,
-      });
+      const summaryResult = await invoke<{ summary: string; provider: string; model: string }>(
+        'generate_summary_with_options',
+        {
+          transcript,
+          style: summaryStyle,
+          language: summaryLanguage,
+          title: videoInfo.title,
+        },
+      );
 
       if (isCancelledRef.current) return;
 
       setResult({
         summary: summaryResult.summary,
+        provider: summaryResult.provider,
+        model: summaryResult.model,
         videoInfo: {
           url: url.trim(),
           title: videoInfo.title,
@@ -208,7 +215,7 @@ export function SummaryPage() {
   const handleSaveToLibrary = useCallback(async () => {
     if (!result) return;
 
-    const { videoInfo, summary } = result;
+    const { videoInfo, summary, provider, model } = result;
 
     // Debug log
     console.log('Saving to library:', { videoInfo, summary: summary.substring(0, 50) });
@@ -226,6 +233,8 @@ export function SummaryPage() {
         duration: videoInfo.duration ? Math.floor(videoInfo.duration) : null,
         source: 'youtube',
         summary: summary,
+        summaryProvider: provider,
+        summaryModel: model,
       });
       setSaved(true);
     } catch (err) {